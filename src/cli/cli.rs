@@ -2,15 +2,30 @@ use std::collections::HashMap;
 use std::fs;
 use std::io::IsTerminal;
 use std::io::{self, Write};
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anstyle::{AnsiColor, Effects, Reset, Style};
-use chrono::Utc;
+use chrono::{Local, NaiveDate, Utc};
 
+use git_issue::cmd::history::IssueCommit;
+use git_issue::flow::Interval;
+use git_issue::graph::{GraphFormat, GraphNode};
+use git_issue::import::ImportFormat;
+use git_issue::inbox::inbox_mark_seen;
 use git_issue::list::IssueData;
-use git_issue::model::{Filter, NamedColor, Priority, RelationshipLink, Settings, Sorting, current_timestamp, load_settings};
-
-use crate::util::{cache_path, issue_exports_dir, open_editor};
+use git_issue::model::{
+    Filter, NamedColor, Operator, Priority, RelationshipLink, Settings, Sorting, Users, format_duration, load_settings,
+};
+use git_issue::report::GroupBy;
+use git_issue::stats::{Durations, Period};
+use git_issue::storage::{FsStorage, GitStorage, Storage};
+use git_issue::user::{UserSource, git_identity, register_hint, suggest_id};
+use git_issue::worklog::week_of;
+
+use crate::complete::{Shell, write_registration};
+use crate::manpages::write_manpages;
+use crate::util::{cache_path, open_editor};
 
 pub fn init(no_commit: bool) -> Result<(), String> {
     let result = git_issue::init(no_commit)?;
@@ -21,9 +36,53 @@ pub fn init(no_commit: bool) -> Result<(), String> {
 
     println!("Initialization done");
 
+    offer_register()?;
+
     Ok(())
 }
 
+/// Offer to register the git identity if there is no current user; only a hint if not interactive
+fn offer_register() -> Result<(), String> {
+    let current = git_issue::whoami()?.value;
+
+    if current.source != UserSource::None || (current.git_name.is_none() && current.git_email.is_none()) {
+        return Ok(());
+    }
+
+    if !io::stdin().is_terminal() {
+        if let Some(hint) = register_hint()? {
+            println!("{hint}");
+        }
+        return Ok(());
+    }
+
+    let id = suggest_id(
+        current.git_name.as_deref().unwrap_or_default(),
+        current.git_email.as_deref().unwrap_or_default(),
+    );
+
+    print!("Register git user \"{}\" as \"{id}\" in users.yaml? [y/N] ", git_identity(&current));
+    io::stdout().flush().map_err(|e| format!("Failed to flush stdout: {e}"))?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("Failed to read input: {e}"))?;
+
+    if input.trim().eq_ignore_ascii_case("y") {
+        let result = git_issue::register_user(Some(id))?;
+
+        for info in result.infos {
+            println!("{}", info);
+        }
+
+        println!("Registered user \"{}\"", result.value);
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn new(
     title: String,
     type_: Option<String>,
@@ -31,9 +90,11 @@ pub fn new(
     assignee: Option<String>,
     priority: Option<Priority>,
     due_date: Option<String>,
+    milestone: Option<String>,
+    estimate: Option<String>,
     labels: Option<Vec<String>>,
 ) -> Result<(), String> {
-    let result = git_issue::new(title, type_, reporter, assignee, priority, due_date, labels)?;
+    let result = git_issue::new(title, type_, reporter, assignee, priority, due_date, milestone, estimate, labels)?;
 
     for info in result.infos {
         println!("{}", info);
@@ -54,9 +115,13 @@ pub fn set(
     assignee: Option<String>,
     priority: Option<Priority>,
     due_date: Option<String>,
+    milestone: Option<String>,
+    estimate: Option<String>,
     labels: Option<Vec<String>>,
     labels_add: Option<Vec<String>>,
     labels_remove: Option<Vec<String>>,
+    batch: bool,
+    no_batch: bool,
 ) -> Result<(), String> {
     let using_wildcard = ids.len() == 1 && ids[0] == "*";
 
@@ -76,6 +141,9 @@ pub fn set(
         wildcard_confirmation(ids.len())?;
     }
 
+    // Bulk updates via '*' default to a single commit
+    let batch = batch || (using_wildcard && !no_batch);
+
     let result = git_issue::set(
         ids,
         state,
@@ -85,9 +153,12 @@ pub fn set(
         assignee,
         priority,
         due_date,
+        milestone,
+        estimate,
         labels,
         labels_add,
         labels_remove,
+        batch,
     )?;
 
     for info in result.infos {
@@ -115,12 +186,559 @@ pub fn link(id: u32, add: Option<Vec<RelationshipLink>>, remove: Option<Vec<Rela
     Ok(())
 }
 
+pub fn log_time(id: u32, duration: String, note: Option<String>, date: Option<String>) -> Result<(), String> {
+    let result = git_issue::log_time(id, &duration, note, date)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    println!("Logged {} on issue #{id}", result.value);
+
+    Ok(())
+}
+
+pub fn timesheet(user: String, from: Option<NaiveDate>, to: Option<NaiveDate>, rev: Option<String>) -> Result<(), String> {
+    let storage = open_storage(rev.as_deref())?;
+
+    // Default: the current week, or the week up to --to
+    let to = to.unwrap_or_else(|| week_of(Local::now().date_naive()).1);
+    let from = from.unwrap_or_else(|| week_of(to).0);
+
+    let result = git_issue::timesheet(storage.as_ref(), &user, from, to)?;
+    let timesheet = result.value;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    println!("Timesheet of {}: {from} to {to}", timesheet.user);
+
+    if timesheet.rows.is_empty() {
+        println!();
+        println!("No time logged.");
+        return Ok(());
+    }
+
+    let days: Vec<String> = timesheet.days.iter().map(|day| day.format("%a %m-%d").to_string()).collect();
+
+    let mut headers = vec!["id", "title"];
+    headers.extend(days.iter().map(String::as_str));
+    headers.push("total");
+
+    let minutes = |minutes: u32| if minutes == 0 { "-".to_string() } else { format_duration(minutes) };
+
+    let mut rows: Vec<Vec<String>> = timesheet
+        .rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.id.to_string(), row.title.clone()];
+            cells.extend(row.minutes.iter().map(|m| minutes(*m)));
+            cells.push(minutes(row.total));
+            cells
+        })
+        .collect();
+
+    let mut totals = vec![String::new(), "total".to_string()];
+    totals.extend(timesheet.totals.iter().map(|m| minutes(*m)));
+    totals.push(minutes(timesheet.total));
+    rows.push(totals);
+
+    print_table(&headers, rows);
+
+    Ok(())
+}
+
+pub fn whoami(register: bool, id: Option<String>) -> Result<(), String> {
+    if register {
+        let result = git_issue::register_user(id)?;
+
+        for info in result.infos {
+            println!("{}", info);
+        }
+
+        println!("Registered user \"{}\"", result.value);
+    }
+
+    let result = git_issue::whoami()?;
+    let current = result.value;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    match current.source {
+        UserSource::Settings => println!("{} (from settings.yaml:user)", current.id),
+        UserSource::Git(identity) => println!("{} (from {identity} of \"{}\")", current.id, git_identity(&current)),
+        UserSource::None => match register_hint()? {
+            Some(hint) => println!("No user. {}", hint.trim_start_matches("Info: ")),
+            None => println!("No user: set settings.yaml:user or the git identity (user.name, user.email)"),
+        },
+    }
+
+    Ok(())
+}
+
+pub fn watch(id: u32, user: String) -> Result<(), String> {
+    let result = git_issue::watch(id, &user)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    match result.value {
+        true => println!("Watching issue #{id}"),
+        false => println!("Already watching issue #{id}"),
+    }
+
+    Ok(())
+}
+
+pub fn unwatch(id: u32, user: String) -> Result<(), String> {
+    let result = git_issue::unwatch(id, &user)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    match result.value {
+        true => println!("Stopped watching issue #{id}"),
+        false => println!("Not watching issue #{id}"),
+    }
+
+    Ok(())
+}
+
+pub fn inbox(user: String, since: Option<String>, keep: bool) -> Result<(), String> {
+    let storage = FsStorage::discover()?;
+
+    // Default: changes since the last look of the user
+    let result = git_issue::inbox(&storage, &user, since.as_deref())?;
+    let inbox = result.value;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    match &inbox.since {
+        Some(since) => println!("Inbox of {}: changes since {}", inbox.user, short_hash(since)),
+        None => println!("Inbox of {}: all changes", inbox.user),
+    }
+
+    if inbox.items.is_empty() {
+        println!();
+        println!("Nothing new.");
+    } else {
+        let users = storage.load_users()?;
+
+        let rows = inbox
+            .items
+            .iter()
+            .map(|item| {
+                let last = item.last();
+
+                vec![
+                    item.id.to_string(),
+                    item.state.clone(),
+                    item.title.clone(),
+                    item.changes.len().to_string(),
+                    last.map_or("-".to_string(), |c| c.time.get(..10).unwrap_or(&c.time).to_string()),
+                    last.map_or("-".to_string(), |c| commit_author(&users, c)),
+                    last.map_or("-".to_string(), |c| c.subject.clone()),
+                ]
+            })
+            .collect();
+
+        print_table(&["id", "state", "title", "changes", "last change", "by", "last commit"], rows);
+    }
+
+    if !keep && since.is_none() {
+        inbox_mark_seen(&inbox.user, &inbox.tip)?;
+    }
+
+    Ok(())
+}
+
+/// Author of a commit: ID of users.yaml if the email is known, otherwise the git name
+fn commit_author(users: &Users, commit: &IssueCommit) -> String {
+    match users.find(&commit.author_email) {
+        Some(user) => user.id.clone(),
+        None => commit.author_name.clone(),
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+pub fn graph(id: Option<u32>, format: GraphFormat, filter: Option<Vec<Filter>>, rev: Option<String>) -> Result<(), String> {
+    let storage = open_storage(rev.as_deref())?;
+
+    if format != GraphFormat::Tree {
+        let result = git_issue::graph_render(storage.as_ref(), id, filter, format)?;
+
+        // Keep stdout clean for piping into dot/mermaid
+        for info in result.infos {
+            eprintln!("{}", info);
+        }
+
+        print!("{}", result.value);
+
+        return Ok(());
+    }
+
+    let Some(id) = id else {
+        return Err("Format 'tree' requires an issue ID".to_string());
+    };
+
+    if filter.is_some() {
+        return Err("Format 'tree' does not support --filter".to_string());
+    }
+
+    let result = git_issue::graph(storage.as_ref(), id)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    let mut lines = Vec::new();
+    graph_lines(&result.value, "", "", &mut lines);
+
+    for line in lines {
+        println!("{line}");
+    }
+
+    Ok(())
+}
+
+/// Render a dependency tree with box-drawing characters; repeated issues are marked with (*)
+fn graph_lines(node: &GraphNode, prefix: &str, child_prefix: &str, lines: &mut Vec<String>) {
+    let repeated = if node.repeated { " (*)" } else { "" };
+    lines.push(format!("{prefix}#{} {} [{}]{repeated}", node.id, node.title, node.state));
+
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i == node.children.len() - 1;
+        let (branch, indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+
+        graph_lines(child, &format!("{child_prefix}{branch}"), &format!("{child_prefix}{indent}"), lines);
+    }
+}
+
+pub fn export(dir: PathBuf, rev: Option<String>) -> Result<(), String> {
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::export_html(storage.as_ref(), &dir)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    println!("Exported {} issue(s) to {}", result.value, dir.display());
+
+    Ok(())
+}
+
+pub fn stats(json: bool, period: Period, rev: Option<String>) -> Result<(), String> {
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::stats(storage.as_ref(), period)?;
+    let stats = result.value;
+
+    // Keep stdout clean for piping JSON
+    for info in result.infos {
+        eprintln!("{}", info);
+    }
+
+    if json {
+        let json = serde_json::to_string_pretty(&stats).map_err(|e| format!("Failed to serialize stats: {e}"))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    println!("Issues: {} (open: {}, closed: {})", stats.total, stats.open, stats.closed);
+
+    let counts = [
+        ("state", &stats.by_state),
+        ("type", &stats.by_type),
+        ("assignee", &stats.by_assignee),
+        ("priority", &stats.by_priority),
+        ("label", &stats.by_label),
+    ];
+
+    for (field, counts) in counts {
+        let rows = counts.iter().map(|(key, count)| vec![key.clone(), count.to_string()]).collect();
+        print_table(&[field, "count"], rows);
+    }
+
+    let rows = stats
+        .over_time
+        .iter()
+        .map(|p| vec![p.period.clone(), p.open.to_string(), p.closed.to_string()])
+        .collect();
+    print_table(&[&period.to_string(), "open", "closed"], rows);
+
+    let mut rows = vec![durations_row("all", &stats.cycle_time)];
+    rows.extend(stats.lead_time.iter().map(|(type_, durations)| durations_row(type_, durations)));
+    print_table(&["created -> done", "count", "mean days", "median days"], rows);
+
+    let due = &stats.due;
+    let rows = vec![
+        vec!["overdue".to_string(), due.overdue.to_string()],
+        vec!["due within 7 days".to_string(), due.due_soon.to_string()],
+        vec!["due later".to_string(), due.due_later.to_string()],
+        vec!["no due date".to_string(), due.no_due_date.to_string()],
+    ];
+    print_table(&["open issues", "count"], rows);
+
+    Ok(())
+}
+
+pub fn flow(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    interval: Interval,
+    filter: Option<Vec<Filter>>,
+    print_csv: bool,
+    json: bool,
+    rev: Option<String>,
+) -> Result<(), String> {
+    let (settings, mut infos) = load_settings()?;
+
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::flow(storage.as_ref(), from, to, interval, filter)?;
+    let flow = result.value;
+    infos.extend(result.infos);
+
+    // Keep stdout clean for piping CSV/JSON
+    for info in infos {
+        eprintln!("{}", info);
+    }
+
+    if print_csv {
+        print!("{}", git_issue::flow::flow_csv(&flow, settings.export_csv_separator)?);
+        return Ok(());
+    }
+
+    if json {
+        let json = serde_json::to_string_pretty(&flow).map_err(|e| format!("Failed to serialize flow: {e}"))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let mut headers = vec!["date"];
+    headers.extend(flow.states.iter().map(String::as_str));
+    headers.push("remaining");
+
+    let rows = flow
+        .points
+        .iter()
+        .map(|point| {
+            let mut row = vec![point.date.clone()];
+            row.extend(
+                flow.states
+                    .iter()
+                    .map(|state| point.states.get(state).copied().unwrap_or(0).to_string()),
+            );
+            row.push(point.remaining.to_string());
+            row
+        })
+        .collect();
+    print_table(&headers, rows);
+
+    Ok(())
+}
+
+fn durations_row(name: &str, durations: &Durations) -> Vec<String> {
+    let days = |d: Option<f64>| d.map(|d| format!("{d:.1}")).unwrap_or("-".to_string());
+    vec![
+        name.to_string(),
+        durations.count.to_string(),
+        days(durations.mean_days),
+        days(durations.median_days),
+    ]
+}
+
+/// Print a table with a blank line before it, columns padded to the widest cell
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    println!();
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - cell.chars().count())))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+
+    line(headers.to_vec());
+    line(
+        widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .iter()
+            .map(String::as_str)
+            .collect(),
+    );
+
+    for row in &rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+pub fn report_changelog(since: String, until: Option<String>, group_by: GroupBy, template: Option<PathBuf>) -> Result<(), String> {
+    let storage = GitStorage::discover("HEAD")?;
+
+    let result = git_issue::changelog(&storage, &since, until.as_deref(), group_by, template.as_deref())?;
+
+    // Keep stdout clean for redirecting into a file
+    for info in result.infos {
+        eprintln!("{}", info);
+    }
+
+    print!("{}", result.value);
+
+    Ok(())
+}
+
+pub fn import(
+    format: Option<ImportFormat>,
+    csv: bool,
+    file: PathBuf,
+    mapping: Option<PathBuf>,
+    create_missing: bool,
+) -> Result<(), String> {
+    if csv {
+        let result = git_issue::import::import_csv(&file)?;
+
+        for info in result.infos {
+            println!("{}", info);
+        }
+
+        println!(
+            "Created {} and updated {} issue(s)",
+            result.value.created.len(),
+            result.value.updated.len()
+        );
+
+        return Ok(());
+    }
+
+    let Some(format) = format else {
+        return Err("Missing format: use --from <format> or --csv".to_string());
+    };
+
+    let result = git_issue::import(format, &file, mapping.as_deref(), create_missing)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    for (key, id) in &result.value.ids {
+        println!("Imported {key} as #{id}");
+    }
+
+    println!("ID map written to {}", result.value.id_map.display());
+
+    Ok(())
+}
+
+pub fn manpages(dir: PathBuf) -> Result<(), String> {
+    let written = write_manpages(&dir)?;
+
+    println!("Wrote {} man pages to {}", written.len(), dir.display());
+
+    Ok(())
+}
+
+pub fn completions(shell: Shell) -> Result<(), String> {
+    write_registration(shell, &mut io::stdout())
+}
+
+pub fn undo(count: usize) -> Result<(), String> {
+    let result = git_issue::undo(count)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    for action in result.value {
+        println!("Undone: {action}");
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn list(
     columns: Option<Vec<String>>,
     filter: Option<Vec<Filter>>,
     sort: Option<Vec<Sorting>>,
     print_csv: bool,
+    output: Option<PathBuf>,
+    no_color: bool,
+    tree: bool,
+    rev: Option<String>,
+) -> Result<(), String> {
+    let (settings, mut infos) = load_settings()?;
+
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = match tree {
+        true => git_issue::tree(storage.as_ref(), None, columns, filter, sort)?,
+        false => git_issue::list(storage.as_ref(), columns, filter, sort)?,
+    };
+
+    infos.extend(result.infos);
+
+    if print_csv {
+        // Keep stdout clean for piping the CSV
+        for info in infos {
+            eprintln!("{}", info);
+        }
+
+        let csv = git_issue::list::list_csv(&result.value, settings.export_csv_separator)?;
+
+        match output {
+            Some(path) => fs::write(&path, csv).map_err(|e| format!("Failed to write {}: {e}", path.display()))?,
+            None => print!("{csv}"),
+        }
+
+        cache_issue_ids(&result.value.issues.iter().map(|issue| issue.id).collect::<Vec<u32>>())?; // For `set` command wildcard support
+
+        return Ok(());
+    }
+
+    for info in infos {
+        println!("{}", info);
+    }
+
+    let users = storage.load_users()?;
+    let mut list = result.value;
+    git_issue::list::display_names(&mut list, &users);
+
+    print_list(&settings, &users, &list.issues, &list.columns, no_color)?;
+
+    Ok(())
+}
+
+pub fn tree(
+    id: Option<u32>,
+    columns: Option<Vec<String>>,
+    filter: Option<Vec<Filter>>,
+    sort: Option<Vec<Sorting>>,
     no_color: bool,
+    rev: Option<String>,
 ) -> Result<(), String> {
     let (settings, infos) = load_settings()?;
 
@@ -128,25 +746,33 @@ pub fn list(
         println!("{}", info);
     }
 
-    let result = git_issue::list(columns, filter, sort)?;
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::tree(storage.as_ref(), id, columns, filter, sort)?;
 
     for info in result.infos {
         println!("{}", info);
     }
 
-    print_list(&settings, &result.value.issues, &result.value.columns, print_csv, no_color)?;
+    let users = storage.load_users()?;
+    let mut list = result.value;
+    git_issue::list::display_names(&mut list, &users);
+
+    print_list(&settings, &users, &list.issues, &list.columns, no_color)?;
 
     Ok(())
 }
 
-pub fn show(id: u32) -> Result<(), String> {
+pub fn show(id: u32, rev: Option<String>) -> Result<(), String> {
     let (settings, infos) = load_settings()?;
 
     for info in infos {
         println!("{}", info);
     }
 
-    let result = git_issue::show(id)?;
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::show(storage.as_ref(), id)?;
 
     for info in result.infos {
         println!("{}", info);
@@ -181,6 +807,158 @@ pub fn edit(id: u32) -> Result<(), String> {
     Ok(())
 }
 
+pub fn milestone_new(name: String, start: Option<String>, due: Option<String>, description: Option<String>) -> Result<(), String> {
+    let result = git_issue::milestone_new(name.clone(), start, due, description)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    println!("Created milestone \"{}\"", name.trim());
+
+    Ok(())
+}
+
+pub fn milestone_close(name: String) -> Result<(), String> {
+    let result = git_issue::milestone_close(&name)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    println!("Closed milestone \"{name}\"");
+
+    Ok(())
+}
+
+pub fn milestone_list(rev: Option<String>) -> Result<(), String> {
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::milestone_list(storage.as_ref())?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    if result.value.is_empty() {
+        println!("No milestones defined: create one with `git issue milestone new <name>`");
+        return Ok(());
+    }
+
+    let rows = result
+        .value
+        .iter()
+        .map(|progress| {
+            let milestone = &progress.milestone;
+            vec![
+                milestone.name.clone(),
+                milestone.state.to_string(),
+                dash_if_empty(&milestone.start),
+                dash_if_empty(&milestone.due),
+                format!("{}/{} ({}%)", progress.done, progress.total(), progress.percent()),
+                progress.overdue.len().to_string(),
+            ]
+        })
+        .collect();
+    print_table(&["name", "state", "start", "due", "done", "overdue"], rows);
+
+    Ok(())
+}
+
+pub fn milestone_show(name: String, rev: Option<String>) -> Result<(), String> {
+    let storage = open_storage(rev.as_deref())?;
+
+    let result = git_issue::milestone_show(storage.as_ref(), &name)?;
+    let progress = result.value;
+    let milestone = &progress.milestone;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    let overdue = progress.overdue.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(", ");
+
+    println!("Milestone: {} ({})", milestone.name, milestone.state);
+    println!("Start:     {}", dash_if_empty(&milestone.start));
+    println!("Due:       {}", dash_if_empty(&milestone.due));
+    println!("Done:      {}/{} ({}%)", progress.done, progress.total(), progress.percent());
+    println!("Overdue:   {}", dash_if_empty(&overdue));
+
+    if !milestone.description.is_empty() {
+        println!();
+        println!("{}", milestone.description);
+    }
+
+    if progress.issues.is_empty() {
+        println!();
+        println!("No issues in this milestone.");
+        return Ok(());
+    }
+
+    println!();
+
+    let filter = Filter {
+        field: "milestone".to_string(),
+        operator: Operator::Eq,
+        value: milestone.name.clone(),
+    };
+
+    list(None, Some(vec![filter]), None, false, None, false, false, rev)
+}
+
+fn dash_if_empty(value: &str) -> String {
+    if value.is_empty() { "-".to_string() } else { value.to_string() }
+}
+
+pub fn diff(rev1: String, rev2: String) -> Result<(), String> {
+    let result = git_issue::diff(&GitStorage::discover(&rev1)?, &GitStorage::discover(&rev2)?)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    let diff = result.value;
+
+    if diff.created.is_empty() && diff.deleted.is_empty() && diff.changed.is_empty() {
+        println!("No issue changes between {rev1} and {rev2}");
+        return Ok(());
+    }
+
+    if !diff.created.is_empty() {
+        println!("Created:");
+        for meta in &diff.created {
+            println!("  #{} -- {}", meta.id, meta.title);
+        }
+    }
+
+    if !diff.deleted.is_empty() {
+        println!("Deleted:");
+        for meta in &diff.deleted {
+            println!("  #{} -- {}", meta.id, meta.title);
+        }
+    }
+
+    if !diff.changed.is_empty() {
+        println!("Changed:");
+        for issue in &diff.changed {
+            println!("  #{} -- {}", issue.id, issue.title);
+            for field in &issue.fields {
+                println!("    {}: {} -> {}", field.field, field.old, field.new);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Storage of the working tree or, if given, of a git revision
+fn open_storage(rev: Option<&str>) -> Result<Box<dyn Storage>, String> {
+    match rev {
+        Some(rev) => Ok(Box::new(GitStorage::discover(rev)?)),
+        None => Ok(Box::new(FsStorage::discover()?)),
+    }
+}
+
 fn read_cached_issue_ids() -> Result<Vec<u32>, String> {
     let cache_file = cache_path()?;
 
@@ -223,69 +1001,47 @@ fn wildcard_confirmation(num_of_ids: usize) -> Result<(), String> {
 /// - settings: loaded user settings
 /// - issues: list of issue data
 /// - columns: list of columns to print (None means default from config)
-/// - print_csv: whether to print as CSV
 /// - no_color: whether to disable color output
-fn print_list(settings: &Settings, issues: &Vec<IssueData>, columns: &Vec<String>, print_csv: bool, no_color: bool) -> Result<(), String> {
-    let column_widths = calculate_column_widths(issues, columns)?;
+fn print_list(settings: &Settings, users: &Users, issues: &Vec<IssueData>, columns: &Vec<String>, no_color: bool) -> Result<(), String> {
+    // Reporter/assignee are display names
+    let me = users.display_name(&settings.user);
 
-    let mut csv_content = String::new();
-    let csv_separator = settings.export_csv_separator;
+    let column_widths = calculate_column_widths(issues, columns)?;
 
     // Enable colors only for interactive terminals and when NO_COLOR is not set
     let color_enabled = std::env::var("NO_COLOR").is_err() && std::io::stdout().is_terminal() && !no_color;
 
     // Print header
     for col in columns {
-        if print_csv {
-            csv_content.push_str(&to_csv_field(col, csv_separator));
+        let width = *column_widths.get(col).unwrap_or(&22);
+        let styled = if color_enabled {
+            colorize_header(settings, col)
         } else {
-            let width = *column_widths.get(col).unwrap_or(&22);
-            let styled = if color_enabled {
-                colorize_header(settings, col)
-            } else {
-                col.to_string()
-            };
-            let padding = width.saturating_sub(col.len());
-            print!("{}{}", styled, " ".repeat(padding));
-        }
+            col.to_string()
+        };
+        let padding = width.saturating_sub(col.len());
+        print!("{}{}", styled, " ".repeat(padding));
     }
 
-    print_ln(print_csv, &mut csv_content);
+    println!();
 
-    if !print_csv {
-        print_header_separator(settings, columns, &column_widths);
-    }
+    print_header_separator(settings, columns, &column_widths);
 
     // Print rows
     for issue in issues {
         for col in columns {
-            let value = issue.data.get(col).map(String::as_str).unwrap_or("");
-
-            if print_csv {
-                csv_content.push_str(&to_csv_field(value, csv_separator));
+            let value = cell_value(issue, col, columns);
+            let width = *column_widths.get(col).unwrap_or(&22);
+            let colored_value = if color_enabled {
+                colorize_value(settings, &me, col, &value)
             } else {
-                let width = *column_widths.get(col).unwrap_or(&22);
-                let colored_value = if color_enabled {
-                    colorize_value(settings, col, value)
-                } else {
-                    value.to_string()
-                };
-                let padding = width.saturating_sub(value.len());
-                print!("{}{}", colored_value, " ".repeat(padding));
-            }
+                value.clone()
+            };
+            let padding = width.saturating_sub(value.chars().count());
+            print!("{}{}", colored_value, " ".repeat(padding));
         }
 
-        print_ln(print_csv, &mut csv_content);
-    }
-
-    if print_csv {
-        // Create exports directory
-        let export_dir = issue_exports_dir()?;
-        fs::create_dir_all(&export_dir).map_err(|e| format!("Failed to create {}: {e}", export_dir.display()))?;
-
-        // Write CSV file
-        let export_file = export_dir.join(format!("{}.csv", current_timestamp().replace(":", "-")));
-        fs::write(&export_file, csv_content).map_err(|e| format!("Failed to write {}: {e}", export_file.display()))?;
+        println!();
     }
 
     cache_issue_ids(&issues.iter().map(|issue| issue.id).collect::<Vec<u32>>())?; // For `set` command wildcard support
@@ -293,6 +1049,22 @@ fn print_list(settings: &Settings, issues: &Vec<IssueData>, columns: &Vec<String
     Ok(())
 }
 
+/// Cell content; in a tree the title (or first column) is indented by the nesting level
+fn cell_value(issue: &IssueData, col: &str, columns: &[String]) -> String {
+    let value = issue.data.get(col).map(String::as_str).unwrap_or("");
+
+    let indent_col = match columns.iter().any(|c| c == "title") {
+        true => "title",
+        false => columns.first().map(String::as_str).unwrap_or(""),
+    };
+
+    if issue.depth == 0 || col != indent_col {
+        return value.to_string();
+    }
+
+    format!("{}└ {value}", "  ".repeat(issue.depth - 1))
+}
+
 fn calculate_column_widths(issues: &Vec<IssueData>, columns: &[String]) -> Result<std::collections::HashMap<String, usize>, String> {
     let mut widths: HashMap<String, usize> = HashMap::new();
 
@@ -304,9 +1076,9 @@ fn calculate_column_widths(issues: &Vec<IssueData>, columns: &[String]) -> Resul
     // Update with max content widths
     for issue in issues {
         for col in columns {
-            let value = issue.data.get(col).map(String::as_str).unwrap_or("");
+            let value = cell_value(issue, col, columns);
             let width = widths.get(col).copied().unwrap_or(0);
-            widths.insert(col.clone(), width.max(value.len()));
+            widths.insert(col.clone(), width.max(value.chars().count()));
         }
     }
 
@@ -318,18 +1090,6 @@ fn calculate_column_widths(issues: &Vec<IssueData>, columns: &[String]) -> Resul
     Ok(widths)
 }
 
-fn print_ln(print_csv: bool, csv_content: &mut String) {
-    if print_csv {
-        csv_content.push('\n');
-    } else {
-        println!();
-    }
-}
-
-fn to_csv_field(value: &str, separator: char) -> String {
-    format!("\"{value}\"{separator}")
-}
-
 fn apply_style(text: &str, style: Style) -> String {
     format!("{style}{text}{reset}", reset = Reset)
 }
@@ -396,12 +1156,12 @@ fn colorize_type(settings: &Settings, type_: &str) -> String {
     apply_style(type_, named_color_to_style(color))
 }
 
-fn colorize_value(settings: &Settings, col: &str, value: &str) -> String {
+fn colorize_value(settings: &Settings, me: &str, col: &str, value: &str) -> String {
     match col {
         "state" => colorize_state(settings, value),
         "priority" => colorize_priority(settings, value),
         "type" => colorize_type(settings, value),
-        "assignee" | "reporter" => colorize_me(settings, value),
+        "assignee" | "reporter" => colorize_me(settings, me, value),
         "due_date" => colorize_due_date(settings, value),
         _ => value.to_string(),
     }
@@ -413,9 +1173,7 @@ fn colorize_header(settings: &Settings, header: &str) -> String {
     apply_style(header, named_color_to_style(color))
 }
 
-fn colorize_me(settings: &Settings, user: &str) -> String {
-    let me = settings.user.clone();
-
+fn colorize_me(settings: &Settings, me: &str, user: &str) -> String {
     if user != me {
         return user.to_string();
     }
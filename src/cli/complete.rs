@@ -0,0 +1,231 @@
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::io::Write;
+
+use clap::ValueEnum;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::{Bash, EnvCompleter, Fish, Powershell, Zsh};
+
+use git_issue::list::get_all_column_names;
+use git_issue::model::{Config, Meta, Users};
+use git_issue::storage::{FsStorage, Storage};
+
+/// Environment variable which switches the binary into completion mode, set by the registration scripts
+pub(crate) const COMPLETE_VAR: &str = "COMPLETE";
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// Print the registration script of a shell
+/// The script calls `git-issue` for every completion, so the values are always the current ones.
+pub(crate) fn write_registration(shell: Shell, buf: &mut dyn Write) -> Result<(), String> {
+    let completer: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+        Shell::Powershell => &Powershell,
+    };
+
+    completer
+        .write_registration(COMPLETE_VAR, "git-issue", "git-issue", "git-issue", buf)
+        .map_err(|e| format!("Failed to write completions: {e}"))?;
+
+    // git's bash completion calls `_git_issue` for `git issue ...`: complete as `git-issue ...`
+    if let Shell::Bash = shell {
+        writeln!(
+            buf,
+            r#"_git_issue() {{
+    local COMP_WORDS=("git-issue" "${{COMP_WORDS[@]:2}}")
+    local COMP_CWORD=$((COMP_CWORD - 1))
+    _clap_complete_git_issue "git-issue" "${{COMP_WORDS[COMP_CWORD]}}"
+}}"#
+        )
+        .map_err(|e| format!("Failed to write completions: {e}"))?;
+    }
+
+    Ok(())
+}
+
+// Completion candidates
+// Errors (e.g. no .gitissues in the current directory) just mean no candidates.
+
+/// Issue IDs, titles as description, newest first
+pub(crate) fn issue_ids() -> Vec<CompletionCandidate> {
+    let mut metas = metas();
+    metas.sort_by(|a, b| b.id.cmp(&a.id));
+
+    metas
+        .into_iter()
+        .enumerate()
+        .map(|(i, meta)| {
+            CompletionCandidate::new(meta.id.to_string())
+                .help(Some(meta.title.into()))
+                .display_order(Some(i))
+        })
+        .collect()
+}
+
+/// Issue IDs and '*' (IDs of the latest `list`)
+pub(crate) fn issue_ids_bulk() -> Vec<CompletionCandidate> {
+    let mut candidates = issue_ids();
+    let order = candidates.len();
+    candidates.push(
+        CompletionCandidate::new("*")
+            .help(Some("Issues of the latest `list`".into()))
+            .display_order(Some(order)),
+    );
+    candidates
+}
+
+/// config.yaml:states
+pub(crate) fn states() -> Vec<CompletionCandidate> {
+    config().map(|config| candidates(config.states)).unwrap_or_default()
+}
+
+/// config.yaml:types
+pub(crate) fn types() -> Vec<CompletionCandidate> {
+    config().map(|config| candidates(config.types)).unwrap_or_default()
+}
+
+/// users.yaml:users:id of the active users, names as description, and 'me'
+pub(crate) fn users() -> Vec<CompletionCandidate> {
+    let mut candidates = vec![CompletionCandidate::new("me").help(Some("settings.yaml:user or git identity".into()))];
+
+    if let Some(users) = load_users() {
+        candidates.extend(users.users.iter().filter(|u| u.active).map(|u| {
+            let help = (!u.name.is_empty()).then(|| u.name.clone().into());
+            CompletionCandidate::new(&u.id).help(help)
+        }));
+    }
+
+    candidates
+}
+
+/// Labels used by any issue
+pub(crate) fn labels() -> Vec<CompletionCandidate> {
+    let labels: BTreeSet<String> = metas().into_iter().flat_map(|meta| meta.labels).collect();
+    candidates(labels)
+}
+
+/// Column names of `list`: meta fields, relationships and computed columns
+pub(crate) fn columns() -> Vec<CompletionCandidate> {
+    config().map(|config| candidates(get_all_column_names(&config))).unwrap_or_default()
+}
+
+/// `--filter field{=|>|<}value`: field names, then the values of the field
+pub(crate) fn filter(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+
+    let Some(pos) = current.find(['=', '>', '<']) else {
+        return field_names(&current, &["="]);
+    };
+
+    let (field, value) = current.split_at(pos + 1);
+    let name = &field[..pos];
+
+    let values = match name {
+        "id" => issue_ids(),
+        "state" => states(),
+        "type" => types(),
+        "reporter" | "assignee" | "watchers" => users(),
+        "labels" => labels(),
+        "priority" => candidates(["P0", "P1", "P2", "P3", "P4"]),
+        _ => vec![],
+    };
+
+    // Comma-separated alternatives: complete the last one, `labels=ui,ba` -> `labels=ui,backend`
+    let (prefix, last) = value.rsplit_once(',').map_or(("", value), |(prefix, last)| (prefix, last));
+    let prefix = if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{field}{prefix},")
+    };
+
+    with_prefix(values, &prefix, last)
+}
+
+/// `--sort field=asc|desc`: field names, then the direction
+pub(crate) fn sort(current: &OsStr) -> Vec<CompletionCandidate> {
+    field_names(&current.to_string_lossy(), &["=asc", "=desc"])
+}
+
+/// `link --add/--remove relationship=ids`: relationship names of config.yaml, then issue IDs
+pub(crate) fn relationship_link(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+
+    match current.split_once('=') {
+        None => {
+            let relationships: Vec<String> = config()
+                .map(|config| config.relationships.keys().cloned().collect())
+                .unwrap_or_default();
+            with_prefix(candidates(relationships), "", &current)
+                .into_iter()
+                .map(|candidate| {
+                    let value = format!("{}=", candidate.get_value().to_string_lossy());
+                    CompletionCandidate::new(value)
+                })
+                .collect()
+        }
+        Some((relationship, ids)) => {
+            let (prefix, last) = ids.rsplit_once(',').map_or(("", ids), |(prefix, last)| (prefix, last));
+            let prefix = if prefix.is_empty() {
+                format!("{relationship}=")
+            } else {
+                format!("{relationship}={prefix},")
+            };
+
+            with_prefix(issue_ids(), &prefix, last)
+        }
+    }
+}
+
+/// Field names starting with `current`, each once per suffix
+fn field_names(current: &str, suffixes: &[&str]) -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return vec![];
+    };
+
+    get_all_column_names(&config)
+        .into_iter()
+        .filter(|name| name.starts_with(current))
+        .flat_map(|name| {
+            suffixes
+                .iter()
+                .map(move |suffix| CompletionCandidate::new(format!("{name}{suffix}")))
+        })
+        .collect()
+}
+
+/// Candidates starting with `value`, prefixed with the already completed part of the argument
+fn with_prefix(candidates: Vec<CompletionCandidate>, prefix: &str, value: &str) -> Vec<CompletionCandidate> {
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.get_value().to_string_lossy().starts_with(value))
+        .map(|candidate| candidate.add_prefix(prefix))
+        .collect()
+}
+
+fn candidates<I: IntoIterator<Item = S>, S: Into<String>>(values: I) -> Vec<CompletionCandidate> {
+    values.into_iter().map(|value| CompletionCandidate::new(value.into())).collect()
+}
+
+fn storage() -> Option<FsStorage> {
+    FsStorage::discover().ok()
+}
+
+fn config() -> Option<Config> {
+    storage()?.load_config().ok()
+}
+
+fn load_users() -> Option<Users> {
+    storage()?.load_users().ok()
+}
+
+fn metas() -> Vec<Meta> {
+    storage().and_then(|storage| storage.load_metas().ok()).unwrap_or_default()
+}
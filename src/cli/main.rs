@@ -1,14 +1,31 @@
 #![deny(warnings, clippy::unwrap_used, clippy::expect_used)]
 use std::fs;
 use std::io::ErrorKind;
+use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::engine::{ArgValueCandidates, ArgValueCompleter};
+use clap_complete::env::CompleteEnv;
 
+use chrono::NaiveDate;
+
+use git_issue::flow::Interval;
+use git_issue::graph::GraphFormat;
+use git_issue::import::ImportFormat;
 use git_issue::model::{Filter, Priority, RelationshipLink, Sorting};
+use git_issue::report::GroupBy;
+use git_issue::stats::Period;
 
 mod cli;
+mod complete;
+mod manpages;
 mod util;
 
+// Only the clap definition of the web server, for its man page
+#[allow(dead_code)]
+#[path = "../web/args.rs"]
+mod web_args;
+
 use util::cache_path;
 
 #[derive(Parser)]
@@ -35,15 +52,15 @@ enum Commands {
         title: String,
 
         /// Issue meta field: type [possible values: see config.yaml:types or '']
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::types))]
         type_: Option<String>,
 
         /// Issue meta field: reporter [possible values: see users.yaml:users:id, 'me' or '']
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::users))]
         reporter: Option<String>,
 
         /// Issue meta field: assignee [possible values: see users.yaml:users:id, 'me' or '']
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::users))]
         assignee: Option<String>,
 
         /// Issue meta field: priority
@@ -54,44 +71,96 @@ enum Commands {
         #[arg(long, alias = "due_date")]
         due_date: Option<String>,
 
+        /// Issue meta field: milestone [possible values: see `milestone list` (open milestones) or '']
+        #[arg(long)]
+        milestone: Option<String>,
+
+        /// Issue meta field: estimate [possible values: duration, e.g. 2h30m, 2h or 45m, or '']
+        #[arg(long)]
+        estimate: Option<String>,
+
         /// Issue meta field: labels
-        #[arg(long, value_delimiter = ',')]
+        #[arg(long, value_delimiter = ',', add = ArgValueCandidates::new(complete::labels))]
         labels: Option<Vec<String>>,
     },
 
     /// List all issues
     List {
         /// Columns to display
-        #[arg(long, value_delimiter = ',')]
+        #[arg(long, value_delimiter = ',', add = ArgValueCandidates::new(complete::columns))]
         columns: Option<Vec<String>>,
 
         /// Filter issues by meta fields [field{=|>|<}value]
-        #[arg(long, num_args = 1..)]
+        #[arg(long, num_args = 1.., add = ArgValueCompleter::new(complete::filter))]
         filter: Option<Vec<Filter>>,
 
         /// Sort issues by meta fields [field=asc|desc]
-        #[arg(long, num_args = 1..)]
+        #[arg(long, num_args = 1.., add = ArgValueCompleter::new(complete::sort))]
         sort: Option<Vec<Sorting>>,
 
-        /// Print output to CSV file
+        /// Print output as CSV (RFC 4180, separator: settings.yaml:export_csv_separator)
         #[arg(long, default_value_t = false)]
         csv: bool,
 
+        /// Write the CSV to a file instead of stdout
+        #[arg(long, requires = "csv")]
+        output: Option<PathBuf>,
+
+        /// Don't color the output
+        #[arg(long, default_value_t = false)]
+        no_color: bool,
+
+        /// Nest issues under their parents (relationship of kind 'hierarchy')
+        #[arg(long, default_value_t = false)]
+        tree: bool,
+
+        /// Read issues from a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// List issues nested under their parents (relationship of kind 'hierarchy')
+    Tree {
+        /// Issue ID [only this issue and its descendants]
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
+        id: Option<u32>,
+
+        /// Columns to display
+        #[arg(long, value_delimiter = ',', add = ArgValueCandidates::new(complete::columns))]
+        columns: Option<Vec<String>>,
+
+        /// Filter issues by meta fields [field{=|>|<}value]
+        #[arg(long, num_args = 1.., add = ArgValueCompleter::new(complete::filter))]
+        filter: Option<Vec<Filter>>,
+
+        /// Sort issues by meta fields [field=asc|desc], applies to siblings
+        #[arg(long, num_args = 1.., add = ArgValueCompleter::new(complete::sort))]
+        sort: Option<Vec<Sorting>>,
+
         /// Don't color the output
         #[arg(long, default_value_t = false)]
         no_color: bool,
+
+        /// Read issues from a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
     },
 
     /// Show issue details
     Show {
         /// Issue ID
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
         id: u32,
+
+        /// Read the issue from a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
     },
 
     /// Change issue meta fields
     Set {
         /// Issue IDs [single ID, comma-separated IDs, or '*' to bulk update all issues from latest `list` command]
-        #[arg(value_delimiter = ',', num_args = 1..)]
+        #[arg(value_delimiter = ',', num_args = 1.., add = ArgValueCandidates::new(complete::issue_ids_bulk))]
         ids: Vec<String>,
 
         /// Issue meta field: title
@@ -99,19 +168,19 @@ enum Commands {
         title: Option<String>,
 
         /// Issue meta field: state [possible values: see config.yaml:states]
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::states))]
         state: Option<String>,
 
         /// Issue meta field: type [possible values: see config.yaml:types or '']
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::types))]
         type_: Option<String>,
 
         /// Issue meta field: reporter [possible values: see users.yaml:users:id, 'me' or '']
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::users))]
         reporter: Option<String>,
 
         /// Issue meta field: assignee [possible values: see users.yaml:users:id, 'me' or '']
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete::users))]
         assignee: Option<String>,
 
         /// Issue meta field: priority
@@ -122,46 +191,361 @@ enum Commands {
         #[arg(long, alias = "due_date")]
         due_date: Option<String>,
 
+        /// Issue meta field: milestone [possible values: see `milestone list` (open milestones) or '']
+        #[arg(long)]
+        milestone: Option<String>,
+
+        /// Issue meta field: estimate [possible values: duration, e.g. 2h30m, 2h or 45m, or '']
+        #[arg(long)]
+        estimate: Option<String>,
+
         /// Issue meta field: labels
-        #[arg(long, value_delimiter = ',', conflicts_with_all = ["labels_add", "labels_remove"])]
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["labels_add", "labels_remove"], add = ArgValueCandidates::new(complete::labels))]
         labels: Option<Vec<String>>,
 
         /// Issue meta field: labels-add
-        #[arg(long, value_delimiter = ',', conflicts_with_all = ["labels"])]
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["labels"], add = ArgValueCandidates::new(complete::labels))]
         labels_add: Option<Vec<String>>,
 
         /// Issue meta field: labels-remove
-        #[arg(long, value_delimiter = ',', conflicts_with_all = ["labels"])]
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["labels"], add = ArgValueCandidates::new(complete::labels))]
         labels_remove: Option<Vec<String>>,
+
+        /// Create a single commit for all issues [default for '*', see config.yaml:commit_message_bulk]
+        #[arg(long, conflicts_with = "no_batch")]
+        batch: bool,
+
+        /// Create one commit per issue [default for explicit IDs]
+        #[arg(long)]
+        no_batch: bool,
     },
 
     /// Edit issue description (markdown)
     Edit {
         /// Issue ID
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
         id: u32,
     },
 
     /// Link issue to other issues via relationships
     Link {
         /// Issue ID
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
         id: u32,
 
         /// Relationship link
-        #[arg(long, num_args = 1.., required_unless_present = "remove")]
+        #[arg(long, num_args = 1.., required_unless_present = "remove", add = ArgValueCompleter::new(complete::relationship_link))]
         add: Option<Vec<RelationshipLink>>,
 
         /// Relationship link
-        #[arg(long, num_args = 1.., required_unless_present = "add")]
+        #[arg(long, num_args = 1.., required_unless_present = "add", add = ArgValueCompleter::new(complete::relationship_link))]
         remove: Option<Vec<RelationshipLink>>,
     },
+
+    /// Log time spent on an issue (author: settings.yaml:user)
+    LogTime {
+        /// Issue ID
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
+        id: u32,
+
+        /// Time spent, e.g. 2h30m, 2h or 45m
+        duration: String,
+
+        /// What was done
+        #[arg(long)]
+        note: Option<String>,
+
+        /// Day the work was done (YYYY-MM-DD) [default: today]
+        #[arg(long)]
+        date: Option<String>,
+    },
+
+    /// Show the time logged by a user per issue and day
+    Timesheet {
+        /// Author of the work log entries [possible values: see users.yaml:users:id or 'me']
+        #[arg(long, default_value = "me", add = ArgValueCandidates::new(complete::users))]
+        user: String,
+
+        /// Current week, Monday to Sunday [default, unless --from/--to is given]
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        week: bool,
+
+        /// First day (YYYY-MM-DD) [default: Monday of the week of --to]
+        #[arg(long)]
+        from: Option<NaiveDate>,
+
+        /// Last day (YYYY-MM-DD) [default: Sunday of the current week]
+        #[arg(long)]
+        to: Option<NaiveDate>,
+
+        /// Read the issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Show the current user: settings.yaml:user, otherwise the git identity matched against users.yaml
+    Whoami {
+        /// Add the git identity (user.name, user.email) to users.yaml if it's not there yet
+        #[arg(long)]
+        register: bool,
+
+        /// ID of the new user [default: derived from the git email or name]
+        #[arg(long, requires = "register")]
+        id: Option<String>,
+    },
+
+    /// Watch an issue: changes show up in the inbox (reporter and assignee watch automatically)
+    Watch {
+        /// Issue ID
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
+        id: u32,
+
+        /// Watcher [possible values: see users.yaml:users:id or 'me']
+        #[arg(long, default_value = "me", add = ArgValueCandidates::new(complete::users))]
+        user: String,
+    },
+
+    /// Stop watching an issue
+    Unwatch {
+        /// Issue ID
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
+        id: u32,
+
+        /// Watcher [possible values: see users.yaml:users:id or 'me']
+        #[arg(long, default_value = "me", add = ArgValueCandidates::new(complete::users))]
+        user: String,
+    },
+
+    /// Show the watched issues which others changed since the last look (from the git history)
+    Inbox {
+        /// Watcher [possible values: see users.yaml:users:id or 'me']
+        #[arg(long, default_value = "me", add = ArgValueCandidates::new(complete::users))]
+        user: String,
+
+        /// Show the changes since a git revision instead of the last look (doesn't mark the inbox as seen)
+        #[arg(long, value_name = "REV")]
+        since: Option<String>,
+
+        /// Don't mark the inbox as seen
+        #[arg(long)]
+        keep: bool,
+    },
+
+    /// Manage milestones / sprints (.gitissues/milestones.yaml)
+    Milestone {
+        #[command(subcommand)]
+        milestone: Milestones,
+    },
+
+    /// Compare issues between two git revisions
+    Diff {
+        /// Base revision (branch, tag, commit)
+        rev1: String,
+
+        /// Revision to compare against the base (branch, tag, commit)
+        rev2: String,
+    },
+
+    /// Show the transitive dependency tree of an issue, or render issue relationships as Graphviz/Mermaid graph
+    Graph {
+        /// Issue ID [required for format 'tree'; for 'dot'/'mermaid' limits the graph to the issue and its dependencies]
+        #[arg(add = ArgValueCandidates::new(complete::issue_ids))]
+        id: Option<u32>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = GraphFormat::Tree)]
+        format: GraphFormat,
+
+        /// Filter issues by meta fields [field{=|>|<}value], see `list`
+        #[arg(long, num_args = 1.., conflicts_with = "id", add = ArgValueCompleter::new(complete::filter))]
+        filter: Option<Vec<Filter>>,
+
+        /// Read the issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Import issues from an export file of GitHub, GitLab or Jira, or create/update issues from a CSV file of `list --csv`
+    Import {
+        /// Format of the export file
+        #[arg(long, value_enum, required_unless_present = "csv")]
+        from: Option<ImportFormat>,
+
+        /// CSV file in the layout of `list --csv`: rows with an ID update the issue, rows without ID create a new one
+        #[arg(long, conflicts_with_all = ["from", "mapping", "create_missing"])]
+        csv: bool,
+
+        /// Export file
+        file: PathBuf,
+
+        /// YAML file translating source values [sections: states, types, users; e.g. states: {In Progress: active}]
+        #[arg(long)]
+        mapping: Option<PathBuf>,
+
+        /// Add missing states/types to config.yaml and users to users.yaml
+        #[arg(long, default_value_t = false)]
+        create_missing: bool,
+    },
+
+    /// Export the issues as a static HTML site (index, issue pages, attachments) for any static host
+    Export {
+        /// Output directory of the site
+        #[arg(long, value_name = "DIR")]
+        html: PathBuf,
+
+        /// Read the issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Show statistics: counts per field, open/closed over time, cycle/lead times and due dates
+    Stats {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Granularity of open/closed over time
+        #[arg(long, value_enum, default_value_t = Period::Month)]
+        period: Period,
+
+        /// Read the issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Show the number of issues per state over time (cumulative flow) and the remaining open issues (burndown)
+    Flow {
+        /// First day (YYYY-MM-DD) [default: day of the oldest issue]
+        #[arg(long)]
+        from: Option<NaiveDate>,
+
+        /// Last day (YYYY-MM-DD) [default: today]
+        #[arg(long)]
+        to: Option<NaiveDate>,
+
+        /// Distance between the points
+        #[arg(long, value_enum, default_value_t = Interval::Day)]
+        interval: Interval,
+
+        /// Filter issues by meta fields [field{=|>|<}value]
+        #[arg(long, num_args = 1.., add = ArgValueCompleter::new(complete::filter))]
+        filter: Option<Vec<Filter>>,
+
+        /// Print as CSV (RFC 4180, separator: settings.yaml:export_csv_separator)
+        #[arg(long, conflicts_with = "json")]
+        csv: bool,
+
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Read the issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Generate reports from the git history of the issues
+    Report {
+        #[command(subcommand)]
+        report: Reports,
+    },
+
+    /// Write the man pages (commands, git-issue-web, YAML files) into DIR/man1 and DIR/man5, e.g. for `git help issue`
+    Manpages {
+        /// Man directory, e.g. ~/.local/share/man
+        dir: PathBuf,
+    },
+
+    /// Print the shell completion script (dynamic: issue IDs, states, types, users, labels, columns, ...)
+    Completions {
+        /// Target shell
+        #[arg(value_enum)]
+        shell: complete::Shell,
+    },
+
+    /// Undo the most recent changes (reverts git-issue commits, or restores from the local journal if commit_auto is disabled)
+    Undo {
+        /// Number of changes to undo
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum Milestones {
+    /// Create a new milestone
+    New {
+        /// Unique name, e.g. "Sprint 3" or "v1.2"
+        name: String,
+
+        /// Start date [possible values: YYYY-MM-DD or '']
+        #[arg(long)]
+        start: Option<String>,
+
+        /// Due date [possible values: YYYY-MM-DD or '']
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Description
+        #[arg(long)]
+        description: Option<String>,
+    },
+
+    /// List all milestones with their progress
+    List {
+        /// Read the milestones and issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Show a milestone with its progress and issues
+    Show {
+        /// Milestone name
+        name: String,
+
+        /// Read the milestone and issues of a git revision (branch, tag, commit) instead of the working tree
+        #[arg(long = "ref", value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Close a milestone: no more issues can be added
+    Close {
+        /// Milestone name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum Reports {
+    /// Markdown changelog of the issues which transitioned into a done state (config.yaml:states_done) within a range
+    Changelog {
+        /// Start of the range: git revision (exclusive) or date YYYY-MM-DD (inclusive)
+        #[arg(long)]
+        since: String,
+
+        /// End of the range: git revision or date YYYY-MM-DD (inclusive) [default: HEAD]
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Group the issues by type or by label
+        #[arg(long, value_enum, default_value_t = GroupBy::Type)]
+        group_by: GroupBy,
+
+        /// Markdown template [default: .gitissues/changelog.md]
+        #[arg(long)]
+        template: Option<PathBuf>,
+    },
 }
 
 fn main() {
+    // Completion request of a shell (see `completions`): print the candidates and exit
+    CompleteEnv::with_factory(Args::command).var(complete::COMPLETE_VAR).complete();
+
     let args = Args::parse();
 
     // Clear cache
     match &args.command {
-        Commands::List { .. } | Commands::Set { .. } => { /* keep cache for list/set */ }
+        Commands::List { .. } | Commands::Tree { .. } | Commands::Set { .. } => { /* keep cache for list/set */ }
         _ => match cache_path() {
             Ok(cache_file) => {
                 if let Err(e) = fs::remove_file(&cache_file)
@@ -184,18 +568,32 @@ fn main() {
             assignee,
             priority,
             due_date,
+            milestone,
+            estimate,
             labels,
-        } => cli::new(title, type_, reporter, assignee, priority, due_date, labels),
+        } => cli::new(title, type_, reporter, assignee, priority, due_date, milestone, estimate, labels),
 
         Commands::List {
             columns,
             filter,
             sort,
             csv,
+            output,
+            no_color,
+            tree,
+            rev,
+        } => cli::list(columns, filter, sort, csv, output, no_color, tree, rev),
+
+        Commands::Tree {
+            id,
+            columns,
+            filter,
+            sort,
             no_color,
-        } => cli::list(columns, filter, sort, csv, no_color),
+            rev,
+        } => cli::tree(id, columns, filter, sort, no_color, rev),
 
-        Commands::Show { id } => cli::show(id),
+        Commands::Show { id, rev } => cli::show(id, rev),
 
         Commands::Set {
             ids,
@@ -206,9 +604,13 @@ fn main() {
             assignee,
             priority,
             due_date,
+            milestone,
+            estimate,
             labels,
             labels_add,
             labels_remove,
+            batch,
+            no_batch,
         } => cli::set(
             ids,
             state,
@@ -218,16 +620,95 @@ fn main() {
             assignee,
             priority,
             due_date,
+            milestone,
+            estimate,
             labels,
             labels_add,
             labels_remove,
+            batch,
+            no_batch,
         ),
 
         Commands::Edit { id } => cli::edit(id),
 
         Commands::Link { id, add, remove } => cli::link(id, add, remove),
+
+        Commands::LogTime { id, duration, note, date } => cli::log_time(id, duration, note, date),
+
+        Commands::Timesheet {
+            user,
+            week: _,
+            from,
+            to,
+            rev,
+        } => cli::timesheet(user, from, to, rev),
+
+        Commands::Whoami { register, id } => cli::whoami(register, id),
+
+        Commands::Watch { id, user } => cli::watch(id, user),
+
+        Commands::Unwatch { id, user } => cli::unwatch(id, user),
+
+        Commands::Inbox { user, since, keep } => cli::inbox(user, since, keep),
+
+        Commands::Milestone { milestone } => match milestone {
+            Milestones::New {
+                name,
+                start,
+                due,
+                description,
+            } => cli::milestone_new(name, start, due, description),
+            Milestones::List { rev } => cli::milestone_list(rev),
+            Milestones::Show { name, rev } => cli::milestone_show(name, rev),
+            Milestones::Close { name } => cli::milestone_close(name),
+        },
+
+        Commands::Diff { rev1, rev2 } => cli::diff(rev1, rev2),
+
+        Commands::Graph { id, format, filter, rev } => cli::graph(id, format, filter, rev),
+
+        Commands::Import {
+            from,
+            csv,
+            file,
+            mapping,
+            create_missing,
+        } => cli::import(from, csv, file, mapping, create_missing),
+
+        Commands::Export { html, rev } => cli::export(html, rev),
+
+        Commands::Stats { json, period, rev } => cli::stats(json, period, rev),
+
+        Commands::Flow {
+            from,
+            to,
+            interval,
+            filter,
+            csv,
+            json,
+            rev,
+        } => cli::flow(from, to, interval, filter, csv, json, rev),
+
+        Commands::Report {
+            report:
+                Reports::Changelog {
+                    since,
+                    until,
+                    group_by,
+                    template,
+                },
+        } => cli::report_changelog(since, until, group_by, template),
+
+        Commands::Manpages { dir } => cli::manpages(dir),
+
+        Commands::Completions { shell } => cli::completions(shell),
+
+        Commands::Undo { count } => cli::undo(count),
     };
 
+    // Notifications still being sent by the background worker
+    git_issue::notify::wait();
+
     if let Err(e) = result {
         eprintln!("Error: {e}");
         std::process::exit(1);
@@ -0,0 +1,290 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Command, CommandFactory};
+use clap_mangen::Man;
+use roff::{Roff, bold, italic, roman};
+use schemars::{JsonSchema, schema_for};
+use serde_json::Value;
+
+use git_issue::model::{Config, Milestones, Settings, Users};
+
+use crate::web_args;
+
+/// Reference pages of the YAML files: (page name, file, schema)
+fn file_pages() -> Vec<(&'static str, &'static str, Value)> {
+    vec![
+        ("git-issue-config", ".gitissues/config.yaml", schema::<Config>()),
+        ("git-issue-settings", ".gitissues/settings.yaml", schema::<Settings>()),
+        ("git-issue-users", ".gitissues/users.yaml", schema::<Users>()),
+        ("git-issue-milestones", ".gitissues/milestones.yaml", schema::<Milestones>()),
+    ]
+}
+
+/// Write the man pages into `dir/man1` (git-issue, one per subcommand, git-issue-web) and `dir/man5` (YAML files)
+/// The pages are generated from the clap definitions and the structs of the YAML files, so they match the binary.
+/// Returns the written files.
+pub(crate) fn write_manpages(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let man1 = dir.join("man1");
+    let man5 = dir.join("man5");
+
+    for dir in [&man1, &man5] {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+
+    let mut written = Vec::new();
+
+    let mut cli = crate::Args::command().disable_help_subcommand(true);
+    cli.build();
+
+    write_command(&cli, true, &man1, &mut written)?;
+
+    let mut web = web_args::Args::command();
+    web.build();
+
+    let mut page = render_command(&web)?;
+    see_also(&mut page, &["git-issue(1)"]);
+    written.push(write_page(&man1, "git-issue-web.1", &page)?);
+
+    for (name, file, schema) in file_pages() {
+        let page = render_file(name, file, &schema);
+        written.push(write_page(&man5, &format!("{name}.5"), &page)?);
+    }
+
+    Ok(written)
+}
+
+/// Page of a command and, recursively, of its subcommands
+fn write_command(cmd: &Command, top: bool, dir: &Path, written: &mut Vec<PathBuf>) -> Result<(), String> {
+    let mut page = render_command(cmd)?;
+
+    if top {
+        files_section(&mut page);
+        let mut references = vec!["git-issue-web(1)".to_string()];
+        references.extend(file_pages().iter().map(|(name, _, _)| format!("{name}(5)")));
+        see_also(&mut page, &references.iter().map(String::as_str).collect::<Vec<_>>());
+    } else {
+        see_also(&mut page, &["git-issue(1)"]);
+    }
+
+    let man = Man::new(cmd.clone());
+    written.push(write_page(dir, &man.get_filename(), &page)?);
+
+    for sub in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        write_command(sub, false, dir, written)?;
+    }
+
+    Ok(())
+}
+
+fn render_command(cmd: &Command) -> Result<String, String> {
+    // clap_mangen prefixes the version with 'v' itself
+    let mut cmd = cmd.clone();
+    if cmd.get_version().is_some() {
+        cmd = cmd.version(env!("CARGO_PKG_VERSION"));
+    }
+
+    let mut buf = Vec::new();
+    Man::new(cmd.clone())
+        .source(source())
+        .render(&mut buf)
+        .map_err(|e| format!("Failed to render man page of {}: {e}", cmd.get_name()))?;
+
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn write_page(dir: &Path, name: &str, page: &str) -> Result<PathBuf, String> {
+    let path = dir.join(name);
+    fs::write(&path, page).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// FILES section of git-issue(1)
+fn files_section(page: &mut String) {
+    let mut roff = Roff::new();
+    roff.control("SH", ["FILES"]);
+
+    for (name, file, _) in file_pages() {
+        roff.control("TP", [] as [&str; 0]);
+        roff.text([bold(file)]);
+        roff.text([roman("See "), bold(name), roman("(5).")]);
+    }
+
+    roff.control("TP", [] as [&str; 0]);
+    roff.text([bold(".gitissues/issues/<id>/")]);
+    roff.text([roman("meta.yaml, description.md, attachments/ and worklog/ of an issue.")]);
+
+    page.push_str(&roff.to_roff());
+}
+
+fn see_also(page: &mut String, references: &[&str]) {
+    let mut roff = Roff::new();
+    roff.control("SH", ["SEE ALSO"]);
+    roff.text([roman(references.join(", "))]);
+    page.push_str(&roff.to_roff());
+}
+
+/// Footer of every page, e.g. "git-issue v0.8.0"
+fn source() -> String {
+    format!("git-issue v{}", env!("CARGO_PKG_VERSION"))
+}
+
+fn schema<T: JsonSchema>() -> Value {
+    schema_for!(T).to_value()
+}
+
+/// Reference of a YAML file: every key with type, default and description
+fn render_file(name: &str, file: &str, schema: &Value) -> String {
+    let mut roff = Roff::new();
+    roff.control("TH", [name, "5", "", &source()]);
+
+    roff.control("SH", ["NAME"]);
+    roff.text([roman(format!("{name} - {file}"))]);
+
+    roff.control("SH", ["DESCRIPTION"]);
+    for line in description(schema).lines() {
+        roff.text([roman(line)]);
+        roff.control("br", [] as [&str; 0]);
+    }
+
+    roff.control("SH", ["KEYS"]);
+    let defs = schema.get("$defs").cloned().unwrap_or(Value::Null);
+    render_properties(&mut roff, schema, &defs, "");
+
+    roff.control("SH", ["SEE ALSO"]);
+    roff.text([roman("git-issue(1)")]);
+
+    roff.render()
+}
+
+fn render_properties(roff: &mut Roff, schema: &Value, defs: &Value, prefix: &str) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    for (name, property) in properties {
+        let key = format!("{prefix}{name}");
+        let target = resolve(property, defs);
+
+        let mut head = vec![bold(&key), roman(format!(" ({})", type_name(target, defs)))];
+
+        if let Some(default) = property.get("default") {
+            head.push(roman(format!(" [default: {}]", yaml(default))));
+        } else if !required.contains(&name.as_str()) {
+            head.push(italic(" optional"));
+        }
+
+        roff.control("TP", [] as [&str; 0]);
+        roff.text(head);
+
+        // The description of the field, otherwise the one of its type
+        let text = match description(property) {
+            "" => description(target),
+            text => text,
+        };
+
+        for line in text.lines() {
+            roff.text([roman(line)]);
+            roff.control("br", [] as [&str; 0]);
+        }
+
+        for (value, text) in variants(target) {
+            roff.text([
+                roman("- "),
+                bold(value),
+                roman(if text.is_empty() { String::new() } else { format!(": {text}") }),
+            ]);
+            roff.control("br", [] as [&str; 0]);
+        }
+
+        // Nested keys: objects, maps of objects (`name.<key>.field`), lists of objects (`name[].field`)
+        if target.get("properties").is_some() {
+            render_properties(roff, target, defs, &format!("{key}."));
+        } else if let Some(values) = target.get("additionalProperties").filter(|v| v.is_object()) {
+            render_properties(roff, resolve(values, defs), defs, &format!("{key}.<name>."));
+        } else if let Some(items) = target.get("items") {
+            render_properties(roff, resolve(items, defs), defs, &format!("{key}[]."));
+        }
+    }
+}
+
+/// Follow `$ref` and `Option<T>` (anyOf with null) to the schema of the type
+fn resolve<'a>(schema: &'a Value, defs: &'a Value) -> &'a Value {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/$defs/");
+        return defs.get(name).map_or(schema, |def| resolve(def, defs));
+    }
+
+    if let Some(any_of) = schema.get("anyOf").and_then(Value::as_array)
+        && let Some(inner) = any_of.iter().find(|s| s.get("type").and_then(Value::as_str) != Some("null"))
+    {
+        return resolve(inner, defs);
+    }
+
+    schema
+}
+
+fn description(schema: &Value) -> &str {
+    schema.get("description").and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Allowed values of an enum with their description
+fn variants(schema: &Value) -> Vec<(String, String)> {
+    if let Some(one_of) = schema.get("oneOf").and_then(Value::as_array) {
+        return one_of
+            .iter()
+            .filter_map(|variant| Some((yaml(variant.get("const")?), description(variant).to_string())))
+            .collect();
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values.iter().map(|value| (yaml(value), String::new())).collect();
+    }
+
+    vec![]
+}
+
+fn type_name(schema: &Value, defs: &Value) -> String {
+    if schema.get("oneOf").is_some() || schema.get("enum").is_some() {
+        return "enum".to_string();
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+
+    let nullable = types.contains(&"null");
+
+    let name = match types.iter().find(|t| **t != "null").copied() {
+        Some("array") => match schema.get("items") {
+            Some(items) => format!("list of {}s", type_name(resolve(items, defs), defs)),
+            None => "list".to_string(),
+        },
+        Some("object") => match schema.get("additionalProperties").filter(|v| v.is_object()) {
+            Some(values) => format!("map of {}s", type_name(resolve(values, defs), defs)),
+            None => "object".to_string(),
+        },
+        Some(t) => t.to_string(),
+        None => "value".to_string(),
+    };
+
+    if nullable { format!("{name} or null") } else { name }
+}
+
+/// Value as written in YAML, e.g. a default
+fn yaml(value: &Value) -> String {
+    match value {
+        Value::String(s) if s.is_empty() => "''".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(values) => format!("[{}]", values.iter().map(yaml).collect::<Vec<_>>().join(", ")),
+        _ => value.to_string(),
+    }
+}
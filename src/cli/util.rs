@@ -1,16 +1,12 @@
 use std::process::Command;
 
-use git_issue::model::{gitissues_base, issue_tmp_dir};
+use git_issue::model::issue_tmp_dir;
 
 /// Returns the path to the cache.txt file.
 pub(crate) fn cache_path() -> Result<std::path::PathBuf, String> {
     Ok(issue_tmp_dir()?.join("cache.txt"))
 }
 
-pub(crate) fn issue_exports_dir() -> Result<std::path::PathBuf, String> {
-    Ok(gitissues_base()?.join("exports"))
-}
-
 pub(crate) fn open_editor(mut editor: String, path: String) -> Result<(), String> {
     if editor == "git" {
         // Read git default editor
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use crate::cmd::list::get_column_value;
+use crate::model::Meta;
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+pub struct FieldChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+pub struct IssueChange {
+    pub id: u32,
+    pub title: String,
+    pub fields: Vec<FieldChange>,
+}
+
+pub struct DiffResult {
+    pub created: Vec<Meta>,
+    pub deleted: Vec<Meta>,
+    pub changed: Vec<IssueChange>,
+}
+
+/// Compare the issues of two storages, e.g. two git revisions (no checkout needed)
+/// - old: base
+/// - new: compared against the base
+pub fn diff(old: &dyn Storage, new: &dyn Storage) -> Cmd<DiffResult> {
+    let old: BTreeMap<u32, Meta> = old.load_metas()?.into_iter().map(|m| (m.id, m)).collect();
+    let new: BTreeMap<u32, Meta> = new.load_metas()?.into_iter().map(|m| (m.id, m)).collect();
+
+    let created = new.values().filter(|m| !old.contains_key(&m.id)).cloned().collect();
+    let deleted = old.values().filter(|m| !new.contains_key(&m.id)).cloned().collect();
+
+    let mut changed = Vec::new();
+
+    for (id, meta_new) in &new {
+        let Some(meta_old) = old.get(id) else { continue };
+
+        let fields = diff_fields(meta_old, meta_new)?;
+
+        if !fields.is_empty() {
+            changed.push(IssueChange {
+                id: *id,
+                title: meta_new.title.clone(),
+                fields,
+            });
+        }
+    }
+
+    Ok(CmdResult {
+        value: DiffResult { created, deleted, changed },
+        infos: vec![],
+    })
+}
+
+/// Per-field changes between two versions of the same issue
+/// `updated` is left out on purpose: it changes with every other field
+fn diff_fields(old: &Meta, new: &Meta) -> Result<Vec<FieldChange>, String> {
+    let mut fields: Vec<String> = [
+        "title",
+        "state",
+        "type",
+        "labels",
+        "reporter",
+        "assignee",
+        "priority",
+        "due_date",
+        "milestone",
+        "estimate",
+        "watchers",
+    ]
+    .iter()
+    .map(|f| f.to_string())
+    .collect();
+
+    for relationship in old.relationships.keys().chain(new.relationships.keys()) {
+        if !fields.contains(relationship) {
+            fields.push(relationship.clone());
+        }
+    }
+
+    fields.push("created".to_string());
+
+    let mut changes = Vec::new();
+
+    for field in fields {
+        let value_old = get_column_value(&field, old)?;
+        let value_new = get_column_value(&field, new)?;
+
+        if value_old != value_new {
+            changes.push(FieldChange {
+                field,
+                old: value_old,
+                new: value_new,
+            });
+        }
+    }
+
+    Ok(changes)
+}
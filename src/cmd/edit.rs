@@ -1,7 +1,12 @@
+use std::fs;
 use std::path::PathBuf;
 
-use crate::cmd::util::git_commit;
-use crate::model::{issue_desc_path, issue_title};
+use crate::cmd::util::{git_commit, load_meta};
+use crate::hooks::{self, Event, EventKind};
+use crate::journal;
+use crate::model::{issue_desc_path, issue_dir, issue_meta_path, issue_title, issue_tmp_dir, load_config};
+use crate::trailers::Trailers;
+use crate::transaction::Change;
 use crate::{Cmd, CmdResult};
 
 /// Start editing the description of an issue
@@ -15,6 +20,11 @@ pub fn edit_start(id: u32) -> Cmd<PathBuf> {
         return Err("Not available: ID/description.md does not exist.".to_string());
     }
 
+    // Keep the original description for the journal (`undo` without auto-commit) and the `on_comment` hooks
+    let backup = edit_backup_path(id)?;
+    fs::create_dir_all(issue_tmp_dir()?).map_err(|e| format!("Failed to create .tmp directory: {e}"))?;
+    fs::copy(path, &backup).map_err(|e| format!("Failed to write {}: {e}", backup.display()))?;
+
     Ok(CmdResult {
         value: desc_path,
         infos: vec![],
@@ -25,10 +35,59 @@ pub fn edit_start(id: u32) -> Cmd<PathBuf> {
 pub fn edit_end(id: u32) -> Cmd<()> {
     let title = issue_title(id)?;
 
-    let result = git_commit(id, title, "edit description");
+    let backup = edit_backup_path(id)?;
+    let mut added = None;
+
+    if backup.exists() {
+        let desc_path = issue_desc_path(id)?;
+        let before = fs::read(&backup).map_err(|e| format!("Failed to read {}: {e}", backup.display()))?;
+        let after = fs::read(&desc_path).map_err(|e| format!("Failed to read {}: {e}", desc_path.display()))?;
+
+        if before != after {
+            added = Some(added_text(&String::from_utf8_lossy(&before), &String::from_utf8_lossy(&after)));
+
+            if !load_config()?.commit_auto {
+                let change = Change {
+                    path: desc_path,
+                    before: Some(before),
+                    after: Some(after),
+                };
+                journal::record_changes(&format!("edit description #{id}"), &[change], &[])?;
+            }
+        }
 
-    match result {
-        Ok(infos) => Ok(CmdResult { value: (), infos }),
-        Err(e) => Err(e),
+        fs::remove_file(&backup).map_err(|e| format!("Failed to remove {}: {e}", backup.display()))?;
     }
+
+    let mut infos = git_commit(
+        id,
+        title,
+        "edit description",
+        Trailers::new("edit", &[id]).with_fields(&["description"]),
+        &[issue_dir(id)?],
+    )?;
+
+    if let Some(text) = added {
+        let meta = load_meta(&issue_meta_path(id)?)?;
+
+        let event = Event {
+            text: Some(text),
+            ..Event::new(EventKind::Comment, &meta)
+        };
+
+        infos.extend(hooks::run(vec![event]));
+    }
+
+    Ok(CmdResult { value: (), infos })
+}
+
+/// Text of `after` following the lines it has in common with the beginning of `before`, e.g. an appended comment
+fn added_text(before: &str, after: &str) -> String {
+    let common = before.lines().zip(after.lines()).take_while(|(a, b)| a == b).count();
+
+    after.lines().skip(common).collect::<Vec<_>>().join("\n").trim().to_string()
+}
+
+fn edit_backup_path(id: u32) -> Result<PathBuf, String> {
+    Ok(issue_tmp_dir()?.join(format!("edit-{id}.md")))
 }
@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::Path;
+
+use askama::Template;
+
+use crate::cmd::list::{display_names, list};
+use crate::cmd::show::show_markdown;
+use crate::html::{Data, ListTemplate, ShowTemplate, markdown_to_html};
+use crate::model::load_settings_or_default;
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+const FAVICON: &[u8] = include_bytes!("../web/favicon.ico");
+
+/// Export all issues as a static HTML site into `dir`, e.g. to publish it from CI
+/// - index.html: issue list with the default columns of config.yaml:list_columns
+/// - issues/ID/index.html: issue page, markdown rendered to HTML
+/// - issues/ID/attachments/: attachments of the issue
+///
+/// All links are relative, the site works from any static host and from the file system.
+/// Returns the number of exported issues
+pub fn export_html(storage: &dyn Storage, dir: &Path) -> Cmd<usize> {
+    let mut result = list(storage, None, None, None)?;
+    let mut infos = result.infos;
+
+    let users = storage.load_users()?;
+    display_names(&mut result.value, &users);
+
+    let columns = result.value.columns;
+
+    let (settings, settings_infos) = load_settings_or_default()?;
+    infos.extend(settings_infos);
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+
+    // Index
+    let mut ids: Vec<u32> = Vec::new();
+    let mut rows: Vec<Vec<Data>> = Vec::new();
+
+    for issue in &result.value.issues {
+        ids.push(issue.id);
+        rows.push(
+            columns
+                .iter()
+                .map(|col| Data {
+                    key: col.clone(),
+                    value: issue.data.get(col).cloned().unwrap_or_default(),
+                })
+                .collect(),
+        );
+    }
+
+    let index = ListTemplate {
+        ids: ids.clone(),
+        rows,
+        user: users.display_name(&settings.user),
+        columns,
+        filters: vec![],
+        site: true,
+        inbox: false,
+    };
+    let index = index.render().map_err(|e| format!("Failed to render index.html: {e}"))?;
+    write_file(&dir.join("index.html"), index.as_bytes())?;
+    write_file(&dir.join("favicon.ico"), FAVICON)?;
+
+    // Issue pages, all issues even if the default list is filtered
+    let all_ids = storage.issue_ids()?;
+
+    for &id in &all_ids {
+        let issue_dir = dir.join("issues").join(id.to_string());
+
+        let content = show_markdown(storage, id)?.value;
+        let html = markdown_to_html(&content, "", &|other| format!("../{other}/index.html"))?;
+
+        let page = ShowTemplate { id, html, site: true };
+        let page = page.render().map_err(|e| format!("Failed to render page of issue #{id}: {e}"))?;
+        write_file(&issue_dir.join("index.html"), page.as_bytes())?;
+
+        for name in storage.attachments(id)? {
+            let Some(bytes) = storage.read_attachment(id, &name)? else {
+                continue;
+            };
+
+            let dst = name.split('/').fold(issue_dir.join("attachments"), |acc, part| acc.join(part));
+            write_file(&dst, &bytes)?;
+        }
+    }
+
+    Ok(CmdResult {
+        value: all_ids.len(),
+        infos,
+    })
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+
+    fs::write(path, bytes).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
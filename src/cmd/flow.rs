@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::cmd::graph::is_done;
+use crate::cmd::history::{parse_time, state_changes};
+use crate::cmd::list::{csv_separator, list};
+use crate::model::{Filter, Meta};
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+/// State over time, None: deleted
+type Timeline = Vec<(DateTime<Utc>, Option<String>)>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Interval {
+    Day,
+    Week,
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interval::Day => write!(f, "day"),
+            Interval::Week => write!(f, "week"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FlowResult {
+    /// config.yaml:states, followed by states only found in the history
+    pub states: Vec<String>,
+    pub points: Vec<FlowPoint>,
+}
+
+#[derive(Serialize)]
+pub struct FlowPoint {
+    /// End of the day (YYYY-MM-DD)
+    pub date: String,
+    /// Number of issues per state (cumulative flow)
+    pub states: IndexMap<String, usize>,
+    /// Number of issues not in a done state (burndown)
+    pub remaining: usize,
+}
+
+/// Reconstruct the number of issues per state at each point in time (cumulative flow) and the remaining open issues (burndown)
+/// - storage: where to read the issues from, the states over time are taken from its git history of `meta.yaml`
+/// - from: first day [default: day of the oldest issue]
+/// - to: last day [default: today (UTC)]
+/// - interval: distance between the points, the last point is always `to`
+/// - filter: only issues matching the filter (current meta fields), see `list`
+///
+/// Issues with uncommitted changes (or without git history) count with their current state from `created` resp. `updated` on.
+pub fn flow(
+    storage: &dyn Storage,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    interval: Interval,
+    filter: Option<Vec<Filter>>,
+) -> Cmd<FlowResult> {
+    let config = storage.load_config()?;
+
+    let listed = list(storage, Some(vec!["id".to_string()]), filter, None)?;
+    let mut infos = listed.infos;
+    let ids: Vec<u32> = listed.value.issues.iter().map(|issue| issue.id).collect();
+
+    let metas: Vec<Meta> = storage.load_metas()?.into_iter().filter(|meta| ids.contains(&meta.id)).collect();
+
+    // State history per issue
+    let mut history: HashMap<u32, Timeline> = HashMap::new();
+
+    match storage.history() {
+        Some(git) => {
+            let tip = git.resolve_rev()?;
+
+            for change in state_changes(&git, &tip, None)? {
+                if let Some(time) = parse_time(&change.time) {
+                    history.entry(change.id).or_default().push((time, change.state));
+                }
+            }
+        }
+        None => infos.push("Info: No git history available, issues count with their current state".to_string()),
+    }
+
+    let timelines: Vec<(Option<DateTime<Utc>>, Timeline)> = metas
+        .iter()
+        .map(|meta| {
+            (
+                parse_time(&meta.created),
+                timeline(meta, history.remove(&meta.id).unwrap_or_default()),
+            )
+        })
+        .collect();
+
+    let mut states = config.states.clone();
+    for (_, timeline) in &timelines {
+        for state in timeline.iter().filter_map(|(_, state)| state.as_ref()) {
+            if !states.contains(state) {
+                states.push(state.clone());
+            }
+        }
+    }
+
+    let today = Utc::now().date_naive();
+    let first = timelines.iter().filter_map(|(created, _)| created.map(|c| c.date_naive())).min();
+    let from = from.or(first).unwrap_or(today);
+    let to = to.unwrap_or(today);
+
+    if from > to {
+        return Err(format!("Invalid range: {from} is after {to}"));
+    }
+
+    let mut points = Vec::new();
+
+    for day in days(from, to, interval) {
+        let end = (day + Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .map(|end| end.and_utc())
+            .unwrap_or_else(Utc::now);
+
+        let mut point = FlowPoint {
+            date: day.format("%Y-%m-%d").to_string(),
+            states: states.iter().map(|state| (state.clone(), 0)).collect(),
+            remaining: 0,
+        };
+
+        for (created, timeline) in &timelines {
+            if created.is_some_and(|created| created >= end) {
+                continue;
+            }
+
+            // Before its first commit an issue has the state of that commit
+            let state = timeline.iter().rev().find(|(time, _)| *time < end).or(timeline.first());
+
+            let Some((_, Some(state))) = state else {
+                continue;
+            };
+
+            *point.states.entry(state.clone()).or_default() += 1;
+
+            if !is_done(&config, state) {
+                point.remaining += 1;
+            }
+        }
+
+        points.push(point);
+    }
+
+    Ok(CmdResult {
+        value: FlowResult { states, points },
+        infos,
+    })
+}
+
+/// CSV with the columns date, one per state and remaining (RFC 4180)
+pub fn flow_csv(flow: &FlowResult, separator: char) -> Result<String, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(csv_separator(separator)?)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(vec![]);
+
+    let mut header = vec!["date".to_string()];
+    header.extend(flow.states.iter().cloned());
+    header.push("remaining".to_string());
+    writer.write_record(&header).map_err(|e| format!("Failed to write CSV: {e}"))?;
+
+    for point in &flow.points {
+        let mut record = vec![point.date.clone()];
+        record.extend(
+            flow.states
+                .iter()
+                .map(|state| point.states.get(state).copied().unwrap_or(0).to_string()),
+        );
+        record.push(point.remaining.to_string());
+        writer.write_record(&record).map_err(|e| format!("Failed to write CSV: {e}"))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| format!("Failed to write CSV: {e}"))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {e}"))
+}
+
+/// States from the history, the current state if there is none or it has uncommitted changes
+fn timeline(meta: &Meta, mut history: Timeline) -> Timeline {
+    let now = Utc::now();
+
+    if history.is_empty() {
+        history.push((parse_time(&meta.created).unwrap_or(now), Some(meta.state.clone())));
+    }
+
+    if history.last().map(|(_, state)| state.as_deref()) != Some(Some(meta.state.as_str())) {
+        history.push((parse_time(&meta.updated).unwrap_or(now), Some(meta.state.clone())));
+    }
+
+    history
+}
+
+/// Days from `from` in steps of the interval, always ending with `to`
+fn days(from: NaiveDate, to: NaiveDate, interval: Interval) -> Vec<NaiveDate> {
+    let step = match interval {
+        Interval::Day => Duration::days(1),
+        Interval::Week => Duration::days(7),
+    };
+
+    let mut days = Vec::new();
+    let mut day = from;
+
+    while day < to {
+        days.push(day);
+        day += step;
+    }
+    days.push(to);
+
+    days
+}
@@ -0,0 +1,569 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use clap::ValueEnum;
+
+use crate::cmd::list::list;
+use crate::model::{Config, Filter, Meta, RelationshipKind, Settings, load_settings_or_default};
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+/// Edges of a relationship kind: issue -> its targets
+/// - dependency: issue -> issues it is blocked by
+/// - hierarchy:  issue -> its parents
+pub(crate) type Edges = BTreeMap<u32, Vec<u32>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// Transitive dependency tree of a single issue
+    Tree,
+    /// Graphviz
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// SVG image, layered top-down
+    Svg,
+}
+
+/// Edge between two issues, reciprocal relationships are collapsed into a single edge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: u32,
+    pub to: u32,
+    pub label: String,
+    /// Symmetric relationship (e.g. related): no direction
+    pub undirected: bool,
+}
+
+pub struct GraphNode {
+    pub id: u32,
+    pub title: String,
+    pub state: String,
+    pub done: bool,
+    /// Already shown elsewhere in the tree, children are not repeated
+    pub repeated: bool,
+    pub children: Vec<GraphNode>,
+}
+
+/// Transitive dependency tree of an issue (the issues it is blocked by, recursively)
+pub fn graph(storage: &dyn Storage, id: u32) -> Cmd<GraphNode> {
+    let config = storage.load_config()?;
+    let metas = storage.load_metas()?;
+
+    let Some(root) = metas.iter().find(|m| m.id == id) else {
+        return Err("Not available: ID does not exist.".to_string());
+    };
+
+    let edges = kind_edges(&config, RelationshipKind::Dependency, &metas)?;
+    let metas: HashMap<u32, &Meta> = metas.iter().map(|m| (m.id, m)).collect();
+
+    let mut seen = HashSet::new();
+    let tree = build_node(&config, root.id, &edges, &metas, &mut seen);
+
+    Ok(CmdResult {
+        value: tree,
+        infos: vec![],
+    })
+}
+
+fn build_node(config: &Config, id: u32, edges: &Edges, metas: &HashMap<u32, &Meta>, seen: &mut HashSet<u32>) -> GraphNode {
+    let (title, state) = match metas.get(&id) {
+        Some(meta) => (meta.title.clone(), meta.state.clone()),
+        None => ("(missing)".to_string(), "-".to_string()),
+    };
+
+    let repeated = !seen.insert(id);
+
+    let children = match repeated {
+        true => vec![],
+        false => edges
+            .get(&id)
+            .map(|targets| targets.iter().map(|t| build_node(config, *t, edges, metas, seen)).collect())
+            .unwrap_or_default(),
+    };
+
+    GraphNode {
+        id,
+        done: is_done(config, &state),
+        title,
+        state,
+        repeated,
+        children,
+    }
+}
+
+/// Render a graph of issues and their relationships
+/// - id: the issue and its transitive dependencies
+/// - filter: issues matching the filters (`list` semantics)
+/// - neither: all issues
+///
+/// Nodes are colored by state via settings.yaml:list_formatting:colors:state
+pub fn graph_render(storage: &dyn Storage, id: Option<u32>, filter: Option<Vec<Filter>>, format: GraphFormat) -> Cmd<String> {
+    if format == GraphFormat::Tree {
+        return Err("Format 'tree' is not a graph rendering, use `graph <id>`".to_string());
+    }
+
+    let config = storage.load_config()?;
+    let (settings, infos) = load_settings_or_default()?;
+
+    let ids: HashSet<u32> = match id {
+        Some(id) => {
+            let mut ids = HashSet::new();
+            collect_ids(&graph(storage, id)?.value, &mut ids);
+            ids
+        }
+        None => list(storage, Some(vec!["id".to_string()]), filter, None)?
+            .value
+            .issues
+            .iter()
+            .map(|issue| issue.id)
+            .collect(),
+    };
+
+    let metas: Vec<Meta> = storage.load_metas()?.into_iter().filter(|m| ids.contains(&m.id)).collect();
+    let edges = graph_edges(&config, &metas);
+
+    let rendered = match format {
+        GraphFormat::Dot => render_dot(&settings, &metas, &edges),
+        GraphFormat::Svg => render_svg(&settings, &metas, &edges),
+        _ => render_mermaid(&settings, &metas, &edges),
+    };
+
+    Ok(CmdResult { value: rendered, infos })
+}
+
+fn collect_ids(node: &GraphNode, ids: &mut HashSet<u32>) {
+    ids.insert(node.id);
+    for child in &node.children {
+        collect_ids(child, ids);
+    }
+}
+
+/// Edges between the given issues over all relationships
+/// A relationship and its reciprocal link are collapsed into one edge, named after the side which defines
+/// the `kind` (otherwise the one listed first in config.yaml:relationships)
+pub fn graph_edges(config: &Config, metas: &[Meta]) -> Vec<GraphEdge> {
+    let ids: HashSet<u32> = metas.iter().map(|m| m.id).collect();
+    let names: Vec<&String> = config.relationships.keys().collect();
+
+    let mut edges: Vec<GraphEdge> = Vec::new();
+
+    for meta in metas {
+        for (name, targets) in &meta.relationships {
+            let Some(relationship) = config.relationships.get(name) else {
+                continue;
+            };
+
+            let link = relationship.link.as_ref().filter(|link| config.relationships.contains_key(*link));
+
+            // Decide which side of a reciprocal pair names the edge
+            let (from_side, label, undirected) = match link {
+                Some(link) if link == name => (true, name.clone(), true),
+                Some(link) => {
+                    let link_has_kind = config.relationships.get(link).is_some_and(|r| r.kind.is_some());
+                    let first = names.iter().position(|n| *n == name) < names.iter().position(|n| *n == link);
+
+                    if relationship.kind.is_some() || (!link_has_kind && first) {
+                        (true, name.clone(), false)
+                    } else {
+                        (false, link.clone(), false)
+                    }
+                }
+                None => (true, name.clone(), false),
+            };
+
+            for target in targets.iter().filter(|t| ids.contains(t)) {
+                let (mut from, mut to) = if from_side { (meta.id, *target) } else { (*target, meta.id) };
+
+                if undirected && from > to {
+                    (from, to) = (to, from);
+                }
+
+                let edge = GraphEdge {
+                    from,
+                    to,
+                    label: label.clone(),
+                    undirected,
+                };
+
+                if !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+fn state_color(settings: &Settings, state: &str) -> Option<&'static str> {
+    settings.list_formatting.colors.state.get(state).and_then(|color| color.hex())
+}
+
+fn render_dot(settings: &Settings, metas: &[Meta], edges: &[GraphEdge]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+
+    let mut lines = vec![
+        "digraph issues {".to_string(),
+        "  node [shape=box, style=\"rounded,filled\", fillcolor=\"#ffffff\"];".to_string(),
+    ];
+
+    for meta in metas {
+        let color = match state_color(settings, &meta.state) {
+            Some(color) => format!(", fillcolor=\"{color}\""),
+            None => String::new(),
+        };
+
+        lines.push(format!(
+            "  {} [label=\"#{} {}\\n[{}]\"{color}];",
+            meta.id,
+            meta.id,
+            escape(&meta.title),
+            escape(&meta.state)
+        ));
+    }
+
+    for edge in edges {
+        let dir = if edge.undirected { ", dir=none" } else { "" };
+        lines.push(format!("  {} -> {} [label=\"{}\"{dir}];", edge.from, edge.to, escape(&edge.label)));
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+}
+
+fn render_mermaid(settings: &Settings, metas: &[Meta], edges: &[GraphEdge]) -> String {
+    // Mermaid entity codes, so that titles cannot break the syntax
+    let escape = |s: &str| s.replace('#', "#35;").replace('"', "#quot;");
+
+    let mut lines = vec!["flowchart TD".to_string()];
+
+    for meta in metas {
+        lines.push(format!(
+            "  i{}[\"{} {} [{}]\"]",
+            meta.id,
+            escape(&format!("#{}", meta.id)),
+            escape(&meta.title),
+            escape(&meta.state)
+        ));
+    }
+
+    for edge in edges {
+        let arrow = if edge.undirected { "---" } else { "-->" };
+        lines.push(format!("  i{} {arrow}|{}| i{}", edge.from, escape(&edge.label), edge.to));
+    }
+
+    // One class per state
+    let mut states: Vec<&str> = Vec::new();
+    for meta in metas {
+        if !states.contains(&meta.state.as_str()) {
+            states.push(&meta.state);
+        }
+    }
+
+    for (i, state) in states.iter().enumerate() {
+        let Some(color) = state_color(settings, state) else {
+            continue;
+        };
+
+        let ids = metas
+            .iter()
+            .filter(|m| m.state == *state)
+            .map(|m| format!("i{}", m.id))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        lines.push(format!("  classDef state{i} fill:{color}"));
+        lines.push(format!("  class {ids} state{i}"));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+const SVG_NODE_WIDTH: f64 = 200.0;
+const SVG_NODE_HEIGHT: f64 = 44.0;
+const SVG_GAP_X: f64 = 30.0;
+const SVG_GAP_Y: f64 = 70.0;
+const SVG_TITLE_CHARS: usize = 26;
+
+/// Self-contained SVG: edges point downwards, same-layer edges (e.g. related) are drawn as arcs below the nodes
+/// Layers by longest path along the directed edges, order within a layer by the positions of the neighbors above
+fn render_svg(settings: &Settings, metas: &[Meta], edges: &[GraphEdge]) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    let mut metas: Vec<&Meta> = metas.iter().collect();
+    metas.sort_by_key(|m| m.id);
+
+    let index: HashMap<u32, usize> = metas.iter().enumerate().map(|(i, m)| (m.id, i)).collect();
+    let links: Vec<(usize, usize, &GraphEdge)> = edges
+        .iter()
+        .filter_map(|e| Some((*index.get(&e.from)?, *index.get(&e.to)?, e)))
+        .collect();
+
+    // Longest path layering, bounded by the number of nodes (relationships without kind may form cycles)
+    let mut layer = vec![0; metas.len()];
+    for _ in 0..metas.len() {
+        let mut changed = false;
+        for (from, to, edge) in &links {
+            if !edge.undirected && layer[*to] <= layer[*from] && layer[*from] + 1 < metas.len() {
+                layer[*to] = layer[*from] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let layer_count = layer.iter().max().map_or(0, |max| max + 1);
+    let mut layers: Vec<Vec<usize>> = vec![vec![]; layer_count];
+    for (node, l) in layer.iter().enumerate() {
+        layers[*l].push(node);
+    }
+
+    // Barycenter ordering, one pass from top to bottom
+    let mut position = vec![0.0; metas.len()];
+    for (l, nodes) in layers.iter_mut().enumerate() {
+        if l > 0 {
+            let key = |node: usize| {
+                let above: Vec<f64> = links
+                    .iter()
+                    .filter_map(|(from, to, _)| match (*from == node, *to == node) {
+                        (true, _) if layer[*to] + 1 == l => Some(position[*to]),
+                        (_, true) if layer[*from] + 1 == l => Some(position[*from]),
+                        _ => None,
+                    })
+                    .collect();
+                match above.is_empty() {
+                    true => f64::MAX,
+                    false => above.iter().sum::<f64>() / above.len() as f64,
+                }
+            };
+            let keys: HashMap<usize, f64> = nodes.iter().map(|node| (*node, key(*node))).collect();
+            nodes.sort_by(|a, b| keys[a].total_cmp(&keys[b]).then(a.cmp(b)));
+        }
+        for (i, node) in nodes.iter().enumerate() {
+            position[*node] = i as f64;
+        }
+    }
+
+    let columns = layers.iter().map(Vec::len).max().unwrap_or(0) as f64;
+    let width = columns * (SVG_NODE_WIDTH + SVG_GAP_X) + SVG_GAP_X;
+    let height = layer_count as f64 * (SVG_NODE_HEIGHT + SVG_GAP_Y) + SVG_GAP_Y / 2.0;
+
+    // Center of each node
+    let mut center = vec![(0.0, 0.0); metas.len()];
+    for (l, nodes) in layers.iter().enumerate() {
+        let offset = (width - nodes.len() as f64 * (SVG_NODE_WIDTH + SVG_GAP_X) + SVG_GAP_X) / 2.0;
+        for (i, node) in nodes.iter().enumerate() {
+            center[*node] = (
+                offset + i as f64 * (SVG_NODE_WIDTH + SVG_GAP_X) + SVG_NODE_WIDTH / 2.0,
+                SVG_GAP_Y / 2.0 + l as f64 * (SVG_NODE_HEIGHT + SVG_GAP_Y) + SVG_NODE_HEIGHT / 2.0,
+            );
+        }
+    }
+
+    let mut svg = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.0} {height:.0}" font-family="sans-serif" font-size="12">"#
+        ),
+        r##"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 z" fill="#555"/></marker></defs>"##.to_string(),
+    ];
+
+    for (from, to, edge) in &links {
+        let ((x1, y1), (x2, y2)) = (center[*from], center[*to]);
+        let marker = if edge.undirected { "" } else { r#" marker-end="url(#arrow)""# };
+
+        let (path, (lx, ly)) = if layer[*from] == layer[*to] {
+            let bottom = y1 + SVG_NODE_HEIGHT / 2.0;
+            let control = ((x1 + x2) / 2.0, bottom + SVG_GAP_Y / 2.0);
+            (
+                format!("M{x1:.0},{bottom:.0} Q{:.0},{:.0} {x2:.0},{bottom:.0}", control.0, control.1),
+                ((x1 + x2) / 2.0, (bottom + control.1) / 2.0 + 4.0),
+            )
+        } else {
+            let (sx, sy) = border(x2 - x1, y2 - y1);
+            let (ex, ey) = border(x1 - x2, y1 - y2);
+            (
+                format!("M{:.0},{:.0} L{:.0},{:.0}", x1 + sx, y1 + sy, x2 + ex, y2 + ey),
+                ((x1 + x2) / 2.0, (y1 + y2) / 2.0 + 4.0),
+            )
+        };
+
+        svg.push(format!(r##"<path d="{path}" fill="none" stroke="#555"{marker}/>"##));
+        svg.push(format!(
+            r##"<text x="{lx:.0}" y="{ly:.0}" text-anchor="middle" fill="#555" stroke="#fff" stroke-width="3" paint-order="stroke">{}</text>"##,
+            escape(&edge.label)
+        ));
+    }
+
+    for (node, meta) in metas.iter().enumerate() {
+        let (x, y) = center[node];
+        let fill = state_color(settings, &meta.state).unwrap_or("#ffffff");
+
+        let title = match meta.title.chars().count() > SVG_TITLE_CHARS {
+            true => format!("{}…", meta.title.chars().take(SVG_TITLE_CHARS - 1).collect::<String>()),
+            false => meta.title.clone(),
+        };
+
+        svg.push(format!(
+            r##"<g class="node"><title>#{} {}</title><rect x="{:.0}" y="{:.0}" width="{SVG_NODE_WIDTH:.0}" height="{SVG_NODE_HEIGHT:.0}" rx="6" fill="{fill}" stroke="#555"/><text x="{x:.0}" y="{:.0}" text-anchor="middle">#{} {}</text><text x="{x:.0}" y="{:.0}" text-anchor="middle" fill="#555">[{}]</text></g>"##,
+            meta.id,
+            escape(&meta.title),
+            x - SVG_NODE_WIDTH / 2.0,
+            y - SVG_NODE_HEIGHT / 2.0,
+            y - 3.0,
+            meta.id,
+            escape(&title),
+            y + 13.0,
+            escape(&meta.state)
+        ));
+    }
+
+    svg.push("</svg>".to_string());
+    svg.join("\n") + "\n"
+}
+
+/// Offset from the center of a node to its border, towards (dx, dy)
+fn border(dx: f64, dy: f64) -> (f64, f64) {
+    let scale_x = if dx == 0.0 { f64::MAX } else { SVG_NODE_WIDTH / 2.0 / dx.abs() };
+    let scale_y = if dy == 0.0 { f64::MAX } else { SVG_NODE_HEIGHT / 2.0 / dy.abs() };
+    let scale = scale_x.min(scale_y);
+
+    (dx * scale, dy * scale)
+}
+
+/// Is the state one of config.yaml:states_done
+pub(crate) fn is_done(config: &Config, state: &str) -> bool {
+    config.states_done.iter().any(|s| s == state)
+}
+
+/// Relationships defining a kind: (relationship, reciprocal relationship)
+/// Only one side of a relationship pair may define the kind, and it must not be symmetric
+pub(crate) fn kind_relationships(config: &Config, kind: RelationshipKind) -> Result<Vec<(String, Option<String>)>, String> {
+    let mut result = Vec::new();
+
+    for (name, relationship) in &config.relationships {
+        if relationship.kind != Some(kind) {
+            continue;
+        }
+
+        let link = relationship.link.clone();
+
+        if let Some(link) = &link {
+            if link == name {
+                return Err(format!(
+                    "Invalid config.yaml:relationships:{name}: kind '{kind}' requires an asymmetric relationship"
+                ));
+            }
+
+            if config.relationships.get(link).is_some_and(|r| r.kind.is_some()) {
+                return Err(format!(
+                    "Invalid config.yaml:relationships: kind is set on both '{name}' and '{link}', set it only on the side whose targets are the blockers/parents"
+                ));
+            }
+        }
+
+        result.push((name.clone(), link));
+    }
+
+    Ok(result)
+}
+
+/// Collect the edges of a relationship kind from both sides of the relationship pairs
+pub(crate) fn kind_edges(config: &Config, kind: RelationshipKind, metas: &[Meta]) -> Result<Edges, String> {
+    let relationships = kind_relationships(config, kind)?;
+    let mut edges = Edges::new();
+
+    let mut add_edge = |from: u32, to: u32| {
+        let targets = edges.entry(from).or_default();
+        if !targets.contains(&to) {
+            targets.push(to);
+        }
+    };
+
+    for meta in metas {
+        for (name, link) in &relationships {
+            for target in meta.relationships.get(name).into_iter().flatten() {
+                add_edge(meta.id, *target);
+            }
+
+            if let Some(link) = link {
+                for source in meta.relationships.get(link).into_iter().flatten() {
+                    add_edge(*source, meta.id);
+                }
+            }
+        }
+    }
+
+    Ok(edges)
+}
+
+/// Children per issue (relationship of kind `hierarchy`), in ascending order of ID
+pub(crate) fn children_of(config: &Config, metas: &[Meta]) -> Result<Edges, String> {
+    let mut children = Edges::new();
+
+    for (child, parents) in kind_edges(config, RelationshipKind::Hierarchy, metas)? {
+        for parent in parents {
+            children.entry(parent).or_default().push(child);
+        }
+    }
+
+    for ids in children.values_mut() {
+        ids.sort();
+        ids.dedup();
+    }
+
+    Ok(children)
+}
+
+/// Find a cycle through the given issue, e.g. [1, 2, 3, 1]
+pub(crate) fn find_cycle(edges: &Edges, id: u32) -> Option<Vec<u32>> {
+    fn visit(edges: &Edges, start: u32, current: u32, path: &mut Vec<u32>, visited: &mut HashSet<u32>) -> bool {
+        for target in edges.get(&current).into_iter().flatten() {
+            if *target == start {
+                path.push(*target);
+                return true;
+            }
+
+            if visited.insert(*target) {
+                path.push(*target);
+                if visit(edges, start, *target, path, visited) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+
+        false
+    }
+
+    let mut path = vec![id];
+    let mut visited = HashSet::from([id]);
+
+    if visit(edges, id, id, &mut path, &mut visited) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Issues with at least one unresolved blocker (dependency not in config.yaml:states_done)
+pub(crate) fn blocked_issues(config: &Config, metas: &[Meta]) -> Result<HashSet<u32>, String> {
+    let edges = kind_edges(config, RelationshipKind::Dependency, metas)?;
+    let states: HashMap<u32, &str> = metas.iter().map(|m| (m.id, m.state.as_str())).collect();
+
+    Ok(edges
+        .iter()
+        .filter(|(_, blockers)| {
+            blockers
+                .iter()
+                .any(|blocker| states.get(blocker).is_some_and(|state| !is_done(config, state)))
+        })
+        .map(|(id, _)| *id)
+        .collect())
+}
@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::Meta;
+use crate::storage::{GitStorage, Storage, issue_path};
+use crate::trailers::TRAILER_ACTION;
+
+/// State of an issue after a commit which changed its `meta.yaml`
+pub(crate) struct StateChange {
+    pub id: u32,
+    /// Commit date (RFC 3339)
+    pub time: String,
+    /// None: the issue was deleted
+    pub state: Option<String>,
+}
+
+impl StateChange {
+    /// Commit date YYYY-MM-DD
+    pub fn date(&self) -> &str {
+        self.time.get(..10).unwrap_or(&self.time)
+    }
+}
+
+/// States of the issues set by the commits between `base` (exclusive) and `tip` along the first parents, oldest first
+/// Commits which only changed other fields are included as well, compare with the previous state to find transitions.
+pub(crate) fn state_changes(storage: &GitStorage, tip: &str, base: Option<&str>) -> Result<Vec<StateChange>, String> {
+    let mut changes = Vec::new();
+
+    for (commit, time, ids) in meta_commits(storage, tip, base)? {
+        let paths: Vec<String> = ids.iter().map(|id| issue_path(*id, "meta.yaml")).collect();
+        let blobs = storage.at(&commit).read_many(&paths)?;
+
+        for (id, blob) in ids.into_iter().zip(blobs) {
+            let state = match blob {
+                // Malformed meta.yaml in the history: skip it, the previous state stays valid
+                Some(blob) => match serde_yaml::from_slice::<Meta>(&blob) {
+                    Ok(meta) => Some(meta.state),
+                    Err(_) => continue,
+                },
+                None => None,
+            };
+
+            changes.push(StateChange {
+                id,
+                time: time.clone(),
+                state,
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Commits between `base` (exclusive) and `tip` along the first parents, oldest first, which changed `meta.yaml` files
+/// Returns (commit, commit date, issue IDs)
+fn meta_commits(storage: &GitStorage, tip: &str, base: Option<&str>) -> Result<Vec<(String, String, Vec<u32>)>, String> {
+    let issues_dir = format!("{}/issues", storage.prefix());
+    let exclude = base.map(|base| format!("^{base}"));
+
+    let mut args = vec![
+        "log",
+        "--first-parent",
+        "--diff-merges=first-parent",
+        "--reverse",
+        "--format=%x00%H %cI",
+        "--name-only",
+        tip,
+    ];
+    if let Some(exclude) = &exclude {
+        args.push(exclude);
+    }
+    // Pathspec relative to the root: git runs in .gitissues/ for the working tree
+    let pathspec = format!(":(top){issues_dir}");
+    args.extend(["--", &pathspec]);
+
+    let output = storage.git(&args)?;
+    let output = String::from_utf8_lossy(&output);
+
+    let mut commits = Vec::new();
+
+    for chunk in output.split('\0').filter(|chunk| !chunk.trim().is_empty()) {
+        let mut lines = chunk.lines();
+        let Some((commit, time)) = lines.next().and_then(|line| line.split_once(' ')) else {
+            continue;
+        };
+
+        let ids: Vec<u32> = lines
+            .filter_map(|path| {
+                path.strip_prefix(&format!("{issues_dir}/"))?
+                    .strip_suffix("/meta.yaml")?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+
+        if !ids.is_empty() {
+            commits.push((commit.to_string(), time.to_string(), ids));
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Commit which changed files of issues
+#[derive(Debug, Clone)]
+pub struct IssueCommit {
+    pub commit: String,
+    /// Commit date (RFC 3339)
+    pub time: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub subject: String,
+    /// Trailer `Issue-Action`, '' for commits not created by git-issue
+    pub action: String,
+    /// Issues with changed files
+    pub ids: Vec<u32>,
+}
+
+/// Commits reachable from `tip` but not from `base`, oldest first, which changed any file of the issues
+/// Unlike `state_changes`, side branches are included (merge commits themselves are skipped)
+pub(crate) fn issue_commits(storage: &GitStorage, tip: &str, base: Option<&str>) -> Result<Vec<IssueCommit>, String> {
+    let issues_dir = format!("{}/issues", storage.prefix());
+    let exclude = base.map(|base| format!("^{base}"));
+
+    let format = format!("--format=%x00%H%x1f%cI%x1f%an%x1f%ae%x1f%s%x1f%(trailers:key={TRAILER_ACTION},valueonly,separator=%x2C)");
+
+    let mut args = vec!["log", "--no-merges", "--reverse", &format, "--name-only", tip];
+    if let Some(exclude) = &exclude {
+        args.push(exclude);
+    }
+    // Pathspec relative to the root: git runs in .gitissues/ for the working tree
+    let pathspec = format!(":(top){issues_dir}");
+    args.extend(["--", &pathspec]);
+
+    let output = storage.git(&args)?;
+    let output = String::from_utf8_lossy(&output);
+
+    let mut commits = Vec::new();
+
+    for chunk in output.split('\0').filter(|chunk| !chunk.trim().is_empty()) {
+        let mut lines = chunk.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+
+        let fields: Vec<&str> = header.split('\x1f').collect();
+        let [commit, time, author_name, author_email, subject, action] = fields[..] else {
+            continue;
+        };
+
+        let mut ids: Vec<u32> = lines
+            .filter_map(|path| path.strip_prefix(&format!("{issues_dir}/"))?.split('/').next()?.parse().ok())
+            .collect();
+        ids.dedup();
+
+        if !ids.is_empty() {
+            commits.push(IssueCommit {
+                commit: commit.to_string(),
+                time: time.to_string(),
+                author_name: author_name.to_string(),
+                author_email: author_email.to_string(),
+                subject: subject.to_string(),
+                action: action.trim().to_string(),
+                ids,
+            });
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Parse a timestamp of meta.yaml or a commit date (RFC 3339)
+pub(crate) fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|time| time.with_timezone(&Utc))
+}
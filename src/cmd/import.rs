@@ -0,0 +1,1249 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cmd::graph::{find_cycle, kind_edges, kind_relationships};
+use crate::cmd::list::{csv_separator, get_all_column_names};
+use crate::cmd::milestone::check_milestone;
+use crate::cmd::new::generate_ids;
+use crate::cmd::util::{git_commit_bulk, load_meta, resolve_user};
+use crate::hooks::{self, Event, EventKind};
+use crate::journal;
+use crate::model::{
+    Config, Meta, Milestones, Priority, RelationshipKind, Settings, Users, config_path, current_timestamp, gitissues_base,
+    is_valid_iso_date, is_valid_state, is_valid_type, is_valid_user, issue_attachments_dir, issue_desc_path, issue_dir, issue_meta_path,
+    load_config, load_milestones, load_settings, load_users, normalize_estimate, users_path,
+};
+use crate::notify;
+use crate::storage::{FsStorage, Storage};
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
+use crate::{Cmd, CmdResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// JSON array of `gh issue list --json ...` or of the GitHub REST API
+    GithubJson,
+    /// JSON array of the GitLab REST API, optionally with `notes` per issue
+    GitlabJson,
+    /// CSV export of Jira, timestamps without UTC offset (Jira exports them in the time zone of the exporting user) are taken as UTC
+    JiraCsv,
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportFormat::GithubJson => write!(f, "github-json"),
+            ImportFormat::GitlabJson => write!(f, "gitlab-json"),
+            ImportFormat::JiraCsv => write!(f, "jira-csv"),
+        }
+    }
+}
+
+/// Translation of values of the source tracker (keys) to values of git-issue (values), see `import --mapping`
+/// Keys are matched case-insensitively
+#[derive(Debug, Default, Deserialize)]
+pub struct Mapping {
+    #[serde(default)]
+    pub states: IndexMap<String, String>,
+    #[serde(default)]
+    pub types: IndexMap<String, String>,
+    #[serde(default)]
+    pub users: IndexMap<String, String>,
+}
+
+pub struct ImportResult {
+    /// Key in the source tracker (e.g. `42`, `PROJ-7`) -> ID of the imported issue
+    pub ids: IndexMap<String, u32>,
+    /// ID map written to `.gitissues/imports/`
+    pub id_map: PathBuf,
+}
+
+pub struct CsvImportResult {
+    pub created: Vec<u32>,
+    pub updated: Vec<u32>,
+}
+
+/// Columns of `list --csv` which `import_csv` writes back, all others (created, updated, relationships, computed) are ignored
+const CSV_COLUMNS: [&str; 10] = [
+    "title",
+    "state",
+    "type",
+    "labels",
+    "reporter",
+    "assignee",
+    "priority",
+    "due_date",
+    "milestone",
+    "estimate",
+];
+
+/// Row of a CSV file to be imported
+struct CsvRow {
+    id: Option<u32>,
+    values: HashMap<String, String>,
+}
+
+/// Issue as read from an export file, before mapping
+#[derive(Default)]
+struct Imported {
+    key: String,
+    title: String,
+    body: String,
+    state: String,
+    type_: String,
+    labels: Vec<String>,
+    reporter: String,
+    assignee: String,
+    due_date: String,
+    created: String,
+    updated: String,
+    comments: Vec<Comment>,
+}
+
+struct Comment {
+    author: String,
+    created: String,
+    body: String,
+}
+
+/// Cross-reference between two issues of the export file
+struct CrossRef {
+    from: String,
+    kind: CrossRefKind,
+    to: String,
+}
+
+#[derive(Clone, Copy)]
+enum CrossRefKind {
+    Related,   // symmetric relationship, e.g. related
+    BlockedBy, // relationship of kind `dependency`, e.g. blocked-by
+    ChildOf,   // relationship of kind `hierarchy`, e.g. parent
+}
+
+/// Content of the ID map written to `.gitissues/imports/`
+#[derive(Serialize)]
+struct IdMap {
+    format: String,
+    file: String,
+    imported: String,
+    ids: IndexMap<String, u32>,
+}
+
+/// Import issues from an export file of another issue tracker
+/// - format: format of the export file
+/// - file: path of the export file
+/// - mapping: optional YAML file translating states, types and users (see `Mapping`)
+/// - create_missing: add states/types to config.yaml and users to users.yaml which do not exist yet, otherwise this is an error
+pub fn import(format: ImportFormat, file: &Path, mapping: Option<&Path>, create_missing: bool) -> Cmd<ImportResult> {
+    // Step 1: Read config and export file
+    let config = load_config()?;
+    let users = load_users()?;
+
+    let mapping = match mapping {
+        Some(path) => load_mapping(path)?,
+        None => Mapping::default(),
+    };
+
+    let raw = fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+
+    let (issues, cross_refs) = match format {
+        ImportFormat::GithubJson => parse_github(&raw)?,
+        ImportFormat::GitlabJson => parse_gitlab(&raw)?,
+        ImportFormat::JiraCsv => parse_jira(&raw)?,
+    };
+
+    if issues.is_empty() {
+        return Err(format!("Nothing to import: {} contains no issues", file.display()));
+    }
+
+    let mut keys = HashSet::new();
+    if let Some(issue) = issues.iter().find(|issue| !keys.insert(issue.key.as_str())) {
+        return Err(format!("Invalid export file: issue {} is contained twice", issue.key));
+    }
+
+    // Step 2: Map states, types and users, collect the ones which do not exist yet
+    let mut missing = Missing::default();
+
+    let issues: Vec<Imported> = issues
+        .into_iter()
+        .map(|mut issue| {
+            issue.state = map_state(&mapping, &config, &issue.state);
+            issue.type_ = map_value(&mapping.types, &issue.type_).unwrap_or_else(|| issue.type_.to_lowercase());
+            issue.reporter = map_user(&mapping, &users, &issue.reporter);
+            issue.assignee = map_user(&mapping, &users, &issue.assignee);
+
+            missing.check(&config, &users, &issue);
+            issue
+        })
+        .collect();
+
+    if !missing.is_empty() && !create_missing {
+        return Err(format!(
+            "Not available: {}. Translate them with --mapping or create them with --create-missing",
+            missing.describe()
+        ));
+    }
+
+    // Step 3: Allocate IDs and create meta fields
+    let ids = generate_ids(issues.len())?;
+    let id_of: IndexMap<String, u32> = issues.iter().map(|issue| issue.key.clone()).zip(ids.iter().copied()).collect();
+
+    let mut metas: IndexMap<u32, Meta> = IndexMap::new();
+
+    for (issue, id) in issues.iter().zip(&ids) {
+        let mut meta = Meta {
+            _version: 1,
+            id: *id,
+            title: issue.title.clone(),
+            state: issue.state.clone(),
+            type_: issue.type_.clone(),
+            labels: issue.labels.clone(),
+            reporter: issue.reporter.clone(),
+            assignee: issue.assignee.clone(),
+            priority: config.priority_default,
+            due_date: issue.due_date.clone(),
+            milestone: String::new(),
+            estimate: String::new(),
+            watchers: Vec::new(),
+            relationships: IndexMap::new(),
+            created: parse_timestamp(&issue.created, &issue.key)?,
+            updated: parse_timestamp(&issue.updated, &issue.key)?,
+        };
+
+        meta.add_watcher(&issue.reporter);
+        meta.add_watcher(&issue.assignee);
+
+        metas.insert(*id, meta);
+    }
+
+    // Step 4: Cross-references between the imported issues become relationships
+    let mut infos = Vec::new();
+
+    for cross_ref in cross_refs {
+        let (Some(from), Some(to)) = (id_of.get(&cross_ref.from), id_of.get(&cross_ref.to)) else {
+            continue; // references to issues outside of the export file
+        };
+
+        if from == to {
+            continue;
+        }
+
+        match relationship_for(&config, cross_ref.kind)? {
+            Some(name) => add_relationship(&config, &mut metas, *from, &name, *to),
+            None => infos.push(format!(
+                "Info: Skipped reference {} -> {}: no matching relationship in config.yaml:relationships",
+                cross_ref.from, cross_ref.to
+            )),
+        }
+    }
+
+    check_cycles(&config, &metas)?;
+
+    // Step 5: Prepare all files
+    let mut transaction = Transaction::new();
+    let mut paths = Vec::new();
+
+    for (issue, meta) in issues.iter().zip(metas.values()) {
+        let dir = issue_dir(meta.id)?;
+        transaction.create_dir(dir.clone());
+        transaction.write(issue_desc_path(meta.id)?, description(issue));
+        transaction.write(issue_attachments_dir(meta.id)?.join(".gitkeep"), "");
+        transaction.write_meta(meta)?;
+        paths.push(dir);
+    }
+
+    if create_missing {
+        for (path, content) in missing.updated_files()? {
+            transaction.write(path.clone(), content);
+            paths.push(path);
+        }
+    }
+
+    let id_map_path = gitissues_base()?
+        .join("imports")
+        .join(format!("{}-{format}.yaml", current_timestamp().replace(":", "-")));
+
+    let id_map = IdMap {
+        format: format.to_string(),
+        file: file.display().to_string(),
+        imported: current_timestamp(),
+        ids: id_of.clone(),
+    };
+    let id_map_yaml = serde_yaml::to_string(&id_map).map_err(|_| "Failed to serialize ID map".to_string())?;
+    transaction.write(id_map_path.clone(), id_map_yaml);
+    paths.push(id_map_path.clone());
+
+    // Step 6: Write all files at once
+    let applied = transaction.apply()?;
+    journal::record(&format!("import of {} issues", ids.len()), &applied)?;
+
+    // Step 7: git commit
+    let infos_commit = git_commit_bulk(&ids, "import", Trailers::new("import", &ids), &paths)?;
+    infos.extend(infos_commit);
+
+    // Step 8: notifications and hooks, like `new`
+    let mut events = Vec::new();
+
+    for meta in metas.values() {
+        events.push(Event::new(EventKind::New, meta));
+
+        if !meta.assignee.is_empty() {
+            events.push(Event::change(EventKind::Assign, meta, "", &meta.assignee));
+        }
+    }
+
+    infos.extend(notify::run(&events));
+    infos.extend(hooks::run(events));
+
+    Ok(CmdResult {
+        value: ImportResult {
+            ids: id_of,
+            id_map: id_map_path,
+        },
+        infos,
+    })
+}
+
+/// Create or update issues from a CSV file in the layout of `list --csv`, e.g. after bulk triage in a spreadsheet
+/// - Rows with an `id` update the issue like `set` (only changed fields), rows without `id` create an issue like `new`
+/// - All issues are written at once and committed in a single commit, so one `undo` reverts the whole import
+/// - `-` stands for an empty value (except for title and state), like in `list`
+/// - The separator is settings.yaml:export_csv_separator
+pub fn import_csv(file: &Path) -> Cmd<CsvImportResult> {
+    let config = load_config()?;
+    let (settings, mut infos) = load_settings()?;
+    let users = load_users()?;
+    let milestones = load_milestones()?;
+
+    let raw = fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(csv_separator(settings.export_csv_separator)?)
+        .from_reader(raw.as_bytes());
+
+    // Validate the header before anything is written
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV: {e}"))?
+        .iter()
+        .map(|h| {
+            if h.trim() == "due-date" {
+                "due_date".to_string()
+            } else {
+                h.trim().to_string()
+            }
+        })
+        .collect();
+
+    let valid_columns = get_all_column_names(&config);
+
+    if let Some(col) = headers.iter().find(|h| !valid_columns.contains(h)) {
+        return Err(format!("Invalid column name in CSV header: {col}"));
+    }
+
+    let ignored: Vec<&str> = headers
+        .iter()
+        .map(String::as_str)
+        .filter(|h| *h != "id" && !CSV_COLUMNS.contains(h))
+        .collect();
+
+    if !ignored.is_empty() {
+        infos.push(format!("Info: Ignored read-only column(s): {}", ignored.join(",")));
+    }
+
+    // Validate all rows before anything is written
+    let mut rows = Vec::new();
+
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2; // header is line 1
+        let record = record.map_err(|e| format!("Invalid CSV in line {line}: {e}"))?;
+
+        let mut values: HashMap<String, String> = headers.iter().cloned().zip(record.iter().map(|v| v.trim().to_string())).collect();
+
+        let id = match values.remove("id").filter(|id| !id.is_empty()) {
+            Some(id) => {
+                let id = id.parse::<u32>().map_err(|_| format!("Invalid ID \"{id}\" in line {line}"))?;
+
+                if !issue_dir(id)?.exists() {
+                    return Err(format!("Not available: ID #{id} in line {line} does not exist."));
+                }
+
+                Some(id)
+            }
+            None => None,
+        };
+
+        values.retain(|col, _| CSV_COLUMNS.contains(&col.as_str()));
+
+        for (col, value) in values.iter_mut() {
+            if col != "title" && col != "state" && value == "-" {
+                value.clear();
+            }
+        }
+
+        let current = id.map(|id| load_meta(&issue_meta_path(id)?)).transpose()?;
+
+        validate_csv_row(&config, &users, &milestones, &values, current.as_ref()).map_err(|e| format!("{e} in line {line}"))?;
+
+        if id.is_none() && values.get("title").is_none_or(|t| t.is_empty()) {
+            return Err(format!("Missing title in line {line}: required to create an issue"));
+        }
+
+        rows.push(CsvRow { id, values });
+    }
+
+    // Prepare all issues, rows without ID get new IDs
+    let mut new_ids = generate_ids(rows.iter().filter(|row| row.id.is_none()).count())?.into_iter();
+
+    let template_path = gitissues_base()?.join("description.md");
+    let template = fs::read(&template_path).map_err(|e| format!("Failed to read description template: {e}"))?;
+    let timestamp = current_timestamp();
+
+    let mut transaction = Transaction::new();
+    let mut created = Vec::new();
+    let mut updated = Vec::new();
+    let mut all_fields: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for row in rows {
+        match row.id {
+            Some(id) => {
+                let meta = load_meta(&issue_meta_path(id)?)?;
+                let mut updated_meta = meta.clone();
+
+                let fields = apply_csv_row(&settings, &users, &milestones, &row.values, &mut updated_meta)?;
+
+                if fields.is_empty() {
+                    continue;
+                }
+
+                updated_meta.updated = timestamp.clone();
+
+                if fields.contains(&"state") {
+                    events.push(Event::change(
+                        EventKind::StateChange,
+                        &updated_meta,
+                        &meta.state,
+                        &updated_meta.state,
+                    ));
+                }
+
+                if fields.contains(&"assignee") {
+                    events.push(Event::change(
+                        EventKind::Assign,
+                        &updated_meta,
+                        &meta.assignee,
+                        &updated_meta.assignee,
+                    ));
+                }
+
+                for field in fields {
+                    if !all_fields.contains(&field) {
+                        all_fields.push(field);
+                    }
+                }
+
+                transaction.write_meta(&updated_meta)?;
+                updated.push(id);
+            }
+            None => {
+                let id = new_ids.next().ok_or("Failed to allocate issue IDs")?;
+
+                if !row.values.contains_key("reporter") && !is_valid_user(&users, &settings.user) {
+                    return Err(format!(
+                        "Invalid reporter \"{}\": settings.yaml::user must be part of users.yaml:users or ''",
+                        settings.user
+                    ));
+                }
+
+                let mut meta = Meta {
+                    _version: 1,
+                    id,
+                    title: String::new(),
+                    state: config.states.first().cloned().unwrap_or_else(|| "new".to_string()),
+                    type_: String::new(),
+                    labels: Vec::new(),
+                    reporter: settings.user.clone(),
+                    assignee: String::new(),
+                    priority: config.priority_default,
+                    due_date: String::new(),
+                    milestone: String::new(),
+                    estimate: String::new(),
+                    watchers: Vec::new(),
+                    relationships: IndexMap::new(),
+                    created: timestamp.clone(),
+                    updated: timestamp.clone(),
+                };
+
+                apply_csv_row(&settings, &users, &milestones, &row.values, &mut meta)?;
+
+                // Reporter and assignee watch the issue
+                meta.add_watcher(&meta.reporter.clone());
+                meta.add_watcher(&meta.assignee.clone());
+
+                events.push(Event::new(EventKind::New, &meta));
+
+                if !meta.assignee.is_empty() {
+                    events.push(Event::change(EventKind::Assign, &meta, "", &meta.assignee));
+                }
+
+                transaction.create_dir(issue_dir(id)?);
+                transaction.write(issue_desc_path(id)?, template.clone());
+                transaction.write(issue_attachments_dir(id)?.join(".gitkeep"), "");
+                transaction.write_meta(&meta)?;
+                created.push(id);
+            }
+        }
+    }
+
+    let ids: Vec<u32> = created.iter().chain(&updated).copied().collect();
+
+    if ids.is_empty() {
+        return Ok(CmdResult {
+            value: CsvImportResult { created, updated },
+            infos,
+        });
+    }
+
+    // Write all files at once
+    let applied = transaction.apply()?;
+    journal::record(&format!("csv import of {} issues", ids.len()), &applied)?;
+
+    // Single commit for all issues, so that one `undo` reverts the whole import
+    let paths = ids.iter().map(|id| issue_dir(*id)).collect::<Result<Vec<_>, _>>()?;
+    let trailers = Trailers::new("import", &ids).with_fields(&all_fields);
+    infos.extend(git_commit_bulk(&ids, "csv import", trailers, &paths)?);
+
+    infos.extend(notify::run(&events));
+    infos.extend(hooks::run(events));
+
+    Ok(CmdResult {
+        value: CsvImportResult { created, updated },
+        infos,
+    })
+}
+
+/// Apply the values of a validated CSV row to an issue like `set` does, only changed fields are touched
+/// Returns the changed fields
+fn apply_csv_row(
+    settings: &Settings,
+    users: &Users,
+    milestones: &Milestones,
+    values: &HashMap<String, String>,
+    meta: &mut Meta,
+) -> Result<Vec<&'static str>, String> {
+    let mut fields = Vec::new();
+
+    for (col, value) in values {
+        match col.as_str() {
+            "title" if meta.title != *value => {
+                meta.title = value.clone();
+                fields.push("title");
+            }
+            "state" if meta.state != *value => {
+                meta.state = value.clone();
+                fields.push("state");
+            }
+            "type" if meta.type_ != *value => {
+                meta.type_ = value.clone();
+                fields.push("type");
+            }
+            "reporter" if meta.reporter != *value => {
+                let value = resolve_user(users, settings, value, "reporter")?;
+
+                if meta.reporter != value {
+                    meta.add_watcher(&value);
+                    meta.reporter = value;
+                    fields.push("reporter");
+                }
+            }
+            "assignee" if meta.assignee != *value => {
+                let value = resolve_user(users, settings, value, "assignee")?;
+
+                if meta.assignee != value {
+                    meta.add_watcher(&value);
+                    meta.assignee = value;
+                    fields.push("assignee");
+                }
+            }
+            "priority" => {
+                let value = value.parse::<Priority>()?;
+
+                if meta.priority != value {
+                    meta.priority = value;
+                    fields.push("priority");
+                }
+            }
+            "due_date" if meta.due_date != *value => {
+                meta.due_date = value.clone();
+                fields.push("due_date");
+            }
+            "milestone" if meta.milestone != *value => {
+                check_milestone(milestones, value)?;
+
+                meta.milestone = value.clone();
+                fields.push("milestone");
+            }
+            "estimate" => {
+                let value = normalize_estimate(value)?;
+
+                if meta.estimate != value {
+                    meta.estimate = value;
+                    fields.push("estimate");
+                }
+            }
+            "labels" => {
+                let labels: Vec<String> = value
+                    .split(',')
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty()) // empty cell: no labels
+                    .collect();
+
+                if meta.labels != labels {
+                    meta.labels = labels;
+                    fields.push("labels");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Same order of fields for every row, independent of the column order
+    fields.sort_by_key(|field| CSV_COLUMNS.iter().position(|col| col == field));
+
+    Ok(fields)
+}
+
+/// Validate the values of a CSV row up front, so that no row is written if any of them is invalid
+/// - current: meta of the issue to update, `None` for a new issue
+fn validate_csv_row(
+    config: &Config,
+    users: &Users,
+    milestones: &Milestones,
+    values: &HashMap<String, String>,
+    current: Option<&Meta>,
+) -> Result<(), String> {
+    if let Some(state) = values.get("state")
+        && !is_valid_state(config, state)
+    {
+        return Err(format!("Invalid state \"{state}\""));
+    }
+
+    if let Some(type_) = values.get("type")
+        && !is_valid_type(config, type_)
+    {
+        return Err(format!("Invalid type \"{type_}\""));
+    }
+
+    // Users by ID, alias or email; inactive users only if they are already set
+    for col in ["reporter", "assignee"] {
+        let Some(user) = values.get(col).filter(|user| !user.is_empty() && *user != "me") else {
+            continue;
+        };
+
+        let current = current.map(|meta| if col == "reporter" { &meta.reporter } else { &meta.assignee });
+
+        match users.find(user) {
+            None => return Err(format!("Invalid {col} \"{user}\"")),
+            Some(found) if !found.active && current != Some(&found.id) => {
+                return Err(format!("Invalid {col} \"{user}\": user is inactive"));
+            }
+            Some(_) => {}
+        }
+    }
+
+    if let Some(priority) = values.get("priority") {
+        priority.parse::<Priority>()?;
+    }
+
+    if let Some(due_date) = values.get("due_date")
+        && !is_valid_iso_date(due_date)?
+    {
+        return Err(format!("Invalid due_date \"{due_date}\""));
+    }
+
+    // Closed milestones are accepted here, `set` only rejects them if the value changes (round-trip)
+    if let Some(milestone) = values.get("milestone")
+        && !milestone.is_empty()
+        && milestones.get(milestone).is_none()
+    {
+        return Err(format!("Invalid milestone \"{milestone}\""));
+    }
+
+    if let Some(estimate) = values.get("estimate")
+        && normalize_estimate(estimate).is_err()
+    {
+        return Err(format!("Invalid estimate \"{estimate}\""));
+    }
+
+    Ok(())
+}
+
+fn load_mapping(path: &Path) -> Result<Mapping, String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_yaml::from_str(&raw).map_err(|e| format!("Mapping file malformatted: {}: {e}", path.display()))
+}
+
+/// Mapped user, otherwise the ID of the user with this ID, alias or email in users.yaml, otherwise unchanged
+fn map_user(mapping: &Mapping, users: &Users, user: &str) -> String {
+    map_value(&mapping.users, user)
+        .or_else(|| users.find(user).map(|u| u.id.clone()))
+        .unwrap_or_else(|| user.to_string())
+}
+
+fn map_value(map: &IndexMap<String, String>, value: &str) -> Option<String> {
+    map.iter().find(|(key, _)| key.eq_ignore_ascii_case(value)).map(|(_, v)| v.clone())
+}
+
+/// Mapped state, otherwise open states become the initial state and all others are lowercased
+fn map_state(mapping: &Mapping, config: &Config, state: &str) -> String {
+    if let Some(state) = map_value(&mapping.states, state) {
+        return state;
+    }
+
+    match state.to_lowercase().as_str() {
+        "open" | "opened" => config.states.first().cloned().unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+/// States, types and users of the imported issues which do not exist yet
+#[derive(Default)]
+struct Missing {
+    states: Vec<String>,
+    types: Vec<String>,
+    users: Vec<String>,
+}
+
+impl Missing {
+    fn check(&mut self, config: &Config, users: &Users, issue: &Imported) {
+        if !is_valid_state(config, &issue.state) && !self.states.contains(&issue.state) {
+            self.states.push(issue.state.clone());
+        }
+
+        if !is_valid_type(config, &issue.type_) && !self.types.contains(&issue.type_) {
+            self.types.push(issue.type_.clone());
+        }
+
+        for user in [&issue.reporter, &issue.assignee] {
+            if !is_valid_user(users, user) && !self.users.contains(user) {
+                self.users.push(user.clone());
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.states.is_empty() && self.types.is_empty() && self.users.is_empty()
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+
+        for (values, location) in [
+            (&self.states, "states in config.yaml:states"),
+            (&self.types, "types in config.yaml:types"),
+            (&self.users, "users in users.yaml:users"),
+        ] {
+            if !values.is_empty() {
+                parts.push(format!("{location} {values:?}"));
+            }
+        }
+
+        parts.join(", ")
+    }
+
+    /// config.yaml and users.yaml with the missing values appended (comments are kept)
+    fn updated_files(&self) -> Result<Vec<(PathBuf, String)>, String> {
+        let mut files = Vec::new();
+
+        if !self.states.is_empty() || !self.types.is_empty() {
+            let path = config_path()?;
+            let mut content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+
+            content = append_to_yaml_list(&content, "states", &self.states)?;
+            content = append_to_yaml_list(&content, "types", &self.types)?;
+
+            serde_yaml::from_str::<Config>(&content).map_err(|e| format!("Failed to update config.yaml: {e}"))?;
+            files.push((path, content));
+        }
+
+        if !self.users.is_empty() {
+            let path = users_path()?;
+            let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+
+            let items: Vec<String> = self.users.iter().map(|user| format!("id: {}", yaml_scalar(user))).collect();
+            let content = append_to_yaml_list(&content, "users", &items)?;
+
+            serde_yaml::from_str::<Users>(&content).map_err(|e| format!("Failed to update users.yaml: {e}"))?;
+            files.push((path, content));
+        }
+
+        Ok(files)
+    }
+}
+
+/// Append items to a top-level block list of a YAML file, e.g. `states:` followed by `  - new` lines
+/// Items may span multiple lines (e.g. a mapping), the following lines are indented below the first one
+pub(crate) fn append_to_yaml_list(content: &str, key: &str, items: &[String]) -> Result<String, String> {
+    if items.is_empty() {
+        return Ok(content.to_string());
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+
+    let Some(start) = lines.iter().position(|line| line.trim_end() == format!("{key}:")) else {
+        return Err(format!("Failed to add {key}: no block list '{key}:' found"));
+    };
+
+    // The list ends before the next top-level entry
+    let mut end = start + 1;
+    let mut indent = "  ".to_string();
+
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            break;
+        }
+
+        if line.trim_start().starts_with("- ") && end == start + 1 {
+            indent = line[..line.len() - line.trim_start().len()].to_string();
+        }
+
+        end = i + 1;
+    }
+
+    let mut result: Vec<String> = lines[..end].iter().map(|line| line.to_string()).collect();
+    result.extend(
+        items
+            .iter()
+            .map(|item| format!("{indent}- {}", item.replace('\n', &format!("\n{indent}  ")))),
+    );
+    result.extend(lines[end..].iter().map(|line| line.to_string()));
+
+    Ok(result.join("\n") + "\n")
+}
+
+/// Quote a value for YAML if needed
+pub(crate) fn yaml_scalar(value: &str) -> String {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .unwrap_or_else(|_| format!("'{value}'"))
+}
+
+/// Timestamp as ISO 8601 (UTC), supports RFC 3339 and the date formats of Jira exports
+/// - Timestamps without offset (Jira) are assumed to be UTC, as the export does not contain the time zone
+fn parse_timestamp(value: &str, key: &str) -> Result<String, String> {
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+    let value = value.trim();
+
+    if value.is_empty() {
+        return Ok(current_timestamp());
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.to_utc().format(FORMAT).to_string());
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%d/%b/%y %I:%M %p", "%d/%b/%Y %I:%M %p"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(datetime.format(FORMAT).to_string());
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(format!("{}T00:00:00Z", date.format("%Y-%m-%d")));
+    }
+
+    Err(format!("Invalid date \"{value}\" of issue {key}"))
+}
+
+/// Name of the relationship for a cross-reference, None if config.yaml has no such relationship
+fn relationship_for(config: &Config, kind: CrossRefKind) -> Result<Option<String>, String> {
+    let name = match kind {
+        CrossRefKind::Related => config
+            .relationships
+            .iter()
+            .find(|(name, relationship)| relationship.link.as_ref() == Some(*name))
+            .map(|(name, _)| name.clone()),
+        CrossRefKind::BlockedBy => kind_relationships(config, RelationshipKind::Dependency)?
+            .into_iter()
+            .next()
+            .map(|r| r.0),
+        CrossRefKind::ChildOf => kind_relationships(config, RelationshipKind::Hierarchy)?
+            .into_iter()
+            .next()
+            .map(|r| r.0),
+    };
+
+    Ok(name)
+}
+
+/// Add a relationship incl. its reciprocal link
+fn add_relationship(config: &Config, metas: &mut IndexMap<u32, Meta>, from: u32, name: &str, to: u32) {
+    let mut add = |id: u32, name: &str, target: u32| {
+        if let Some(meta) = metas.get_mut(&id) {
+            let targets = meta.relationships.entry(name.to_string()).or_default();
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    };
+
+    add(from, name, to);
+
+    if let Some(link) = config.relationships.get(name).and_then(|r| r.link.as_ref()) {
+        add(to, link, from);
+    }
+}
+
+/// Reject imports which would create a cycle of a relationship kind
+fn check_cycles(config: &Config, metas: &IndexMap<u32, Meta>) -> Result<(), String> {
+    let mut all_metas = FsStorage::discover()?.load_metas()?;
+    all_metas.extend(metas.values().cloned());
+
+    for kind in [RelationshipKind::Dependency, RelationshipKind::Hierarchy] {
+        let edges = kind_edges(config, kind, &all_metas)?;
+
+        for id in metas.keys() {
+            if let Some(cycle) = find_cycle(&edges, *id) {
+                let cycle = cycle.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(" -> ");
+                return Err(format!("Rejected: the import would create a {kind} cycle: {cycle}"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// description.md: the body, followed by the comments
+fn description(issue: &Imported) -> String {
+    let mut content = format!("# Description\n\n{}\n", issue.body.trim());
+
+    if !issue.comments.is_empty() {
+        content.push_str("\n# Comments\n");
+
+        for comment in &issue.comments {
+            content.push_str(&format!(
+                "\n## {} -- {}\n\n{}\n",
+                dash_if_blank(&comment.author),
+                dash_if_blank(&comment.created),
+                comment.body.trim()
+            ));
+        }
+    }
+
+    content
+}
+
+fn dash_if_blank(value: &str) -> &str {
+    if value.trim().is_empty() { "-" } else { value }
+}
+
+/// Mentions of other issues (`#42`) in the body and comments
+fn mentions(issue: &Imported) -> Vec<CrossRef> {
+    let Ok(re) = Regex::new(r"(?:^|[^\w/&])#(\d+)\b") else {
+        return vec![];
+    };
+
+    let texts = std::iter::once(&issue.body).chain(issue.comments.iter().map(|c| &c.body));
+
+    texts
+        .flat_map(|text| re.captures_iter(text).map(|c| c[1].to_string()).collect::<Vec<_>>())
+        .map(|key| CrossRef {
+            from: issue.key.clone(),
+            kind: CrossRefKind::Related,
+            to: key,
+        })
+        .collect()
+}
+
+fn parse_json_array(raw: &str) -> Result<Vec<Value>, String> {
+    match serde_json::from_str::<Value>(raw).map_err(|e| format!("Invalid JSON: {e}"))? {
+        Value::Array(items) => Ok(items),
+        _ => Err("Invalid export file: expected a JSON array of issues".to_string()),
+    }
+}
+
+/// String of a JSON field, empty if missing or null
+fn json_str(value: &Value, field: &str) -> String {
+    match value.get(field) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// String field of a nested JSON object, e.g. `author.login`
+fn json_nested_str(value: &Value, field: &str, nested: &str) -> String {
+    value.get(field).map(|v| json_str(v, nested)).unwrap_or_default()
+}
+
+/// First non-empty of several alternative fields
+fn json_str_any(value: &Value, fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| json_str(value, field))
+        .find(|s| !s.is_empty())
+        .unwrap_or_default()
+}
+
+fn json_array<'a>(value: &'a Value, field: &str) -> &'a [Value] {
+    value
+        .get(field)
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or_default()
+}
+
+/// GitHub: `gh issue list --json number,title,body,state,labels,author,assignees,comments,createdAt,updatedAt`
+/// or the REST API (`user` instead of `author`, snake_case dates; `comments` is only a count there)
+fn parse_github(raw: &str) -> Result<(Vec<Imported>, Vec<CrossRef>), String> {
+    let mut issues = Vec::new();
+    let mut cross_refs = Vec::new();
+
+    for item in parse_json_array(raw)? {
+        let key = json_str(&item, "number");
+        if key.is_empty() {
+            return Err("Invalid export file: issue without 'number'".to_string());
+        }
+
+        let reporter = match json_nested_str(&item, "author", "login") {
+            login if login.is_empty() => json_nested_str(&item, "user", "login"),
+            login => login,
+        };
+
+        let issue = Imported {
+            title: json_str(&item, "title"),
+            body: json_str(&item, "body"),
+            state: json_str(&item, "state"),
+            labels: json_array(&item, "labels").iter().map(|l| json_str(l, "name")).collect(),
+            reporter,
+            assignee: json_array(&item, "assignees")
+                .first()
+                .map(|a| json_str(a, "login"))
+                .unwrap_or_default(),
+            created: json_str_any(&item, &["createdAt", "created_at"]),
+            updated: json_str_any(&item, &["updatedAt", "updated_at"]),
+            comments: json_array(&item, "comments")
+                .iter()
+                .map(|c| Comment {
+                    author: json_nested_str(c, "author", "login"),
+                    created: json_str_any(c, &["createdAt", "created_at"]),
+                    body: json_str(c, "body"),
+                })
+                .collect(),
+            key,
+            ..Default::default()
+        };
+
+        cross_refs.extend(mentions(&issue));
+        issues.push(issue);
+    }
+
+    Ok((issues, cross_refs))
+}
+
+/// GitLab: REST API (`GET /projects/:id/issues`), the comments are read from an optional `notes` array per issue
+fn parse_gitlab(raw: &str) -> Result<(Vec<Imported>, Vec<CrossRef>), String> {
+    let mut issues = Vec::new();
+    let mut cross_refs = Vec::new();
+
+    for item in parse_json_array(raw)? {
+        let key = json_str(&item, "iid");
+        if key.is_empty() {
+            return Err("Invalid export file: issue without 'iid'".to_string());
+        }
+
+        let due_date = json_str(&item, "due_date");
+        if !is_valid_iso_date(&due_date)? {
+            return Err(format!("Invalid due_date \"{due_date}\" of issue {key}"));
+        }
+
+        let assignee = match json_array(&item, "assignees").first() {
+            Some(assignee) => json_str(assignee, "username"),
+            None => json_nested_str(&item, "assignee", "username"),
+        };
+
+        let issue = Imported {
+            title: json_str(&item, "title"),
+            body: json_str(&item, "description"),
+            state: json_str(&item, "state"),
+            labels: json_array(&item, "labels")
+                .iter()
+                .filter_map(|l| l.as_str().map(String::from))
+                .collect(),
+            reporter: json_nested_str(&item, "author", "username"),
+            assignee,
+            due_date,
+            created: json_str(&item, "created_at"),
+            updated: json_str(&item, "updated_at"),
+            comments: json_array(&item, "notes")
+                .iter()
+                .filter(|n| !n.get("system").and_then(|s| s.as_bool()).unwrap_or(false))
+                .map(|n| Comment {
+                    author: json_nested_str(n, "author", "username"),
+                    created: json_str(n, "created_at"),
+                    body: json_str(n, "body"),
+                })
+                .collect(),
+            key,
+            ..Default::default()
+        };
+
+        cross_refs.extend(mentions(&issue));
+        issues.push(issue);
+    }
+
+    Ok((issues, cross_refs))
+}
+
+/// Jira: CSV export (all fields); `Labels`, `Comment` and issue link columns may occur several times
+/// - Comment: `<date>;<author>;<body>`
+/// - Parent: key or `Issue id` of the parent
+/// - Inward/Outward issue link (<type>): keys of the linked issues, type `Blocks` becomes a dependency, all others are related
+fn parse_jira(raw: &str) -> Result<(Vec<Imported>, Vec<CrossRef>), String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(raw.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Invalid CSV: {e}"))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    let columns = |name: &str| -> Vec<usize> {
+        headers
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| h.eq_ignore_ascii_case(name))
+            .map(|(i, _)| i)
+            .collect()
+    };
+
+    if columns("Issue key").is_empty() || columns("Summary").is_empty() {
+        return Err("Invalid export file: columns 'Issue key' and 'Summary' are required".to_string());
+    }
+
+    let links: Vec<(usize, bool, String)> = headers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, h)| {
+            let (inward, rest) = match (h.strip_prefix("Inward issue link ("), h.strip_prefix("Outward issue link (")) {
+                (Some(rest), _) => (true, rest),
+                (_, Some(rest)) => (false, rest),
+                _ => return None,
+            };
+            Some((i, inward, rest.trim_end_matches(')').to_string()))
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    let mut cross_refs = Vec::new();
+    let mut parents = Vec::new();
+    let mut keys_by_issue_id = HashMap::new();
+
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Invalid CSV: {e}"))?;
+
+        let values = |name: &str| -> Vec<String> {
+            columns(name)
+                .into_iter()
+                .filter_map(|i| record.get(i))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect()
+        };
+        let value = |name: &str| values(name).into_iter().next().unwrap_or_default();
+
+        let key = value("Issue key");
+        if key.is_empty() {
+            return Err("Invalid export file: issue without 'Issue key'".to_string());
+        }
+
+        if let Some(issue_id) = values("Issue id").into_iter().next() {
+            keys_by_issue_id.insert(issue_id, key.clone());
+        }
+
+        if let Some(parent) = values("Parent").into_iter().chain(values("Parent id")).next() {
+            parents.push((key.clone(), parent));
+        }
+
+        for (i, inward, type_) in &links {
+            let Some(target) = record.get(*i).map(str::trim).filter(|t| !t.is_empty()) else {
+                continue;
+            };
+
+            let (from, to) = if *inward {
+                (key.clone(), target.to_string())
+            } else {
+                (target.to_string(), key.clone())
+            };
+
+            let kind = match type_.eq_ignore_ascii_case("blocks") {
+                true => CrossRefKind::BlockedBy,
+                false => CrossRefKind::Related,
+            };
+
+            cross_refs.push(CrossRef { from, kind, to });
+        }
+
+        let comments = values("Comment")
+            .into_iter()
+            .map(|comment| {
+                let mut parts = comment.splitn(3, ';');
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(created), Some(author), Some(body)) => Comment {
+                        author: author.to_string(),
+                        created: created.to_string(),
+                        body: body.to_string(),
+                    },
+                    _ => Comment {
+                        author: String::new(),
+                        created: String::new(),
+                        body: comment.clone(),
+                    },
+                }
+            })
+            .collect();
+
+        issues.push(Imported {
+            title: value("Summary"),
+            body: value("Description"),
+            state: value("Status"),
+            type_: value("Issue Type"),
+            labels: values("Labels"),
+            reporter: value("Reporter"),
+            assignee: value("Assignee"),
+            created: value("Created"),
+            updated: value("Updated"),
+            comments,
+            key,
+            ..Default::default()
+        });
+    }
+
+    for (child, parent) in parents {
+        let parent = keys_by_issue_id.get(&parent).cloned().unwrap_or(parent);
+
+        cross_refs.push(CrossRef {
+            from: child,
+            kind: CrossRefKind::ChildOf,
+            to: parent,
+        });
+    }
+
+    Ok((issues, cross_refs))
+}
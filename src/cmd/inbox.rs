@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cmd::history::{IssueCommit, issue_commits};
+use crate::cmd::util::user_handle_me;
+use crate::model::{Users, issue_tmp_dir, load_settings_or_default};
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+/// Watched issues of a user which changed since a commit
+pub struct Inbox {
+    pub user: String,
+    /// Commit the inbox was read at, the next last-seen marker
+    pub tip: String,
+    /// Last-seen commit or `since`, `None`: all changes
+    pub since: Option<String>,
+    /// Most recently changed issues first
+    pub items: Vec<InboxItem>,
+}
+
+pub struct InboxItem {
+    pub id: u32,
+    pub title: String,
+    pub state: String,
+    /// Commits of other users, oldest first
+    pub changes: Vec<IssueCommit>,
+}
+
+/// Watched issues with changes of other users since the last look, from the git history
+/// - storage: where to read the issues from (working tree, git revision, ...); uncommitted changes are ignored
+/// - user: watcher, 'me' for settings.yaml:user
+/// - since: show the changes after this revision [default: last-seen commit of the user, see `inbox_mark_seen`]
+/// - Changes of the watchers themselves (`watch`, `unwatch`) are skipped
+pub fn inbox(storage: &dyn Storage, user: &str, since: Option<&str>) -> Cmd<Inbox> {
+    let (settings, infos) = load_settings_or_default()?;
+    let users = storage.load_users()?;
+
+    let mut user = user.to_string();
+    user_handle_me(&users, &settings, &mut user)?;
+
+    if let Some(found) = users.find(&user) {
+        user = found.id.clone();
+    }
+
+    if user.is_empty() {
+        return Err("No user: set settings.yaml::user or use --user".to_string());
+    }
+
+    let Some(git) = storage.history() else {
+        return Err("No git history available: the inbox is based on commits".to_string());
+    };
+
+    let tip = git.resolve_rev()?;
+
+    let since = match since {
+        Some(since) => Some(git.at(since).resolve_rev()?),
+        // A marker which is no longer known (e.g. after a rebase and gc) shows all changes
+        None => inbox_seen(&user)?.and_then(|seen| git.at(&seen).resolve_rev().ok()),
+    };
+
+    let mut changes: BTreeMap<u32, Vec<IssueCommit>> = BTreeMap::new();
+
+    for commit in issue_commits(&git, &tip, since.as_deref())? {
+        if matches!(commit.action.as_str(), "watch" | "unwatch") || is_own_commit(&users, &user, &commit) {
+            continue;
+        }
+
+        for id in &commit.ids {
+            changes.entry(*id).or_default().push(commit.clone());
+        }
+    }
+
+    let mut items: Vec<InboxItem> = storage
+        .load_metas()?
+        .into_iter()
+        .filter(|meta| meta.watchers.contains(&user))
+        .filter_map(|meta| {
+            let changes = changes.remove(&meta.id)?;
+
+            Some(InboxItem {
+                id: meta.id,
+                title: meta.title,
+                state: meta.state,
+                changes,
+            })
+        })
+        .collect();
+
+    items.sort_by(|a, b| last_time(b).cmp(last_time(a)).then(b.id.cmp(&a.id)));
+
+    Ok(CmdResult {
+        value: Inbox { user, tip, since, items },
+        infos,
+    })
+}
+
+impl InboxItem {
+    /// Most recent change
+    pub fn last(&self) -> Option<&IssueCommit> {
+        self.changes.last()
+    }
+}
+
+fn last_time(item: &InboxItem) -> &str {
+    item.last().map_or("", |commit| commit.time.as_str())
+}
+
+/// Commit authored by the user: author email in users.yaml:users:emails, or author name as ID, name or alias
+fn is_own_commit(users: &Users, user: &str, commit: &IssueCommit) -> bool {
+    let by_email = users.find(&commit.author_email).is_some_and(|u| u.id == user);
+
+    let by_name = users.get(user).is_some_and(|u| {
+        let name = &commit.author_name;
+        *name == u.id || (!u.name.is_empty() && *name == u.name) || u.aliases.contains(name)
+    });
+
+    by_email || by_name
+}
+
+/// Last-seen commit of a user, `None` if the inbox was never marked as seen
+pub fn inbox_seen(user: &str) -> Result<Option<String>, String> {
+    Ok(load_markers()?.remove(user))
+}
+
+/// Remember a commit as last seen by the user (local, not version-controlled)
+pub fn inbox_mark_seen(user: &str, commit: &str) -> Result<(), String> {
+    let mut markers = load_markers()?;
+    markers.insert(user.to_string(), commit.to_string());
+
+    let path = markers_path()?;
+    let yaml = serde_yaml::to_string(&markers).map_err(|_| "Failed to serialize inbox markers".to_string())?;
+
+    fs::create_dir_all(issue_tmp_dir()?).map_err(|e| format!("Failed to create .tmp directory: {e}"))?;
+    fs::write(&path, yaml).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Last-seen commit per user in `.gitissues/.tmp/inbox.yaml`
+fn load_markers() -> Result<BTreeMap<String, String>, String> {
+    // No local .gitissues (e.g. web server on a bare repository): nothing seen yet
+    let Ok(path) = markers_path() else {
+        return Ok(BTreeMap::new());
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_yaml::from_str(&raw).map_err(|e| format!("{} malformatted: {e}", path.display())),
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
+
+fn markers_path() -> Result<PathBuf, String> {
+    Ok(issue_tmp_dir()?.join("inbox.yaml"))
+}
@@ -1,8 +1,10 @@
 use std::fs;
 use std::path::PathBuf;
 
+use crate::cmd::milestone::DEFAULT_MILESTONES;
 use crate::cmd::util::git_commit_non_templated;
-use crate::model::{config_path, create_settings_if_missing, gitissues_base, users_path};
+use crate::model::{config_path, create_settings_if_missing, gitissues_base, milestones_path, users_path};
+use crate::trailers::Trailers;
 use crate::{Cmd, CmdResult};
 
 pub fn init(no_commit: bool) -> Cmd<()> {
@@ -30,13 +32,24 @@ pub fn init(no_commit: bool) -> Cmd<()> {
     let users_dst = users_path()?;
     fs::write(&users_dst, DEFAULT_USERS).map_err(|e| format!("Failed to write default users to {}: {e}", users_dst.display()))?;
 
+    // Copy default milestones file
+    let milestones_dst = milestones_path()?;
+    fs::write(&milestones_dst, DEFAULT_MILESTONES)
+        .map_err(|e| format!("Failed to write default milestones to {}: {e}", milestones_dst.display()))?;
+
     // Copy default description file
     const DEFAULT_DESC: &str = include_str!("../../config/description-default.md");
     let desc_dst = gitissues_base()?.join("description.md");
     fs::write(&desc_dst, DEFAULT_DESC).map_err(|e| format!("Failed to write default description to {}: {e}", desc_dst.display()))?;
 
+    // Copy default changelog template
+    const DEFAULT_CHANGELOG: &str = include_str!("../../config/changelog-default.md");
+    let changelog_dst = gitissues_base()?.join("changelog.md");
+    fs::write(&changelog_dst, DEFAULT_CHANGELOG)
+        .map_err(|e| format!("Failed to write default changelog template to {}: {e}", changelog_dst.display()))?;
+
     if !no_commit {
-        let infos_commit = git_commit_non_templated("init")?;
+        let infos_commit = git_commit_non_templated("init", Trailers::new("init", &[]))?;
         infos.extend(infos_commit);
         return Ok(CmdResult { value: (), infos });
     }
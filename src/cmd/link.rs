@@ -1,7 +1,14 @@
-use std::fs;
+use indexmap::IndexMap;
 
+use crate::cmd::graph::{find_cycle, kind_edges, kind_relationships};
 use crate::cmd::util::{git_commit, load_meta};
-use crate::model::{Config, RelationshipLink, current_timestamp, issue_dir, issue_meta_path, issue_title, load_config};
+use crate::journal;
+use crate::model::{
+    Config, Meta, RelationshipKind, RelationshipLink, current_timestamp, issue_dir, issue_meta_path, issue_title, load_config,
+};
+use crate::storage::{FsStorage, Storage};
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
 use crate::{Cmd, CmdResult};
 
 enum Action {
@@ -30,23 +37,51 @@ pub fn link(id: u32, add: Option<Vec<RelationshipLink>>, remove: Option<Vec<Rela
         validate_relationships(id, relationship, &config)?;
     }
 
-    // Process relationships
+    let relationships: Vec<String> = add.iter().chain(remove.iter()).flatten().map(|r| r.relationship.clone()).collect();
+    let added: Vec<String> = add.iter().flatten().map(|r| r.relationship.clone()).collect();
+
+    // Process relationships (in memory only, nothing is written unless all of them succeed)
+
+    let mut metas = IndexMap::new();
 
     if let Some(relationship) = add {
         for relationship in relationship {
-            update_relationship(Action::Add, id, &relationship, &config)?;
+            update_relationship(Action::Add, id, &relationship, &config, &mut metas)?;
         }
     }
 
     if let Some(relationship) = remove {
         for relationship in relationship {
-            update_relationship(Action::Remove, id, &relationship, &config)?;
+            update_relationship(Action::Remove, id, &relationship, &config, &mut metas)?;
+        }
+    }
+
+    // Reject cycles of relationships with semantics (dependency, hierarchy)
+    check_cycles(id, &added, &config, &metas)?;
+
+    // Save updated meta files at once
+
+    let mut transaction = Transaction::new();
+    let mut dirs = Vec::new();
+    let mut ids = Vec::new();
+
+    for (meta, meta_updated) in metas.values() {
+        if meta_updated != meta {
+            transaction.write_meta(meta_updated)?;
+            dirs.push(issue_dir(meta_updated.id)?);
+            ids.push(meta_updated.id);
         }
     }
 
+    let applied = transaction.apply()?;
+
+    let ids_joined = ids.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(",");
+    journal::record(&format!("links updated {ids_joined}"), &applied)?;
+
     let title = issue_title(id)?;
+    let trailers = Trailers::new("link", &ids).with_fields(&relationships);
 
-    let infos = git_commit(id, title, "links updated")?;
+    let infos = git_commit(id, title, "links updated", trailers, &dirs)?;
 
     Ok(CmdResult { value: (), infos })
 }
@@ -89,20 +124,67 @@ fn check_target_ids(id: u32, target_ids: &Vec<u32>) -> Result<(), String> {
     Ok(())
 }
 
-fn update_relationship(action: Action, id: u32, relationship: &RelationshipLink, config: &Config) -> Result<(), String> {
-    let current_timestamp = current_timestamp();
+/// Check that the added relationships do not create a cycle of a relationship kind
+/// Any new cycle has to pass through the issue being linked
+fn check_cycles(id: u32, added: &[String], config: &Config, metas: &IndexMap<u32, (Meta, Meta)>) -> Result<(), String> {
+    for kind in [RelationshipKind::Dependency, RelationshipKind::Hierarchy] {
+        let relationships = kind_relationships(config, kind)?;
+
+        let affected = relationships
+            .iter()
+            .any(|(name, link)| added.contains(name) || link.as_ref().is_some_and(|l| added.contains(l)));
 
-    let meta_path = issue_meta_path(id)?;
-    let meta = load_meta(&meta_path)?;
+        if !affected {
+            continue;
+        }
+
+        // All issues as they would be after the update
+        let mut all_metas = FsStorage::discover()?.load_metas()?;
+        for meta in all_metas.iter_mut() {
+            if let Some((_, updated)) = metas.get(&meta.id) {
+                *meta = updated.clone();
+            }
+        }
+
+        let edges = kind_edges(config, kind, &all_metas)?;
 
-    let mut meta_updated = meta.clone();
+        if let Some(cycle) = find_cycle(&edges, id) {
+            let cycle = cycle.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(" -> ");
+            return Err(format!("Rejected: the link would create a {kind} cycle: {cycle}"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the in-memory copy of the meta of an issue, loading it on first access
+/// (meta.yaml as loaded, meta.yaml with updates)
+fn loaded_meta(metas: &mut IndexMap<u32, (Meta, Meta)>, id: u32) -> Result<&mut Meta, String> {
+    if !metas.contains_key(&id) {
+        let meta = load_meta(&issue_meta_path(id)?)?;
+        metas.insert(id, (meta.clone(), meta));
+    }
+
+    metas
+        .get_mut(&id)
+        .map(|(_, updated)| updated)
+        .ok_or_else(|| format!("Failed to load meta.yaml of #{id}"))
+}
+
+fn update_relationship(
+    action: Action,
+    id: u32,
+    relationship: &RelationshipLink,
+    config: &Config,
+    metas: &mut IndexMap<u32, (Meta, Meta)>,
+) -> Result<(), String> {
+    let current_timestamp = current_timestamp();
 
     // update relationships of ID
 
-    let relationship_category = meta_updated
-        .relationships
-        .entry(relationship.relationship.clone())
-        .or_insert_with(Vec::new);
+    let meta = loaded_meta(metas, id)?;
+
+    let relationship_category = meta.relationships.entry(relationship.relationship.clone()).or_insert_with(Vec::new);
 
     let mut updated = false;
     for target_id in &relationship.target_ids {
@@ -122,15 +204,13 @@ fn update_relationship(action: Action, id: u32, relationship: &RelationshipLink,
         }
     }
 
-    if updated {
-        meta_updated.updated = current_timestamp.clone();
+    if !updated {
+        return Err("No changes made to relationships".to_string());
     }
 
-    // update relationships of target ID
+    meta.updated = current_timestamp.clone();
 
-    let mut target_metas = Vec::new();
-    let mut target_metas_paths = Vec::new();
-    let mut target_metas_updated = Vec::new();
+    // update relationships of target ID
 
     if let Some(rel_config) = config.relationships.get(&relationship.relationship)
         && let Some(link) = &rel_config.link
@@ -138,56 +218,27 @@ fn update_relationship(action: Action, id: u32, relationship: &RelationshipLink,
         // link is the name of the relationship that needs to be updated for the target IDs
 
         for target_id in &relationship.target_ids {
-            let target_meta_path = issue_meta_path(*target_id)?;
-            let target_meta = load_meta(&target_meta_path)?;
+            let target_meta = loaded_meta(metas, *target_id)?;
 
-            let mut target_meta_updated = target_meta.clone();
-
-            let relationship_category = target_meta_updated.relationships.entry(link.clone()).or_insert_with(Vec::new);
+            let relationship_category = target_meta.relationships.entry(link.clone()).or_insert_with(Vec::new);
 
             match action {
                 Action::Add => {
                     if !relationship_category.contains(&id) {
                         relationship_category.push(id);
 
-                        target_meta_updated.updated = current_timestamp.clone();
+                        target_meta.updated = current_timestamp.clone();
                     }
                 }
                 Action::Remove => {
                     if relationship_category.contains(&id) {
                         relationship_category.retain(|x| x != &id);
-                        target_meta_updated.updated = current_timestamp.clone();
+                        target_meta.updated = current_timestamp.clone();
                     }
                 }
             }
-
-            target_metas.push(target_meta);
-            target_metas_updated.push(target_meta_updated);
-            target_metas_paths.push(target_meta_path);
         }
     }
 
-    // Save updated meta files
-
-    if meta_updated != meta {
-        let updated_yaml = serde_yaml::to_string(&meta_updated).map_err(|_| "Failed to serialize meta.yaml".to_string())?;
-
-        fs::write(&meta_path, updated_yaml).map_err(|_| "Failed to write meta.yaml".to_string())?;
-    } else {
-        return Err("No changes made to relationships".to_string());
-    }
-
-    for ((target_meta, target_meta_updated), target_meta_path) in target_metas.into_iter().zip(target_metas_updated).zip(target_metas_paths)
-    {
-        if target_meta_updated == target_meta {
-            continue;
-        }
-
-        let updated_target_yaml =
-            serde_yaml::to_string(&target_meta_updated).map_err(|_| "Failed to serialize target meta.yaml".to_string())?;
-
-        fs::write(&target_meta_path, updated_target_yaml).map_err(|_| "Failed to write target meta.yaml".to_string())?;
-    }
-
     Ok(())
 }
@@ -1,22 +1,41 @@
 use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::collections::HashMap;
-use std::fs;
 use std::str::FromStr;
 
 use regex::Regex;
 
 use crate::model::{
-    Config, Filter, Meta, Operator, Priority, Settings, Sorting, Users, issue_desc_path, issues_dir, load_config, load_settings, load_users,
+    Config, Filter, Meta, Milestones, Operator, Priority, Settings, Sorting, Users, format_duration, load_settings_or_default,
+    parse_duration,
 };
+use crate::storage::Storage;
 use crate::{Cmd, CmdResult};
 
-use crate::cmd::util::{dash_if_empty, load_description, load_meta, user_handle_me};
+use crate::cmd::graph::{blocked_issues, children_of, is_done};
+use crate::cmd::util::{dash_if_empty, user_handle_me};
+
+/// Columns which are not stored in meta.yaml but computed from all issues
+/// - blocked: issue has an unresolved blocker (relationship of kind `dependency`)
+/// - children: number of children (relationship of kind `hierarchy`)
+/// - children_closed: percentage of children in config.yaml:states_done
+/// - children_due: earliest due_date of the children
+/// - spent: total time of the work log
+/// - remaining: estimate minus spent, at least 0m
+const COMPUTED_COLUMNS: [&str; 6] = ["blocked", "children", "children_closed", "children_due", "spent", "remaining"];
+
+/// Computed columns which need the work logs of all issues
+const WORKLOG_COLUMNS: [&str; 2] = ["spent", "remaining"];
+
+/// Values of the computed columns per issue: {ID: {column: value, ...}}
+type Computed = HashMap<u32, HashMap<String, String>>;
 
 // (ID, {column: value, ...})
 pub struct IssueData {
     pub id: u32,
     pub data: HashMap<String, String>,
+    /// Nesting level in the hierarchy tree, always 0 for a flat list
+    pub depth: usize,
 }
 
 pub struct ListResult {
@@ -25,18 +44,37 @@ pub struct ListResult {
 }
 
 /// List issues with optional columns, filters, and sorting
+/// - storage: where to read the issues from (working tree, git revision, ...)
 /// - columns: list of columns to print (None means default from config)
 /// - filter: list of filters to be applied
 /// - sort: list of sortings to be applied
-pub fn list(columns: Option<Vec<String>>, filter: Option<Vec<Filter>>, sort: Option<Vec<Sorting>>) -> Cmd<ListResult> {
-    let config = load_config()?;
-    let (settings, infos) = load_settings()?;
+pub fn list(
+    storage: &dyn Storage,
+    columns: Option<Vec<String>>,
+    filter: Option<Vec<Filter>>,
+    sort: Option<Vec<Sorting>>,
+) -> Cmd<ListResult> {
+    let config = storage.load_config()?;
+    let (settings, infos) = load_settings_or_default()?;
+
+    let milestones = storage.load_milestones()?;
 
-    let mut issues = get_issues_metadata()?;
+    let mut issues = storage.load_metas()?;
 
-    sort_issues(&config, &mut issues, sort)?;
+    // Work logs are only read if needed, as every entry is a separate file
+    let with_worklog = columns
+        .as_ref()
+        .unwrap_or(&config.list_columns)
+        .iter()
+        .chain(filter.iter().flatten().map(|f| &f.field))
+        .chain(sort.iter().flatten().map(|s| &s.field))
+        .any(|field| field == "*" || WORKLOG_COLUMNS.contains(&field.as_str()));
 
-    filter_issues(&config, &settings, &mut issues, filter)?;
+    let computed = compute_columns(storage, &config, &issues, with_worklog)?;
+
+    sort_issues(&config, &milestones, &computed, &mut issues, sort)?;
+
+    filter_issues(storage, &config, &settings, &computed, &mut issues, filter)?;
 
     let mut cols = match &columns {
         Some(value) => value.clone(),
@@ -59,11 +97,18 @@ pub fn list(columns: Option<Vec<String>>, filter: Option<Vec<Filter>>, sort: Opt
         let mut map = HashMap::new();
 
         for col in &cols {
-            let value = get_column_value(col, &meta)?;
+            let value = match computed_value(&computed, col, &meta) {
+                Some(value) => value.to_string(),
+                None => get_column_value(col, &meta)?,
+            };
             map.insert(col.clone(), value);
         }
 
-        issues_data.push(IssueData { id: meta.id, data: map });
+        issues_data.push(IssueData {
+            id: meta.id,
+            data: map,
+            depth: 0,
+        });
     }
 
     Ok(CmdResult {
@@ -75,40 +120,57 @@ pub fn list(columns: Option<Vec<String>>, filter: Option<Vec<Filter>>, sort: Opt
     })
 }
 
-fn get_issues_metadata() -> Result<Vec<Meta>, String> {
-    let path = issues_dir()?;
-    let mut issues: Vec<Meta> = Vec::new();
-
-    // Precondition: .gitissues/issues must exist (user must run init first)
-    if !path.exists() {
-        return Err("Not initialized: .gitissues/issues does not exist. Run `git issue init` first.".to_string());
-    }
+/// Replace the user IDs of the columns `reporter`, `assignee` and `watchers` with the display names of users.yaml
+/// Not for CSV: the IDs are needed for the round-trip via `import --csv`
+pub fn display_names(list: &mut ListResult, users: &Users) {
+    for issue in &mut list.issues {
+        for col in ["reporter", "assignee"] {
+            if let Some(value) = issue.data.get_mut(col) {
+                *value = users.display_name(value);
+            }
+        }
 
-    for entry in fs::read_dir(path).map_err(|e| format!("Failed to read issues directory: {e}"))? {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
-        if !entry.file_type().map_err(|e| format!("Failed to read file type: {e}"))?.is_dir() {
-            continue;
+        if let Some(value) = issue.data.get_mut("watchers") {
+            *value = value.split(',').map(|id| users.display_name(id)).collect::<Vec<_>>().join(",");
         }
+    }
+}
 
-        let dir_name = entry.file_name();
-        let name_str = dir_name.to_string_lossy().to_string();
+/// RFC 4180 CSV of a list: header row with the column names, then one row per issue
+/// Fields are quoted only if needed, embedded quotes are doubled, records end with CRLF
+/// - separator: settings.yaml:export_csv_separator
+pub fn list_csv(list: &ListResult, separator: char) -> Result<String, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(csv_separator(separator)?)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+
+    writer
+        .write_record(&list.columns)
+        .map_err(|e| format!("Failed to write CSV: {e}"))?;
+
+    for issue in &list.issues {
+        let record = list.columns.iter().map(|col| issue.data.get(col).map(String::as_str).unwrap_or(""));
+        writer.write_record(record).map_err(|e| format!("Failed to write CSV: {e}"))?;
+    }
 
-        // Try to parse the directory name as a u32 (issue ID)
-        if name_str.parse::<u32>().is_err() {
-            continue; // skip non-numeric directories
-        }
+    let bytes = writer.into_inner().map_err(|e| format!("Failed to write CSV: {e}"))?;
 
-        // Load meta.yaml
-        let meta_path = entry.path().join("meta.yaml");
-        let meta = load_meta(&meta_path)?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {e}"))
+}
 
-        issues.push(meta);
+/// settings.yaml:export_csv_separator as a single byte
+pub(crate) fn csv_separator(separator: char) -> Result<u8, String> {
+    match u8::try_from(separator) {
+        Ok(byte) if separator.is_ascii() && separator != '"' && separator != '\n' && separator != '\r' => Ok(byte),
+        _ => Err(format!(
+            "Invalid settings.yaml:export_csv_separator '{separator}': must be a single ASCII character other than '\"'"
+        )),
     }
-
-    Ok(issues)
 }
 
-fn get_all_column_names(config: &Config) -> Vec<String> {
+/// Names of all columns: meta fields, relationships of config.yaml and computed columns
+pub fn get_all_column_names(config: &Config) -> Vec<String> {
     let mut columns = vec![
         "id".to_string(),
         "title".to_string(),
@@ -119,15 +181,93 @@ fn get_all_column_names(config: &Config) -> Vec<String> {
         "assignee".to_string(),
         "priority".to_string(),
         "due_date".to_string(),
+        "milestone".to_string(),
+        "estimate".to_string(),
+        "watchers".to_string(),
     ];
 
     columns.extend(config.relationships.keys().cloned().collect::<Vec<String>>());
 
+    columns.extend(COMPUTED_COLUMNS.iter().map(|c| c.to_string()));
+
     columns.extend(vec!["created".to_string(), "updated".to_string()]);
 
     columns
 }
 
+fn compute_columns(storage: &dyn Storage, config: &Config, issues: &[Meta], with_worklog: bool) -> Result<Computed, String> {
+    let blocked = blocked_issues(config, issues)?;
+    let children = children_of(config, issues)?;
+    let metas: HashMap<u32, &Meta> = issues.iter().map(|m| (m.id, m)).collect();
+
+    // Total minutes of the work log per issue, `None` without entries
+    let mut spent: HashMap<u32, Option<u32>> = HashMap::new();
+
+    if with_worklog {
+        for meta in issues {
+            let mut total = None;
+
+            for entry in storage.load_worklog(meta.id)? {
+                total = Some(total.unwrap_or(0) + parse_duration(&entry.spent)?);
+            }
+
+            spent.insert(meta.id, total);
+        }
+    }
+
+    Ok(issues
+        .iter()
+        .map(|meta| {
+            let is_blocked = if blocked.contains(&meta.id) { "yes" } else { "no" };
+
+            let children: Vec<&Meta> = children
+                .get(&meta.id)
+                .into_iter()
+                .flatten()
+                .filter_map(|id| metas.get(id).copied())
+                .collect();
+
+            let closed = match children.len() {
+                0 => "-".to_string(),
+                len => {
+                    let done = children.iter().filter(|c| is_done(config, &c.state)).count();
+                    format!("{}%", done * 100 / len)
+                }
+            };
+
+            let due = children
+                .iter()
+                .map(|c| c.due_date.as_str())
+                .filter(|d| !d.is_empty())
+                .min()
+                .unwrap_or("-");
+
+            let mut values = HashMap::from([
+                ("blocked".to_string(), is_blocked.to_string()),
+                ("children".to_string(), children.len().to_string()),
+                ("children_closed".to_string(), closed),
+                ("children_due".to_string(), due.to_string()),
+            ]);
+
+            if let Some(spent) = spent.get(&meta.id) {
+                let remaining = match parse_duration(&meta.estimate) {
+                    Ok(estimate) if !meta.estimate.is_empty() => format_duration(estimate.saturating_sub(spent.unwrap_or(0))),
+                    _ => "-".to_string(),
+                };
+
+                values.insert("spent".to_string(), spent.map_or("-".to_string(), format_duration));
+                values.insert("remaining".to_string(), remaining);
+            }
+
+            (meta.id, values)
+        })
+        .collect())
+}
+
+fn computed_value<'a>(computed: &'a Computed, col: &str, meta: &Meta) -> Option<&'a str> {
+    computed.get(&meta.id).and_then(|values| values.get(col)).map(|v| v.as_str())
+}
+
 fn validate_column_names(config: &Config, columns: &mut [String], context: &str) -> Result<(), String> {
     for col in columns.iter_mut() {
         // normalize aliases
@@ -155,7 +295,14 @@ fn wildcard_expansion(config: &Config, columns: &mut Vec<String>) {
     }
 }
 
-fn filter_issues(config: &Config, settings: &Settings, issues: &mut Vec<Meta>, filters: Option<Vec<Filter>>) -> Result<(), String> {
+fn filter_issues(
+    storage: &dyn Storage,
+    config: &Config,
+    settings: &Settings,
+    computed: &Computed,
+    issues: &mut Vec<Meta>,
+    filters: Option<Vec<Filter>>,
+) -> Result<(), String> {
     if let Some(mut filters) = filters {
         // Validate all filter fields
         let mut filter_fields: Vec<String> = filters.iter().map(|f| f.field.clone()).collect();
@@ -168,12 +315,12 @@ fn filter_issues(config: &Config, settings: &Settings, issues: &mut Vec<Meta>, f
 
         validate_filters(&filters)?;
 
-        let users = load_users()?;
+        let users = storage.load_users()?;
 
         // Apply filters
         issues.retain(|meta| {
             filters.iter().all(|filter| match filter.operator {
-                Operator::Eq => filter_eq(filter, meta, settings, &users),
+                Operator::Eq => filter_eq(storage, filter, meta, settings, &users, computed),
                 Operator::Gt => filter_gt(filter, meta).unwrap_or(false),
                 Operator::Lt => filter_lt(filter, meta).unwrap_or(false),
             })
@@ -214,7 +361,11 @@ fn validate_filters(filters: &[Filter]) -> Result<(), String> {
     Ok(())
 }
 
-fn filter_eq(filter: &Filter, meta: &Meta, settings: &Settings, users: &Users) -> bool {
+fn filter_eq(storage: &dyn Storage, filter: &Filter, meta: &Meta, settings: &Settings, users: &Users, computed: &Computed) -> bool {
+    if let Some(value) = computed_value(computed, &filter.field, meta) {
+        return do_strings_match(value, &filter.value);
+    }
+
     match filter.field.as_str() {
         "id" => do_strings_match(&meta.id.to_string(), &filter.value),
         "title" => do_strings_match(&meta.title, &filter.value),
@@ -225,9 +376,12 @@ fn filter_eq(filter: &Filter, meta: &Meta, settings: &Settings, users: &Users) -
         "reporter" => do_strings_match_with_me(&meta.reporter, &filter.value, settings, users),
         "priority" => do_strings_match(&format!("{:?}", meta.priority).replace("-", ""), &filter.value),
         "due_date" => do_strings_match(&meta.due_date, &filter.value),
+        "milestone" => do_strings_match(&meta.milestone, &filter.value),
+        "estimate" => do_strings_match(&meta.estimate, &filter.value),
+        "watchers" => is_in_user_list(&meta.watchers, &filter.value, settings, users),
         "created" => do_strings_match(&meta.created, &filter.value),
         "updated" => do_strings_match(&meta.updated, &filter.value),
-        "description" => does_description_match(meta.id, &filter.value),
+        "description" => does_description_match(storage, meta.id, &filter.value),
         relationship => {
             if let Some(ids) = meta.relationships.get(relationship) {
                 is_in_u32_list(ids, &filter.value)
@@ -294,19 +448,34 @@ fn do_strings_match(value: &str, pattern: &str) -> bool {
     false
 }
 
+/// Like `do_strings_match` for users: 'me' is settings.yaml:user, '@team' all members of the team, aliases/emails their user
 fn do_strings_match_with_me(value: &str, pattern: &str, settings: &Settings, users: &Users) -> bool {
     let mut pattern_me_replaced = Vec::new();
 
     for word in pattern.split(',') {
-        let mut word = word.to_string();
+        let mut word = word.trim().to_string();
         match user_handle_me(users, settings, &mut word) {
             Ok(_) => { /* continue */ }
             Err(_) => return false,
         }
 
+        if let Some(team) = word.strip_prefix('@') {
+            pattern_me_replaced.extend(users.team_members(team).into_iter().map(String::from));
+            continue;
+        }
+
+        if let Some(user) = users.find(&word) {
+            word = user.id.clone();
+        }
+
         pattern_me_replaced.push(word);
     }
 
+    // A team without members matches nothing
+    if pattern_me_replaced.is_empty() {
+        return false;
+    }
+
     do_strings_match(value, pattern_me_replaced.join(",").as_str())
 }
 
@@ -319,6 +488,15 @@ fn is_in_str_list(list: &[String], pattern: &str) -> bool {
     list.iter().any(|str| do_strings_match(str, pattern))
 }
 
+/// Check if pattern matches any user in the list, with 'me', aliases and teams
+fn is_in_user_list(list: &[String], pattern: &str, settings: &Settings, users: &Users) -> bool {
+    if pattern.is_empty() && list.is_empty() {
+        return true;
+    }
+
+    list.iter().any(|user| do_strings_match_with_me(user, pattern, settings, users))
+}
+
 /// Check if pattern matches any u32 in the list
 fn is_in_u32_list(list: &[u32], pattern: &str) -> bool {
     if pattern.is_empty() && list.is_empty() {
@@ -328,13 +506,8 @@ fn is_in_u32_list(list: &[u32], pattern: &str) -> bool {
     list.iter().any(|id| do_strings_match(&id.to_string(), pattern))
 }
 
-fn does_description_match(id: u32, pattern: &str) -> bool {
-    let description_path = match issue_desc_path(id) {
-        Ok(value) => value,
-        Err(_) => return false,
-    };
-
-    let description = match load_description(description_path.as_path()) {
+fn does_description_match(storage: &dyn Storage, id: u32, pattern: &str) -> bool {
+    let description = match storage.load_description(id) {
         Ok(value) => value,
         Err(_) => return false,
     };
@@ -342,7 +515,22 @@ fn does_description_match(id: u32, pattern: &str) -> bool {
     do_strings_match(&description, pattern)
 }
 
-fn sort_issues(config: &Config, issues: &mut [Meta], sorts: Option<Vec<Sorting>>) -> Result<(), String> {
+fn sort_issues(
+    config: &Config,
+    milestones: &Milestones,
+    computed: &Computed,
+    issues: &mut [Meta],
+    sorts: Option<Vec<Sorting>>,
+) -> Result<(), String> {
+    // Milestones in the order of milestones.yaml, unknown ones after them, empty first
+    let milestone_rank = |meta: &Meta| {
+        let position = milestones.milestones.iter().position(|m| m.name == meta.milestone);
+        (!meta.milestone.is_empty()).then(|| position.unwrap_or(milestones.milestones.len()))
+    };
+
+    // Durations in minutes, none ('' or '-') first
+    let duration = |value: Option<&str>| value.and_then(|v| parse_duration(v).ok());
+
     if let Some(mut sorts) = sorts {
         // Validate all sort fields
         let mut sort_fields: Vec<String> = sorts.iter().map(|s| s.field.clone()).collect();
@@ -365,8 +553,15 @@ fn sort_issues(config: &Config, issues: &mut [Meta], sorts: Option<Vec<Sorting>>
                     "assignee" => a.assignee.cmp(&b.assignee),
                     "priority" => a.priority.as_int().cmp(&b.priority.as_int()),
                     "due_date" => a.due_date.cmp(&b.due_date),
+                    "milestone" => milestone_rank(a).cmp(&milestone_rank(b)).then(a.milestone.cmp(&b.milestone)),
+                    "estimate" => duration(Some(&a.estimate)).cmp(&duration(Some(&b.estimate))),
+                    "watchers" => a.watchers.cmp(&b.watchers),
                     "created" => a.created.cmp(&b.created),
                     "updated" => a.updated.cmp(&b.updated),
+                    col if WORKLOG_COLUMNS.contains(&col) => {
+                        duration(computed_value(computed, col, a)).cmp(&duration(computed_value(computed, col, b)))
+                    }
+                    col if COMPUTED_COLUMNS.contains(&col) => computed_value(computed, col, a).cmp(&computed_value(computed, col, b)),
                     relationship => {
                         if let Some(a_ids) = a.relationships.get(relationship) {
                             if let Some(b_ids) = b.relationships.get(relationship) {
@@ -408,7 +603,7 @@ fn get_relationship_value(col: &str, meta: &Meta) -> String {
     }
 }
 
-fn get_column_value(col: &str, meta: &Meta) -> Result<String, String> {
+pub(crate) fn get_column_value(col: &str, meta: &Meta) -> Result<String, String> {
     match col {
         "id" => Ok(meta.id.to_string()),
         "title" => Ok(meta.title.clone()),
@@ -419,6 +614,9 @@ fn get_column_value(col: &str, meta: &Meta) -> Result<String, String> {
         "assignee" => Ok(dash_if_empty(&meta.assignee)),
         "priority" => Ok(format!("{:?}", meta.priority)),
         "due_date" => Ok(dash_if_empty(&meta.due_date)),
+        "milestone" => Ok(dash_if_empty(&meta.milestone)),
+        "estimate" => Ok(dash_if_empty(&meta.estimate)),
+        "watchers" => Ok(dash_if_empty(&meta.watchers.join(","))),
         "created" => Ok(meta.created.clone()),
         "updated" => Ok(meta.updated.clone()),
         _ => Ok(get_relationship_value(col, meta)),
@@ -0,0 +1,210 @@
+use chrono::{Local, NaiveDate};
+
+use crate::cmd::graph::is_done;
+use crate::cmd::util::run_git;
+use crate::journal;
+use crate::model::{
+    Config, Meta, Milestone, MilestoneState, Milestones, is_valid_iso_date, is_valid_milestone, load_config, load_milestones,
+    milestones_path,
+};
+use crate::storage::Storage;
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
+use crate::{Cmd, CmdResult};
+
+pub(crate) const DEFAULT_MILESTONES: &str = include_str!("../../config/milestones-default.yaml");
+
+/// Milestone with the progress of its issues
+pub struct MilestoneProgress {
+    pub milestone: Milestone,
+    /// IDs of the issues of the milestone, sorted
+    pub issues: Vec<u32>,
+    /// Number of issues in config.yaml:states_done
+    pub done: usize,
+    /// Open issues past their due_date, or past the due date of the milestone if they have none
+    pub overdue: Vec<u32>,
+}
+
+impl MilestoneProgress {
+    pub fn total(&self) -> usize {
+        self.issues.len()
+    }
+
+    /// Done issues in percent, 0 for an empty milestone
+    pub fn percent(&self) -> usize {
+        match self.total() {
+            0 => 0,
+            total => self.done * 100 / total,
+        }
+    }
+}
+
+/// Create a milestone in milestones.yaml
+/// - name: unique, must not contain ',' or '*' (used in `list --filter`)
+/// - start, due: YYYY-MM-DD or ''
+pub fn milestone_new(name: String, start: Option<String>, due: Option<String>, description: Option<String>) -> Cmd<()> {
+    let mut milestones = load_milestones()?;
+
+    let name = name.trim().to_string();
+
+    if name.is_empty() || name == "-" || name.contains(',') || name.contains('*') {
+        return Err(format!(
+            "Invalid milestone name \"{name}\": must not be empty, '-' or contain ',' or '*'"
+        ));
+    }
+
+    if milestones.get(&name).is_some() {
+        return Err(format!("Milestone \"{name}\" already exists."));
+    }
+
+    let start = start.unwrap_or_default();
+    let due = due.unwrap_or_default();
+
+    for (field, value) in [("start", &start), ("due", &due)] {
+        if !is_valid_iso_date(value)? {
+            return Err(format!("Invalid {field} format: Use 'YYYY-MM-DD' or ''"));
+        }
+    }
+
+    if !start.is_empty() && !due.is_empty() && start > due {
+        return Err(format!("Invalid range: start {start} is after due {due}"));
+    }
+
+    milestones.milestones.push(Milestone {
+        name: name.clone(),
+        start,
+        due,
+        description: description.unwrap_or_default(),
+        state: MilestoneState::Open,
+    });
+
+    let infos = write_milestones(&milestones, "new", &name)?;
+
+    Ok(CmdResult { value: (), infos })
+}
+
+/// Close a milestone: its issues keep the milestone, but no issues can be added anymore
+pub fn milestone_close(name: &str) -> Cmd<()> {
+    let mut milestones = load_milestones()?;
+
+    let Some(milestone) = milestones.milestones.iter_mut().find(|m| m.name == name) else {
+        return Err(format!("Not available: Milestone \"{name}\" does not exist."));
+    };
+
+    if milestone.state == MilestoneState::Closed {
+        return Err(format!("Milestone \"{name}\" is already closed."));
+    }
+
+    milestone.state = MilestoneState::Closed;
+
+    let infos = write_milestones(&milestones, "close", name)?;
+
+    Ok(CmdResult { value: (), infos })
+}
+
+/// All milestones in the order of milestones.yaml, with the progress of their issues
+/// - storage: where to read the milestones and issues from (working tree, git revision, ...)
+pub fn milestone_list(storage: &dyn Storage) -> Cmd<Vec<MilestoneProgress>> {
+    let config = storage.load_config()?;
+    let milestones = storage.load_milestones()?;
+    let metas = storage.load_metas()?;
+
+    let today = Local::now().date_naive();
+
+    Ok(CmdResult {
+        value: milestones
+            .milestones
+            .into_iter()
+            .map(|milestone| progress(&config, milestone, &metas, today))
+            .collect(),
+        infos: vec![],
+    })
+}
+
+/// A single milestone with the progress of its issues
+pub fn milestone_show(storage: &dyn Storage, name: &str) -> Cmd<MilestoneProgress> {
+    let result = milestone_list(storage)?;
+
+    let progress = result
+        .value
+        .into_iter()
+        .find(|progress| progress.milestone.name == name)
+        .ok_or_else(|| format!("Not available: Milestone \"{name}\" does not exist."))?;
+
+    Ok(CmdResult {
+        value: progress,
+        infos: result.infos,
+    })
+}
+
+/// Check that issues can be added to a milestone: open milestone of milestones.yaml or '' (none)
+pub(crate) fn check_milestone(milestones: &Milestones, value: &str) -> Result<(), String> {
+    if is_valid_milestone(milestones, value) {
+        return Ok(());
+    }
+
+    if milestones.get(value).is_some() {
+        return Err(format!("Milestone \"{value}\" is closed: no more issues can be added."));
+    }
+
+    let open: Vec<&str> = milestones
+        .milestones
+        .iter()
+        .filter(|m| m.state == MilestoneState::Open)
+        .map(|m| m.name.as_str())
+        .chain([""])
+        .collect();
+
+    Err(format!(
+        "Invalid milestone \"{value}\". Valid options: {open:?} | Create one with `git issue milestone new`"
+    ))
+}
+
+fn progress(config: &Config, milestone: Milestone, metas: &[Meta], today: NaiveDate) -> MilestoneProgress {
+    let mut issues: Vec<&Meta> = metas.iter().filter(|meta| meta.milestone == milestone.name).collect();
+    issues.sort_by_key(|meta| meta.id);
+
+    let done = issues.iter().filter(|meta| is_done(config, &meta.state)).count();
+
+    let overdue = issues
+        .iter()
+        .filter(|meta| !is_done(config, &meta.state))
+        .filter(|meta| {
+            let due = if meta.due_date.is_empty() { &milestone.due } else { &meta.due_date };
+            NaiveDate::parse_from_str(due, "%Y-%m-%d").is_ok_and(|due| due < today)
+        })
+        .map(|meta| meta.id)
+        .collect();
+
+    MilestoneProgress {
+        issues: issues.iter().map(|meta| meta.id).collect(),
+        milestone,
+        done,
+        overdue,
+    }
+}
+
+/// Write milestones.yaml (keeping the comments of the default file) and commit it
+fn write_milestones(milestones: &Milestones, action: &str, name: &str) -> Result<Vec<String>, String> {
+    let config = load_config()?;
+    let path = milestones_path()?;
+
+    let header = DEFAULT_MILESTONES.split("_version").next().unwrap_or_default();
+    let yaml = serde_yaml::to_string(milestones).map_err(|_| "Failed to serialize milestones.yaml".to_string())?;
+
+    let mut transaction = Transaction::new();
+    transaction.write(path.clone(), format!("{header}{yaml}"));
+
+    let applied = transaction.apply()?;
+    journal::record(&format!("milestone {action} {name}"), &applied)?;
+
+    if !config.commit_auto {
+        return Ok(vec![]);
+    }
+
+    run_git(
+        &format!("[issue] milestone {action} -- {name}"),
+        &Trailers::new("milestone", &[]),
+        &[path],
+    )
+}
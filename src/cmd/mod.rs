@@ -1,11 +1,26 @@
+pub mod diff;
 pub mod edit;
+pub mod export;
+pub mod flow;
+pub mod graph;
+pub mod history;
+pub mod import;
+pub mod inbox;
 pub mod init;
 pub mod link;
 pub mod list;
+pub mod milestone;
 pub mod new;
+pub mod report;
 pub mod set;
 pub mod show;
+pub mod stats;
+pub mod tree;
+pub mod undo;
+pub mod user;
 pub mod util;
+pub mod watch;
+pub mod worklog;
 
 pub struct CmdResult<T> {
     pub value: T,
@@ -7,13 +7,21 @@ use chrono::Utc;
 
 use indexmap::IndexMap;
 
-use crate::cmd::util::{git_commit, user_handle_me};
+use crate::cmd::milestone::check_milestone;
+use crate::cmd::util::{git_commit, resolve_user};
+use crate::hooks::{self, Event, EventKind};
+use crate::journal;
 use crate::model::{
     IdGeneration, Meta, Priority, current_timestamp, gitissues_base, is_valid_iso_date, is_valid_type, is_valid_user,
-    issue_attachments_dir, issue_desc_path, issue_dir, issue_meta_path, issues_dir, load_config, load_settings, load_users, padded_id,
+    issue_attachments_dir, issue_desc_path, issue_dir, issues_dir, load_config, load_milestones, load_settings, load_users,
+    normalize_estimate, padded_id,
 };
+use crate::notify;
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
 use crate::{Cmd, CmdResult};
 
+#[allow(clippy::too_many_arguments)]
 pub fn new(
     title: String,
     type_: Option<String>,
@@ -21,6 +29,8 @@ pub fn new(
     assignee: Option<String>,
     priority: Option<Priority>,
     due_date: Option<String>,
+    milestone: Option<String>,
+    estimate: Option<String>,
     labels: Option<Vec<String>>,
 ) -> Cmd<u32> {
     // Step 1: Allocate the next issue ID
@@ -45,17 +55,8 @@ pub fn new(
         ));
     }
 
-    let mut reporter_val = match reporter {
-        Some(value) => {
-            if !is_valid_user(&users, &value) {
-                return Err(format!(
-                    "Invalid reporter \"{value}\". Valid options: {:?} | Configurable in users.yaml:users",
-                    users.users.iter().map(|u| u.id.as_str()).chain(["me", ""]).collect::<Vec<_>>()
-                ));
-            } else {
-                value
-            }
-        }
+    let reporter_val = match reporter {
+        Some(value) => resolve_user(&users, &settings, &value, "reporter")?,
         None => {
             if !is_valid_user(&users, &settings.user) {
                 return Err(format!(
@@ -68,17 +69,7 @@ pub fn new(
         }
     };
 
-    user_handle_me(&users, &settings, &mut reporter_val)?;
-
-    let mut assignee_val = assignee.unwrap_or_default();
-    if !is_valid_user(&users, &assignee_val) {
-        return Err(format!(
-            "Invalid assignee \"{assignee_val}\". Valid options: {:?} | Configurable in users.yaml:users",
-            users.users.iter().map(|u| u.id.as_str()).chain(["me", ""]).collect::<Vec<_>>()
-        ));
-    }
-
-    user_handle_me(&users, &settings, &mut assignee_val)?;
+    let assignee_val = resolve_user(&users, &settings, &assignee.unwrap_or_default(), "assignee")?;
 
     let due_date_val = due_date.clone().unwrap_or_default();
     match is_valid_iso_date(&due_date_val) {
@@ -87,13 +78,19 @@ pub fn new(
         Err(e) => return Err(format!("Error: {e}")),
     }
 
+    let milestone_val = milestone.unwrap_or_default();
+    let milestones = load_milestones()?;
+    check_milestone(&milestones, &milestone_val)?;
+
+    let estimate_val = normalize_estimate(&estimate.unwrap_or_default())?;
+
     let mut labels_val = labels.unwrap_or_default();
     labels_val.retain(|label| !label.is_empty());
 
     // Step 4: Create meta fields and validate
     let timestamp = current_timestamp();
 
-    let meta = Meta {
+    let mut meta = Meta {
         _version: 1,
         id: issue_id,
         title: title.clone(),
@@ -104,38 +101,52 @@ pub fn new(
         assignee: assignee_val,
         priority: priority.unwrap_or(config.priority_default),
         due_date: due_date.unwrap_or_default(),
+        milestone: milestone_val,
+        estimate: estimate_val,
+        watchers: Vec::new(),
         relationships: IndexMap::new(),
         created: timestamp.clone(),
         updated: timestamp,
     };
 
-    // Step 5: Create the issue directory
-    let dir = issue_dir(issue_id)?;
-    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create issue directory: {e}"))?;
+    // Reporter and assignee watch the issue
+    meta.add_watcher(&meta.reporter.clone());
+    meta.add_watcher(&meta.assignee.clone());
 
-    // Step 6: Write description.md
-    let desc_path = issue_desc_path(issue_id)?;
+    // Step 5: Prepare the issue directory
+    let dir = issue_dir(issue_id)?;
+    let mut transaction = Transaction::new();
+    transaction.create_dir(dir.clone());
 
+    // Step 6: Prepare description.md
     let template_path = gitissues_base()?.join("description.md");
-    fs::copy(&template_path, &desc_path).map_err(|e| format!("Failed to write description.md: {e}"))?;
+    let template = fs::read(&template_path).map_err(|e| format!("Failed to read description template: {e}"))?;
+    transaction.write(issue_desc_path(issue_id)?, template);
 
-    // Step 7: Create attachments directory
-    let attachment_dir = issue_attachments_dir(issue_id)?;
-    fs::create_dir_all(&attachment_dir).map_err(|e| format!("Failed to create issue directory: {e}"))?;
+    // Step 7: Prepare attachments directory incl. .gitkeep
+    transaction.write(issue_attachments_dir(issue_id)?.join(".gitkeep"), "");
 
-    // Step 7.1: Add .gitkeep
-    fs::write(attachment_dir.join(".gitkeep"), "").map_err(|e| format!("Failed to write .gitkeep: {e}"))?;
+    // Step 8: Prepare meta.yaml
+    transaction.write_meta(&meta)?;
 
-    // Step 8: Write meta.yaml
-    let meta_yaml_path = issue_meta_path(issue_id)?;
+    // Step 9: Write all files at once
+    let applied = transaction.apply()?;
+    journal::record(&format!("new #{issue_id}"), &applied)?;
 
-    let meta_yaml = serde_yaml::to_string(&meta).map_err(|_| "Failed to serialize meta.yaml".to_string())?;
-    fs::write(&meta_yaml_path, meta_yaml).map_err(|e| format!("Failed to write meta.yaml: {e}"))?;
-
-    // Step 9: git commit
-    let info_commit = git_commit(issue_id, title, "new")?;
+    // Step 10: git commit
+    let info_commit = git_commit(issue_id, title, "new", Trailers::new("new", &[issue_id]), &[dir])?;
     infos.extend(info_commit);
 
+    // Step 11: notifications and hooks
+    let mut events = vec![Event::new(EventKind::New, &meta)];
+
+    if !meta.assignee.is_empty() {
+        events.push(Event::change(EventKind::Assign, &meta, "", &meta.assignee));
+    }
+
+    infos.extend(notify::run(&events));
+    infos.extend(hooks::run(events));
+
     Ok(CmdResult { value: issue_id, infos })
 }
 
@@ -158,6 +169,23 @@ fn generate_id() -> Result<u32, String> {
     Ok(id)
 }
 
+/// Generates `count` new IDs at once (e.g. for `import`), following config.yaml:id_generation
+pub(crate) fn generate_ids(count: usize) -> Result<Vec<u32>, String> {
+    let path = issues_dir()?;
+    let mut id = generate_id()?;
+    let mut ids = Vec::new();
+
+    while ids.len() < count {
+        if !path.join(padded_id(id)).exists() {
+            ids.push(id);
+        }
+
+        id = id.checked_add(1).ok_or("Failed to generate IDs: ID range exhausted.".to_string())?;
+    }
+
+    Ok(ids)
+}
+
 fn generate_id_sequential(issues_dir: &Path) -> Result<u32, String> {
     let mut max_id = 0u32;
 
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
+
+use crate::cmd::graph::is_done;
+use crate::cmd::history::state_changes;
+use crate::model::{Meta, gitissues_base};
+use crate::storage::{GitStorage, Storage};
+use crate::{Cmd, CmdResult};
+
+const DEFAULT_TEMPLATE: &str = include_str!("../../config/changelog-default.md");
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    Type,
+    Labels,
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::Type => write!(f, "type"),
+            GroupBy::Labels => write!(f, "labels"),
+        }
+    }
+}
+
+/// Issue which transitioned into a state of config.yaml:states_done
+pub struct ChangelogEntry {
+    pub meta: Meta,
+    /// Date of the commit of the transition (YYYY-MM-DD)
+    pub closed: String,
+}
+
+/// Start or end of the report range
+enum Bound {
+    Rev(String),
+    Date(NaiveDate),
+}
+
+impl Bound {
+    fn parse(value: &str) -> Bound {
+        match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => Bound::Date(date),
+            Err(_) => Bound::Rev(value.to_string()),
+        }
+    }
+}
+
+/// Generate a markdown changelog of the issues which transitioned into a done state (config.yaml:states_done)
+/// - storage: git repository, the history of the `meta.yaml` files along the first parents is evaluated
+/// - since: start of the range, git revision (exclusive) or date YYYY-MM-DD (inclusive)
+/// - until: end of the range, git revision or date YYYY-MM-DD (inclusive) [default: HEAD]
+/// - group_by: group the issues by type or by label (an issue with several labels appears in each group)
+/// - template: markdown template [default: .gitissues/changelog.md, otherwise the built-in template]
+///
+/// Issues reopened before the end of the range are left out.
+pub fn changelog(storage: &GitStorage, since: &str, until: Option<&str>, group_by: GroupBy, template: Option<&Path>) -> Cmd<String> {
+    let template = load_template(template)?;
+    let entries = changelog_entries(storage, since, until)?;
+
+    let config = storage.load_config()?;
+    let groups = group_entries(&entries, group_by, &config.types);
+
+    let until_name = until.unwrap_or("HEAD");
+    let date = Local::now().format("%Y-%m-%d").to_string();
+
+    let header = |text: &str| {
+        fill(text, |name| match name {
+            "since" => Some(since.to_string()),
+            "until" => Some(until_name.to_string()),
+            "date" => Some(date.clone()),
+            _ => None,
+        })
+    };
+    let group_part = |text: &str, group: &str| fill(text, |name| (name == "group").then(|| group.to_string()));
+
+    let Some((before, group_block, after)) = split_block(&template, "group") else {
+        return Err("Invalid changelog template: missing {#group} ... {/group} block".to_string());
+    };
+
+    let Some((group_before, issue_block, group_after)) = split_block(group_block, "issue") else {
+        return Err("Invalid changelog template: missing {#issue} ... {/issue} block inside of {#group}".to_string());
+    };
+
+    let mut output = header(before);
+
+    for (group, entries) in &groups {
+        output.push_str(&group_part(group_before, group));
+
+        for entry in entries {
+            output.push_str(&fill(issue_block, |name| match name {
+                "id" => Some(entry.meta.id.to_string()),
+                "title" => Some(entry.meta.title.clone()),
+                "type" => Some(entry.meta.type_.clone()),
+                "labels" => Some(entry.meta.labels.join(", ")),
+                "assignee" => Some(entry.meta.assignee.clone()),
+                "closed" => Some(entry.closed.clone()),
+                _ => None,
+            }));
+        }
+
+        output.push_str(&group_part(group_after, group));
+    }
+
+    output.push_str(&header(after));
+
+    let mut infos = vec![];
+
+    if entries.is_empty() {
+        infos.push(format!(
+            "Info: No issues transitioned into {:?} within the range",
+            config.states_done
+        ));
+    }
+
+    Ok(CmdResult {
+        value: format!("{}\n", output.trim_end()),
+        infos,
+    })
+}
+
+/// Issues which transitioned into a done state within the range and are still done at its end, ordered by ID
+pub fn changelog_entries(storage: &GitStorage, since: &str, until: Option<&str>) -> Result<Vec<ChangelogEntry>, String> {
+    let tip = match until.map(Bound::parse) {
+        None => rev_parse(storage, "HEAD")?,
+        Some(Bound::Rev(rev)) => rev_parse(storage, &rev)?,
+        Some(Bound::Date(date)) => {
+            last_commit_before(storage, &format!("{date} 23:59:59"), "HEAD")?.ok_or_else(|| format!("No commit until {date}"))?
+        }
+    };
+
+    let base = match Bound::parse(since) {
+        Bound::Rev(rev) => Some(rev_parse(storage, &rev)?),
+        Bound::Date(date) => last_commit_before(storage, &format!("{date} 00:00:00"), &tip)?,
+    };
+
+    let config = storage.at(&tip).load_config()?;
+
+    // States at the start of the range
+    let mut states: HashMap<u32, String> = match &base {
+        Some(base) => storage
+            .at(base)
+            .load_metas()?
+            .into_iter()
+            .map(|meta| (meta.id, meta.state))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    // Walk the commits of the range, oldest first, and track the state changes
+    let mut transitions: HashMap<u32, String> = HashMap::new();
+
+    for change in state_changes(storage, &tip, base.as_deref())? {
+        let Some(state) = change.state.clone() else {
+            states.remove(&change.id);
+            continue;
+        };
+
+        let was_done = states.get(&change.id).is_some_and(|state| is_done(&config, state));
+
+        if is_done(&config, &state) && !was_done {
+            transitions.insert(change.id, change.date().to_string());
+        }
+
+        states.insert(change.id, state);
+    }
+
+    let mut entries: Vec<ChangelogEntry> = storage
+        .at(&tip)
+        .load_metas()?
+        .into_iter()
+        .filter(|meta| is_done(&config, &meta.state))
+        .filter_map(|meta| {
+            let closed = transitions.get(&meta.id)?.clone();
+            Some(ChangelogEntry { meta, closed })
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| entry.meta.id);
+
+    Ok(entries)
+}
+
+/// Groups in the order of config.yaml:types (or by label name), issues without type/label last in "Other"
+fn group_entries<'a>(entries: &'a [ChangelogEntry], group_by: GroupBy, types: &[String]) -> Vec<(String, Vec<&'a ChangelogEntry>)> {
+    let mut keys: Vec<String> = match group_by {
+        GroupBy::Type => types.to_vec(),
+        GroupBy::Labels => {
+            let mut labels: Vec<String> = entries.iter().flat_map(|e| e.meta.labels.clone()).collect();
+            labels.sort();
+            labels.dedup();
+            labels
+        }
+    };
+    keys.push(String::new());
+
+    let mut groups = Vec::new();
+
+    for key in keys {
+        let members: Vec<&ChangelogEntry> = entries
+            .iter()
+            .filter(|entry| match group_by {
+                GroupBy::Type if key.is_empty() => !types.contains(&entry.meta.type_),
+                GroupBy::Type => entry.meta.type_ == key,
+                GroupBy::Labels if key.is_empty() => entry.meta.labels.is_empty(),
+                GroupBy::Labels => entry.meta.labels.contains(&key),
+            })
+            .collect();
+
+        if !members.is_empty() {
+            groups.push((group_name(&key), members));
+        }
+    }
+
+    groups
+}
+
+/// Capitalized group name, e.g. "bug" -> "Bug"
+fn group_name(key: &str) -> String {
+    let mut chars = key.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Other".to_string(),
+    }
+}
+
+/// Template from the given file, .gitissues/changelog.md or the built-in default; a leading `<!-- -->` comment is removed
+fn load_template(path: Option<&Path>) -> Result<String, String> {
+    let template = match path {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?,
+        None => match gitissues_base().map(|base| base.join("changelog.md")) {
+            Ok(path) if path.exists() => fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?,
+            _ => DEFAULT_TEMPLATE.to_string(),
+        },
+    };
+
+    let template = template.trim_start();
+
+    if template.starts_with("<!--")
+        && let Some(end) = template.find("-->")
+    {
+        return Ok(template[end + 3..].trim_start().to_string());
+    }
+
+    Ok(template.to_string())
+}
+
+/// Split the template at the lines `{#name}` and `{/name}`: (before, inner, after)
+fn split_block<'a>(template: &'a str, name: &str) -> Option<(&'a str, &'a str, &'a str)> {
+    let start_marker = format!("{{#{name}}}");
+    let end_marker = format!("{{/{name}}}");
+
+    let start = template.find(&start_marker)?;
+    let inner_start = line_end(template, start + start_marker.len());
+    let end = inner_start + template[inner_start..].find(&end_marker)?;
+    let after_start = line_end(template, end + end_marker.len());
+
+    Some((&template[..start], &template[inner_start..end], &template[after_start..]))
+}
+
+/// Fill the `{name}` placeholders of a template in a single pass, values are inserted as they are (a title containing
+/// `{assignee}` stays unchanged), unknown placeholders are kept
+fn fill(text: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let replaced = rest[1..]
+            .find(['{', '}'])
+            .filter(|&end| rest.as_bytes()[end + 1] == b'}')
+            .and_then(|end| Some((end + 2, value(&rest[1..end + 1])?)));
+
+        match replaced {
+            Some((len, value)) => {
+                output.push_str(&value);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Position after the newline following `pos` (markers are removed together with their line break)
+fn line_end(text: &str, pos: usize) -> usize {
+    match text[pos..].find('\n') {
+        Some(i) if text[pos..pos + i].trim().is_empty() => pos + i + 1,
+        _ => pos,
+    }
+}
+
+fn rev_parse(storage: &GitStorage, rev: &str) -> Result<String, String> {
+    storage.at(rev).resolve_rev()
+}
+
+/// Last commit on the first-parent chain of `tip` which is older than `before`
+fn last_commit_before(storage: &GitStorage, before: &str, tip: &str) -> Result<Option<String>, String> {
+    let output = storage.git(&["rev-list", "-1", "--first-parent", &format!("--before={before}"), tip])?;
+    let commit = String::from_utf8_lossy(&output).trim().to_string();
+
+    Ok(if commit.is_empty() { None } else { Some(commit) })
+}
@@ -1,14 +1,19 @@
-use std::fs;
-
-use crate::cmd::util::{git_commit, load_meta, user_handle_me};
+use crate::cmd::milestone::check_milestone;
+use crate::cmd::util::{git_commit, git_commit_bulk, load_meta, resolve_user};
+use crate::hooks::{self, Event, EventKind};
+use crate::journal;
 use crate::model::{
-    Priority, current_timestamp, is_valid_iso_date, is_valid_state, is_valid_type, is_valid_user, issue_dir, issue_meta_path, load_config,
-    load_settings, load_users,
+    Priority, current_timestamp, is_valid_iso_date, is_valid_state, is_valid_type, issue_dir, issue_meta_path, load_config,
+    load_milestones, load_settings, load_users, normalize_estimate,
 };
+use crate::notify;
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
 use crate::{Cmd, CmdResult};
 
 /// Set metadata fields of issues
 /// Returns number of issues updated and optional info messages
+/// - batch: create a single commit for all issues instead of one commit per issue
 #[allow(clippy::too_many_arguments)]
 pub fn set(
     ids: Vec<u32>,
@@ -19,13 +24,17 @@ pub fn set(
     assignee: Option<String>,
     priority: Option<Priority>,
     due_date: Option<String>,
+    milestone: Option<String>,
+    estimate: Option<String>,
     labels: Option<Vec<String>>,
     labels_add: Option<Vec<String>>,
     labels_remove: Option<Vec<String>>,
+    batch: bool,
 ) -> Cmd<u32> {
     let config = load_config()?;
     let (settings, mut infos) = load_settings()?;
     let users = load_users()?;
+    let milestones = load_milestones()?;
 
     // Precondition: .gitissues/issues/ID must exist
     for id in &ids {
@@ -37,7 +46,9 @@ pub fn set(
         }
     }
 
-    let mut num_updated_issues = 0;
+    // Validate and prepare all updates before any file is touched
+    let mut updates = Vec::new();
+    let mut events = Vec::new();
 
     for id in ids {
         // Load meta.yaml
@@ -87,18 +98,10 @@ pub fn set(
         if let Some(value) = reporter.as_ref()
             && updated_meta.reporter != *value
         {
-            if !is_valid_user(&users, value) {
-                return Err(format!(
-                    "Invalid reporter \"{value}\". Valid options: {:?} | Configurable in users.yaml:users",
-                    users.users.iter().map(|u| u.id.as_str()).chain(["me", ""]).collect::<Vec<_>>()
-                ));
-            }
-
-            let mut value = value.clone();
+            let value = resolve_user(&users, &settings, value, "reporter")?;
 
-            user_handle_me(&users, &settings, &mut value)?;
-
-            if updated_meta.reporter != *value {
+            if updated_meta.reporter != value {
+                updated_meta.add_watcher(&value);
                 updated_meta.reporter = value;
                 fields.push("reporter");
             }
@@ -107,18 +110,10 @@ pub fn set(
         if let Some(value) = assignee.as_ref()
             && updated_meta.assignee != *value
         {
-            if !is_valid_user(&users, value) {
-                return Err(format!(
-                    "Invalid assignee \"{value}\". Valid options: {:?} | Configurable in users.yaml:users",
-                    users.users.iter().map(|u| u.id.as_str()).chain(["me", ""]).collect::<Vec<_>>()
-                ));
-            }
-
-            let mut value = value.clone();
-
-            user_handle_me(&users, &settings, &mut value)?;
+            let value = resolve_user(&users, &settings, value, "assignee")?;
 
             if updated_meta.assignee != value {
+                updated_meta.add_watcher(&value);
                 updated_meta.assignee = value;
                 fields.push("assignee");
             }
@@ -144,6 +139,24 @@ pub fn set(
             fields.push("due_date");
         }
 
+        if let Some(value) = milestone.as_deref()
+            && updated_meta.milestone != value
+        {
+            check_milestone(&milestones, value)?;
+
+            updated_meta.milestone = value.to_string();
+            fields.push("milestone");
+        }
+
+        if let Some(value) = estimate.as_deref() {
+            let value = normalize_estimate(value)?;
+
+            if updated_meta.estimate != value {
+                updated_meta.estimate = value;
+                fields.push("estimate");
+            }
+        }
+
         if let Some(value) = labels.as_ref()
             && updated_meta.labels != *value
         {
@@ -184,19 +197,78 @@ pub fn set(
 
         updated_meta.updated = current_timestamp();
 
-        let updated_yaml = serde_yaml::to_string(&updated_meta).map_err(|_| "Failed to serialize meta.yaml".to_string())?;
+        if fields.contains(&"state") {
+            events.push(Event::change(
+                EventKind::StateChange,
+                &updated_meta,
+                &meta.state,
+                &updated_meta.state,
+            ));
+        }
 
-        fs::write(&meta_path, updated_yaml).map_err(|_| "Failed to write meta.yaml".to_string())?;
+        if fields.contains(&"assignee") {
+            events.push(Event::change(
+                EventKind::Assign,
+                &updated_meta,
+                &meta.assignee,
+                &updated_meta.assignee,
+            ));
+        }
 
-        let info_commit = git_commit(id, updated_meta.title, &format!("set {}", fields.join(",")))?;
+        updates.push((updated_meta, fields));
+    }
 
-        infos.extend(info_commit);
+    // Write all meta.yaml files at once
+    let mut transaction = Transaction::new();
 
-        num_updated_issues += 1;
+    for (meta, _) in &updates {
+        transaction.write_meta(meta)?;
     }
 
+    let applied = transaction.apply()?;
+
+    let mut all_fields: Vec<&str> = Vec::new();
+    let mut ids = Vec::new();
+
+    for (meta, fields) in &updates {
+        for field in fields {
+            if !all_fields.contains(field) {
+                all_fields.push(field);
+            }
+        }
+
+        ids.push(meta.id);
+    }
+
+    let ids_joined = ids.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(",");
+    journal::record(&format!("set {} {ids_joined}", all_fields.join(",")), &applied)?;
+
+    if batch && !updates.is_empty() {
+        let dirs = ids.iter().map(|id| issue_dir(*id)).collect::<Result<Vec<_>, _>>()?;
+
+        let trailers = Trailers::new("set", &ids).with_fields(&all_fields);
+        let info_commit = git_commit_bulk(&ids, &format!("set {}", all_fields.join(",")), trailers, &dirs)?;
+
+        infos.extend(info_commit);
+    } else {
+        for (meta, fields) in &updates {
+            let info_commit = git_commit(
+                meta.id,
+                meta.title.clone(),
+                &format!("set {}", fields.join(",")),
+                Trailers::new("set", &[meta.id]).with_fields(fields),
+                &[issue_dir(meta.id)?],
+            )?;
+
+            infos.extend(info_commit);
+        }
+    }
+
+    infos.extend(notify::run(&events));
+    infos.extend(hooks::run(events));
+
     Ok(CmdResult {
-        value: num_updated_issues,
+        value: updates.len() as u32,
         infos,
     })
 }
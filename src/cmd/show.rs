@@ -1,48 +1,45 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use indexmap::IndexMap;
 
 use regex::Regex;
 
-use crate::cmd::util::{dash_if_empty, load_description, load_meta};
-use crate::model::{Meta, issue_attachments_dir, issue_dir, issue_meta_path, issue_tmp_show_dir};
+use crate::cmd::util::dash_if_empty;
+use crate::model::{Meta, Users, WorkLogEntry, format_duration, issue_tmp_show_dir, parse_duration};
+use crate::storage::Storage;
 use crate::{Cmd, CmdResult};
 
 /// Show the issue with the given ID by generating a markdown file in a temporary directory
 /// The markdown file contains the issue title, meta data in table format and description
 /// The description attachments are also copied to the temporary directory
 /// Returns the path to the markdown file
-pub fn show(id: u32) -> Cmd<PathBuf> {
-    let path = issue_dir(id)?;
-
-    // Precondition: .gitissues/issues/ID must exist
-    if !path.exists() {
-        return Err("Not available: ID does not exist.".to_string());
-    }
-
-    // Load meta.yaml
-    let meta = load_meta(&issue_meta_path(id)?)?;
+pub fn show(storage: &dyn Storage, id: u32) -> Cmd<PathBuf> {
+    let content = show_markdown(storage, id)?.value;
 
     // Create per-issue tmp directory
     let tmp_issue_path = issue_tmp_show_dir(id)?;
     let _ = fs::remove_dir_all(&tmp_issue_path);
     fs::create_dir_all(&tmp_issue_path).map_err(|e| format!("Failed to create {}: {e}", tmp_issue_path.display()))?;
 
-    // Generate markdown content
-    let mut content: String = generate_content_metadata(id, &meta);
-    add_content_description(path.as_path(), &mut content)?;
-
     // Write markdown file
     let tmp_file = tmp_issue_path.join("show.md");
     fs::write(&tmp_file, content).map_err(|e| format!("Failed to write {}: {e}", tmp_file.display()))?;
 
     // Copy attachments to tmp directory
-    let attachments_src = issue_attachments_dir(id)?;
-    if attachments_src.exists() {
-        let tmp_attachments = tmp_issue_path.join("attachments");
-        copy_dir_recursive(&attachments_src, &tmp_attachments)?;
+    for name in storage.attachments(id)? {
+        let Some(bytes) = storage.read_attachment(id, &name)? else {
+            continue;
+        };
+
+        let dst = name.split('/').fold(tmp_issue_path.join("attachments"), |acc, part| acc.join(part));
+
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+
+        fs::write(&dst, bytes).map_err(|e| format!("Failed to write {}: {e}", dst.display()))?;
     }
 
     Ok(CmdResult {
@@ -51,10 +48,33 @@ pub fn show(id: u32) -> Cmd<PathBuf> {
     })
 }
 
-fn generate_content_metadata(id: u32, meta: &Meta) -> String {
+/// Generate the markdown of the issue with the given ID: title, meta data in table format and description
+pub fn show_markdown(storage: &dyn Storage, id: u32) -> Cmd<String> {
+    // Precondition: .gitissues/issues/ID must exist
+    if !storage.issue_ids()?.contains(&id) {
+        return Err("Not available: ID does not exist.".to_string());
+    }
+
+    // Load meta.yaml
+    let meta = storage.load_meta(id)?;
+
+    // Generate markdown content
+    let users = storage.load_users()?;
+
+    let mut content: String = generate_content_metadata(id, &meta, &users);
+    add_content_worklog(&meta, &storage.load_worklog(id)?, &users, &mut content)?;
+    add_content_description(&storage.load_description(id)?, &mut content)?;
+
+    Ok(CmdResult {
+        value: content,
+        infos: vec![],
+    })
+}
+
+fn generate_content_metadata(id: u32, meta: &Meta, users: &Users) -> String {
     let mut content = String::new();
 
-    let (width, values) = get_values(meta);
+    let (width, values) = get_values(meta, users);
 
     content.push_str("<!-- READ-ONLY VIEW -->\n");
     content.push('\n');
@@ -71,6 +91,9 @@ fn generate_content_metadata(id: u32, meta: &Meta) -> String {
     content.push_str(&format!("| **assignee**      | {:width$} |\n", values["assignee"]));
     content.push_str(&format!("| **priority**      | {:width$} |\n", values["priority"]));
     content.push_str(&format!("| **due_date**      | {:width$} |\n", values["due_date"]));
+    content.push_str(&format!("| **milestone**     | {:width$} |\n", values["milestone"]));
+    content.push_str(&format!("| **estimate**      | {:width$} |\n", values["estimate"]));
+    content.push_str(&format!("| **watchers**      | {:width$} |\n", values["watchers"]));
     content.push_str(&format!("| **relationships** | {}", values["relationships"]));
     content.push_str(&format!("| **created**       | {:width$} |\n", values["created"]));
     content.push_str(&format!("| **updated**       | {:width$} |\n", values["updated"]));
@@ -78,16 +101,22 @@ fn generate_content_metadata(id: u32, meta: &Meta) -> String {
     content
 }
 
-fn get_values(meta: &Meta) -> (usize, HashMap<String, String>) {
+fn get_values(meta: &Meta, users: &Users) -> (usize, HashMap<String, String>) {
     let mut values = HashMap::new();
 
     values.insert("state".to_string(), meta.state.clone());
     values.insert("type".to_string(), dash_if_empty(&meta.type_));
     values.insert("labels".to_string(), dash_if_empty(&meta.labels.join(",")));
-    values.insert("reporter".to_string(), dash_if_empty(&meta.reporter));
-    values.insert("assignee".to_string(), dash_if_empty(&meta.assignee));
+    values.insert("reporter".to_string(), dash_if_empty(&display_user(users, &meta.reporter)));
+    values.insert("assignee".to_string(), dash_if_empty(&display_user(users, &meta.assignee)));
     values.insert("priority".to_string(), format!("{:?}", meta.priority));
     values.insert("due_date".to_string(), dash_if_empty(&meta.due_date));
+    values.insert("milestone".to_string(), dash_if_empty(&meta.milestone));
+    values.insert("estimate".to_string(), dash_if_empty(&meta.estimate));
+    values.insert(
+        "watchers".to_string(),
+        dash_if_empty(&meta.watchers.iter().map(|w| display_user(users, w)).collect::<Vec<_>>().join(", ")),
+    );
     values.insert("created".to_string(), meta.created.clone());
     values.insert("updated".to_string(), meta.updated.clone());
 
@@ -104,6 +133,14 @@ fn get_values(meta: &Meta) -> (usize, HashMap<String, String>) {
     (max_width, values)
 }
 
+/// Display name with the ID, e.g. `Alice Smith (alice)`; only the ID if the user has no name
+fn display_user(users: &Users, id: &str) -> String {
+    match users.display_name(id) {
+        name if name == id => name,
+        name => format!("{name} ({id})"),
+    }
+}
+
 fn content_relationships(relationships: &IndexMap<String, Vec<u32>>, min_width: usize) -> (usize, String) {
     let mut content = String::new();
     let mut max_width = 1;
@@ -133,43 +170,86 @@ fn content_relationships(relationships: &IndexMap<String, Vec<u32>>, min_width:
     (max_width, content)
 }
 
-fn add_content_description(path: &Path, content: &mut String) -> Result<(), String> {
-    // Load description.md
-    let desc_path = path.join("description.md");
-    let desc_raw = load_description(&desc_path)?;
+/// Work log as table with the total spent time, nothing if there are no entries
+fn add_content_worklog(meta: &Meta, entries: &[WorkLogEntry], users: &Users, content: &mut String) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
 
-    let re = Regex::new(r"(?m)^#").map_err(|e| format!("Invalid regex for description headers: {e}"))?; // (?m) enables multi-line mode
-
-    // Replace # with ###
-    let desc_nested = re.replace_all(&desc_raw, "###");
+    let headers = ["**date**", "**author**", "**spent**", "**note**"];
+
+    let rows: Vec<[String; 4]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.date.clone(),
+                display_user(users, &entry.author),
+                entry.spent.clone(),
+                dash_if_empty(&entry.note.replace('|', "\\|").replace('\n', " ")),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([headers[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells.iter().zip(&widths).map(|(cell, width)| format!("{cell:width$}")).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
 
     content.push('\n');
-    content.push_str("## Description\n");
+    content.push_str("## Work Log\n");
+    content.push('\n');
+    content.push_str(&line(&headers.map(String::from)));
+    content.push_str(&format!(
+        "|{}|\n",
+        widths
+            .iter()
+            .map(|width| format!(" :{} ", "-".repeat(width - 1)))
+            .collect::<Vec<_>>()
+            .join("|")
+    ));
+
+    let mut spent = 0;
+
+    for (entry, row) in entries.iter().zip(&rows) {
+        spent += parse_duration(&entry.spent)?;
+        content.push_str(&line(row));
+    }
+
     content.push('\n');
-    content.push_str(&format!("{desc_nested}"));
+
+    match parse_duration(&meta.estimate) {
+        Ok(estimate) => content.push_str(&format!(
+            "**spent**: {} of {} (remaining: {})\n",
+            format_duration(spent),
+            meta.estimate,
+            format_duration(estimate.saturating_sub(spent))
+        )),
+        Err(_) => content.push_str(&format!("**spent**: {}\n", format_duration(spent))),
+    }
 
     Ok(())
 }
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
-    if !src.exists() {
-        return Ok(()); // nothing to copy
-    }
 
-    fs::create_dir_all(dst).map_err(|e| format!("Failed to create {}: {e}", dst.display()))?;
+fn add_content_description(desc_raw: &str, content: &mut String) -> Result<(), String> {
+    let re = Regex::new(r"(?m)^#").map_err(|e| format!("Invalid regex for description headers: {e}"))?; // (?m) enables multi-line mode
 
-    for entry in fs::read_dir(src).map_err(|e| format!("Failed to read {}: {e}", src.display()))? {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
-        let ty = entry.file_type().map_err(|e| format!("Failed to read file type: {e}"))?;
-        let name = entry.file_name();
-        let src_path = entry.path();
-        let dst_path = dst.join(&name);
+    // Replace # with ###
+    let desc_nested = re.replace_all(desc_raw, "###");
 
-        if ty.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path).map_err(|e| format!("Failed to copy {} -> {}: {e}", src_path.display(), dst_path.display()))?;
-        }
-    }
+    content.push('\n');
+    content.push_str("## Description\n");
+    content.push('\n');
+    content.push_str(&format!("{desc_nested}"));
 
     Ok(())
 }
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::cmd::graph::is_done;
+use crate::cmd::history::{parse_time, state_changes};
+use crate::cmd::list::get_column_value;
+use crate::model::{Config, Meta};
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+/// Done or not over time
+type Timeline = Vec<(DateTime<Utc>, bool)>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Week => write!(f, "week"),
+            Period::Month => write!(f, "month"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub open: usize,
+    pub closed: usize,
+    pub by_state: IndexMap<String, usize>,
+    pub by_type: IndexMap<String, usize>,
+    pub by_assignee: IndexMap<String, usize>,
+    pub by_priority: IndexMap<String, usize>,
+    pub by_label: IndexMap<String, usize>,
+    /// Open and closed issues at the end of each period
+    pub over_time: Vec<PeriodCount>,
+    /// Days from `created` to the transition into a done state, all issues
+    pub cycle_time: Durations,
+    /// Days from `created` to the transition into a done state, per type
+    pub lead_time: IndexMap<String, Durations>,
+    /// Open issues by due date
+    pub due: DueCounts,
+}
+
+#[derive(Serialize)]
+pub struct PeriodCount {
+    /// YYYY-MM or YYYY-Www
+    pub period: String,
+    pub open: usize,
+    pub closed: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct Durations {
+    pub count: usize,
+    pub mean_days: Option<f64>,
+    pub median_days: Option<f64>,
+}
+
+#[derive(Serialize, Default)]
+pub struct DueCounts {
+    pub overdue: usize,
+    /// Due today or within the next 7 days
+    pub due_soon: usize,
+    pub due_later: usize,
+    pub no_due_date: usize,
+}
+
+/// Compute statistics of the issues
+/// - storage: where to read the issues from (working tree, git revision, ...)
+/// - period: granularity of `over_time`
+///
+/// The times of the transitions into a done state (config.yaml:states_done) are taken from the git history of `meta.yaml`.
+/// Without history (no git repository, uncommitted changes) the `updated` timestamp is used instead.
+pub fn stats(storage: &dyn Storage, period: Period) -> Cmd<Stats> {
+    let config = storage.load_config()?;
+    let mut metas = storage.load_metas()?;
+    metas.sort_by_key(|meta| meta.id);
+
+    let mut infos = Vec::new();
+
+    // State history per issue: (time, done)
+    let mut history: HashMap<u32, Timeline> = HashMap::new();
+
+    match storage.history() {
+        Some(git) => {
+            let tip = git.resolve_rev()?;
+
+            for change in state_changes(&git, &tip, None)? {
+                let (Some(state), Some(time)) = (change.state, parse_time(&change.time)) else {
+                    continue;
+                };
+
+                history.entry(change.id).or_default().push((time, is_done(&config, &state)));
+            }
+        }
+        None => infos.push("Info: No git history available, done times are taken from 'updated'".to_string()),
+    }
+
+    let timelines: Vec<(&Meta, Timeline)> = metas
+        .iter()
+        .map(|meta| (meta, timeline(&config, meta, history.remove(&meta.id).unwrap_or_default())))
+        .collect();
+
+    let closed = metas.iter().filter(|meta| is_done(&config, &meta.state)).count();
+
+    // Lead/cycle times of the done issues
+    let mut lead_times: IndexMap<String, Vec<f64>> = config.types.iter().map(|t| (t.clone(), vec![])).collect();
+    let mut cycle_times = Vec::new();
+
+    for (meta, timeline) in &timelines {
+        if !is_done(&config, &meta.state) {
+            continue;
+        }
+
+        let (Some(created), Some(done)) = (parse_time(&meta.created), last_done_transition(timeline)) else {
+            continue;
+        };
+
+        let days = (done - created).num_seconds().max(0) as f64 / 86400.0;
+        cycle_times.push(days);
+        lead_times.entry(get_column_value("type", meta)?).or_default().push(days);
+    }
+
+    let lead_time = lead_times
+        .into_iter()
+        .filter(|(_, days)| !days.is_empty())
+        .map(|(type_, days)| (type_, durations(days)))
+        .collect();
+
+    Ok(CmdResult {
+        value: Stats {
+            total: metas.len(),
+            open: metas.len() - closed,
+            closed,
+            by_state: count_by(&metas, config.states.clone(), |meta| vec![meta.state.clone()]),
+            by_type: count_by(&metas, config.types.clone(), |meta| {
+                vec![get_column_value("type", meta).unwrap_or_default()]
+            }),
+            by_assignee: count_by(&metas, vec![], |meta| vec![get_column_value("assignee", meta).unwrap_or_default()]),
+            by_priority: count_by(&metas, ["P0", "P1", "P2", "P3", "P4"].map(String::from).to_vec(), |meta| {
+                vec![get_column_value("priority", meta).unwrap_or_default()]
+            }),
+            by_label: count_by(&metas, vec![], |meta| meta.labels.clone()),
+            over_time: over_time(&timelines, period),
+            cycle_time: durations(cycle_times),
+            lead_time,
+            due: due_counts(&config, &metas),
+        },
+        infos,
+    })
+}
+
+/// Counts per key: `order` first (also with count 0), then the other keys sorted, "-" (empty) last
+fn count_by(metas: &[Meta], order: Vec<String>, keys: impl Fn(&Meta) -> Vec<String>) -> IndexMap<String, usize> {
+    let mut counts: IndexMap<String, usize> = order.into_iter().map(|key| (key, 0)).collect();
+    let known = counts.len();
+
+    for meta in metas {
+        for key in keys(meta) {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+
+    let mut others: Vec<(String, usize)> = counts.drain(known..).collect();
+    others.sort_by(|(a, _), (b, _)| (a == "-").cmp(&(b == "-")).then(a.cmp(b)));
+    counts.extend(others);
+
+    counts
+}
+
+/// Done or not over time, from `created` on, ending with the current state
+fn timeline(config: &Config, meta: &Meta, mut history: Timeline) -> Timeline {
+    let done = is_done(config, &meta.state);
+    let now = Utc::now();
+
+    if history.is_empty()
+        && let Some(created) = parse_time(&meta.created)
+    {
+        history.push((created, false));
+    }
+
+    // Uncommitted state change
+    if history.last().map(|(_, d)| *d) != Some(done) {
+        history.push((parse_time(&meta.updated).unwrap_or(now), done));
+    }
+
+    history
+}
+
+fn last_done_transition(timeline: &[(DateTime<Utc>, bool)]) -> Option<DateTime<Utc>> {
+    let mut last = None;
+    let mut was_done = false;
+
+    for (time, done) in timeline {
+        if *done && !was_done {
+            last = Some(*time);
+        }
+        was_done = *done;
+    }
+
+    last
+}
+
+fn over_time(timelines: &[(&Meta, Timeline)], period: Period) -> Vec<PeriodCount> {
+    let starts: Vec<Option<DateTime<Utc>>> = timelines
+        .iter()
+        .map(|(meta, timeline)| parse_time(&meta.created).or(timeline.first().map(|(time, _)| *time)))
+        .collect();
+
+    let Some(first) = starts.iter().flatten().min() else {
+        return vec![];
+    };
+
+    let today = Local::now().date_naive();
+    let mut result = Vec::new();
+    let mut day = period_start(first.date_naive(), period);
+
+    while day <= today {
+        let next = match period {
+            Period::Week => day + Duration::days(7),
+            Period::Month => day.checked_add_months(chrono::Months::new(1)).unwrap_or(today + Duration::days(1)),
+        };
+        let end = next.and_hms_opt(0, 0, 0).map(|end| end.and_utc()).unwrap_or_else(Utc::now);
+
+        let mut count = PeriodCount {
+            period: period_name(day, period),
+            open: 0,
+            closed: 0,
+        };
+
+        for (start, (_, timeline)) in starts.iter().zip(timelines) {
+            if start.is_none_or(|start| start >= end) {
+                continue;
+            }
+
+            match timeline.iter().rev().find(|(time, _)| *time < end) {
+                Some((_, true)) => count.closed += 1,
+                _ => count.open += 1,
+            }
+        }
+
+        result.push(count);
+        day = next;
+    }
+
+    result
+}
+
+fn period_start(date: NaiveDate, period: Period) -> NaiveDate {
+    match period {
+        Period::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        Period::Month => date.with_day(1).unwrap_or(date),
+    }
+}
+
+fn period_name(date: NaiveDate, period: Period) -> String {
+    match period {
+        Period::Week => {
+            let week = date.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        Period::Month => date.format("%Y-%m").to_string(),
+    }
+}
+
+fn due_counts(config: &Config, metas: &[Meta]) -> DueCounts {
+    let today = Local::now().date_naive();
+    let mut due = DueCounts::default();
+
+    for meta in metas.iter().filter(|meta| !is_done(config, &meta.state)) {
+        match NaiveDate::parse_from_str(&meta.due_date, "%Y-%m-%d") {
+            Ok(date) if date < today => due.overdue += 1,
+            Ok(date) if date <= today + Duration::days(7) => due.due_soon += 1,
+            Ok(_) => due.due_later += 1,
+            Err(_) => due.no_due_date += 1,
+        }
+    }
+
+    due
+}
+
+fn durations(mut days: Vec<f64>) -> Durations {
+    if days.is_empty() {
+        return Durations::default();
+    }
+
+    days.sort_by(|a, b| a.total_cmp(b));
+
+    let count = days.len();
+    let mean = days.iter().sum::<f64>() / count as f64;
+    let median = if count.is_multiple_of(2) {
+        (days[count / 2 - 1] + days[count / 2]) / 2.0
+    } else {
+        days[count / 2]
+    };
+
+    Durations {
+        count,
+        mean_days: Some(round(mean)),
+        median_days: Some(round(median)),
+    }
+}
+
+/// One decimal
+fn round(days: f64) -> f64 {
+    (days * 10.0).round() / 10.0
+}
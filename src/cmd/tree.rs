@@ -0,0 +1,91 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::cmd::graph::children_of;
+use crate::cmd::list::{IssueData, ListResult, list};
+use crate::model::{Filter, Sorting};
+use crate::storage::Storage;
+use crate::{Cmd, CmdResult};
+
+/// List issues nested under their parents (relationship of kind `hierarchy`)
+/// - storage: where to read the issues from (working tree, git revision, ...)
+/// - id: only this issue and its descendants (None means all issues)
+/// - columns, filter, sort: see `list`
+///
+/// Issues whose parents are filtered out become roots. An issue with several parents is only nested under the first one.
+pub fn tree(
+    storage: &dyn Storage,
+    id: Option<u32>,
+    columns: Option<Vec<String>>,
+    filter: Option<Vec<Filter>>,
+    sort: Option<Vec<Sorting>>,
+) -> Cmd<ListResult> {
+    let config = storage.load_config()?;
+    let metas = storage.load_metas()?;
+
+    let result = list(storage, columns, filter, sort)?;
+    let ListResult { issues, columns } = result.value;
+
+    let listed: HashSet<u32> = issues.iter().map(|issue| issue.id).collect();
+
+    // Children and parents within the listed issues, keeping the order of the list
+    let position: HashMap<u32, usize> = issues.iter().enumerate().map(|(i, issue)| (issue.id, i)).collect();
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut has_parent: HashSet<u32> = HashSet::new();
+
+    for (parent, ids) in children_of(&config, &metas)? {
+        if !listed.contains(&parent) {
+            continue;
+        }
+
+        for child in ids.into_iter().filter(|c| listed.contains(c)) {
+            children.entry(parent).or_default().push(child);
+            has_parent.insert(child);
+        }
+    }
+
+    for ids in children.values_mut() {
+        ids.sort_by_key(|id| position.get(id));
+    }
+
+    let roots: Vec<u32> = match id {
+        Some(id) if listed.contains(&id) => vec![id],
+        Some(_) => return Err("Not available: ID does not exist.".to_string()),
+        None => issues.iter().map(|issue| issue.id).filter(|id| !has_parent.contains(id)).collect(),
+    };
+
+    let mut data: HashMap<u32, IssueData> = issues.into_iter().map(|issue| (issue.id, issue)).collect();
+    let mut nested = Vec::new();
+    let mut seen = HashSet::new();
+
+    for root in roots {
+        nest(root, 0, &children, &mut data, &mut seen, &mut nested);
+    }
+
+    Ok(CmdResult {
+        value: ListResult { issues: nested, columns },
+        infos: result.infos,
+    })
+}
+
+fn nest(
+    id: u32,
+    depth: usize,
+    children: &HashMap<u32, Vec<u32>>,
+    data: &mut HashMap<u32, IssueData>,
+    seen: &mut HashSet<u32>,
+    nested: &mut Vec<IssueData>,
+) {
+    // Each issue only once, under its first parent
+    if !seen.insert(id) {
+        return;
+    }
+
+    if let Some(mut issue) = data.remove(&id) {
+        issue.depth = depth;
+        nested.push(issue);
+    }
+
+    for child in children.get(&id).into_iter().flatten() {
+        nest(*child, depth + 1, children, data, seen, nested);
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::cmd::util::run_git;
+use crate::journal;
+use crate::model::{Config, gitissues_base, load_config};
+use crate::storage::{GitStorage, Storage};
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
+use crate::{Cmd, CmdResult};
+
+/// A commit created by git-issue which can be undone
+struct IssueCommit {
+    hash: String,
+    subject: String,
+    ids: Vec<u32>,
+}
+
+/// Undo the most recent git-issue changes
+/// - count: number of changes (commits or journal entries) to undo
+///
+/// With `commit_auto` the commits are reverted with a new commit, otherwise the local journal is used.
+/// Returns the undone changes
+pub fn undo(count: usize) -> Cmd<Vec<String>> {
+    if count == 0 {
+        return Err("Nothing to undo: count must be at least 1".to_string());
+    }
+
+    let config = load_config()?;
+
+    if config.commit_auto {
+        undo_commits(&config, count)
+    } else {
+        undo_journal(count)
+    }
+}
+
+/// Revert the most recent git-issue commits with a single new commit
+fn undo_commits(config: &Config, count: usize) -> Cmd<Vec<String>> {
+    let prefix = GitStorage::discover("HEAD")?.prefix().to_string();
+    let commits = find_issue_commits(config, &prefix, count)?;
+
+    if commits.is_empty() {
+        return Err("Nothing to undo: no git-issue commits found".to_string());
+    }
+
+    // Precondition: the commits only touched .gitissues/ and are no merges
+    // For every path: the newest commit which touched it, and the oldest one (to restore its parent's content)
+    let mut newest: IndexMap<String, String> = IndexMap::new();
+    let mut oldest: IndexMap<String, String> = IndexMap::new();
+
+    for commit in &commits {
+        let parents = git(&["rev-list", "--parents", "-n", "1", &commit.hash])?;
+        if parents.split_whitespace().count() > 2 {
+            return Err(format!("Cannot undo {}: merge commits are not supported", short(&commit.hash)));
+        }
+
+        let files = git(&["diff-tree", "--no-commit-id", "--name-only", "-r", "--root", &commit.hash])?;
+
+        for file in files.lines().filter(|f| !f.is_empty()) {
+            let Some(path) = file.strip_prefix(&format!("{prefix}/")) else {
+                return Err(format!(
+                    "Cannot undo {} '{}': it touches files outside of .gitissues/: {file}",
+                    short(&commit.hash),
+                    commit.subject
+                ));
+            };
+
+            newest.entry(path.to_string()).or_insert_with(|| commit.hash.clone());
+            oldest.insert(path.to_string(), commit.hash.clone());
+        }
+    }
+
+    // Precondition: the files were not changed afterwards (committed or not)
+    let pathspecs: Vec<String> = newest.keys().map(|path| format!(":(top){prefix}/{path}")).collect();
+
+    for (path, hash) in &newest {
+        let pathspec = format!(":(top){prefix}/{path}");
+        if !git_success(&["diff", "--quiet", hash, "HEAD", "--", &pathspec])? {
+            return Err(format!("Cannot undo: {path} was changed afterwards"));
+        }
+    }
+
+    let mut args = vec!["status", "--porcelain", "--"];
+    args.extend(pathspecs.iter().map(|p| p.as_str()));
+
+    if !git(&args)?.trim().is_empty() {
+        return Err("Cannot undo: .gitissues/ has uncommitted changes in the affected files".to_string());
+    }
+
+    // Restore the content of every path before the oldest commit which touched it
+    let base = gitissues_base()?;
+    let mut transaction = Transaction::new();
+
+    let mut by_commit: IndexMap<&str, Vec<String>> = IndexMap::new();
+    for (path, hash) in &oldest {
+        by_commit.entry(hash.as_str()).or_default().push(path.clone());
+    }
+
+    for (hash, paths) in by_commit {
+        let contents = match GitStorage::discover(&format!("{hash}^")) {
+            Ok(parent) => parent.read_many(&paths)?,
+            Err(_) => vec![None; paths.len()], // root commit
+        };
+
+        for (path, content) in paths.iter().zip(contents) {
+            match content {
+                Some(content) => transaction.write(base.join(path), content),
+                None => transaction.remove(base.join(path)),
+            }
+        }
+    }
+
+    let applied = transaction.apply()?;
+    let paths = applied.paths();
+
+    for path in &paths {
+        remove_empty_parents(path, &base);
+    }
+
+    // Commit the revert
+    let subjects: Vec<String> = commits.iter().map(|c| c.subject.clone()).collect();
+
+    let title = match commits.as_slice() {
+        [commit] => format!("[issue] undo: {}", commit.subject),
+        _ => format!("[issue] undo {} changes", commits.len()),
+    };
+
+    let mut ids: Vec<u32> = commits.iter().flat_map(|c| c.ids.clone()).collect();
+    ids.sort();
+    ids.dedup();
+
+    let hashes: Vec<String> = commits.iter().map(|c| c.hash.clone()).collect();
+    let trailers = Trailers::new("undo", &ids).with_reverts(&hashes);
+
+    let infos = run_git(&title, &trailers, &paths)?;
+
+    Ok(CmdResult { value: subjects, infos })
+}
+
+/// Most recent commits touching .gitissues/ created by git-issue, skipping commits which were already undone
+/// - Issue commits are identified by their trailers
+/// - Older commits without trailers are identified by the commit message templates
+fn find_issue_commits(config: &Config, prefix: &str, count: usize) -> Result<Vec<IssueCommit>, String> {
+    let templates = [
+        template_regex(&config.commit_message)?,
+        template_regex(&config.commit_message_bulk)?,
+    ];
+
+    let log = git(&["log", "--format=%H%x1f%s%x1f%B%x1e", "--", &format!(":(top){prefix}")])?;
+
+    let mut undone: HashSet<String> = HashSet::new();
+    let mut commits = Vec::new();
+
+    for record in log.split('\x1e') {
+        let mut parts = record.trim_start_matches('\n').splitn(3, '\x1f');
+        let (Some(hash), Some(subject), message) = (parts.next(), parts.next(), parts.next().unwrap_or_default()) else {
+            continue;
+        };
+
+        if hash.is_empty() {
+            continue;
+        }
+
+        let trailers = Trailers::parse(message);
+        let action = trailers.as_ref().map(|t| t.action.as_str());
+
+        if let Some(trailers) = trailers.as_ref()
+            && trailers.action == "undo"
+        {
+            undone.extend(trailers.reverts.iter().cloned());
+            continue;
+        }
+
+        if undone.contains(hash) {
+            continue;
+        }
+
+        // Nothing before `init` can be undone
+        if action == Some("init") || subject == "[issue] init" {
+            break;
+        }
+
+        if trailers.is_none() && !templates.iter().any(|t| t.is_match(subject)) {
+            if commits.is_empty() {
+                return Err(format!(
+                    "Cannot undo: last change of .gitissues/ was not made by git-issue: {} {subject}",
+                    short(hash)
+                ));
+            }
+            break;
+        }
+
+        commits.push(IssueCommit {
+            hash: hash.to_string(),
+            subject: subject.to_string(),
+            ids: trailers.map(|t| t.ids).unwrap_or_default(),
+        });
+
+        if commits.len() == count {
+            break;
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Regex matching commit subjects created from a commit message template (placeholders match anything)
+fn template_regex(template: &str) -> Result<Regex, String> {
+    let mut pattern = regex::escape(template);
+
+    for placeholder in ["action", "id", "title", "count", "ids"] {
+        pattern = pattern.replace(&regex::escape(&format!("{{{placeholder}}}")), ".*");
+    }
+
+    Regex::new(&format!("^{pattern}$")).map_err(|e| format!("Invalid commit message template: {e}"))
+}
+
+/// Undo the most recent journal entries (`commit_auto` disabled)
+fn undo_journal(count: usize) -> Cmd<Vec<String>> {
+    let base = gitissues_base()?;
+    let mut undone = Vec::new();
+
+    for _ in 0..count {
+        let Some((dir, entry)) = journal::last()? else {
+            break;
+        };
+
+        let mut transaction = Transaction::new();
+
+        for file in &entry.files {
+            let path = base.join(&file.path);
+
+            // Precondition: the file was not changed afterwards
+            let current = if path.exists() {
+                Some(fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?)
+            } else {
+                None
+            };
+
+            if current != journal::read_content(&dir, file.after.as_deref())? {
+                return Err(format!("Cannot undo '{}': {} was changed afterwards", entry.action, file.path));
+            }
+
+            match journal::read_content(&dir, file.before.as_deref())? {
+                Some(content) => transaction.write(path, content),
+                None => transaction.remove(path),
+            }
+        }
+
+        transaction.apply()?;
+
+        for created_dir in entry.created_dirs.iter().rev() {
+            let _ = fs::remove_dir(base.join(created_dir)); // only if empty
+        }
+
+        journal::remove_entry(&dir)?;
+        undone.push(entry.action);
+    }
+
+    if undone.is_empty() {
+        return Err("Nothing to undo: journal is empty".to_string());
+    }
+
+    Ok(CmdResult {
+        value: undone,
+        infos: vec![],
+    })
+}
+
+/// Remove directories left empty after removing a file, keeping the top-level directories of .gitissues (e.g. issues/)
+fn remove_empty_parents(path: &Path, base: &Path) {
+    let mut current: Option<PathBuf> = path.parent().map(|p| p.to_path_buf());
+
+    while let Some(dir) = current {
+        if dir.parent() == Some(base) || !dir.starts_with(base) || fs::remove_dir(&dir).is_err() {
+            break;
+        }
+        current = dir.parent().map(|p| p.to_path_buf());
+    }
+}
+
+fn short(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.first().unwrap_or(&""), stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Exit status of a git command, e.g. `git diff --quiet`
+fn git_success(args: &[&str]) -> Result<bool, String> {
+    let status = Command::new("git")
+        .args(args)
+        .status()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    Ok(status.success())
+}
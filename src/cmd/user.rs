@@ -0,0 +1,160 @@
+use std::fs;
+
+use crate::cmd::import::{append_to_yaml_list, yaml_scalar};
+use crate::cmd::util::run_git;
+use crate::journal;
+use crate::model::{GitIdentity, GitUserConfig, Users, load_config, load_settings_file, load_users, users_path};
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
+use crate::{Cmd, CmdResult};
+
+/// Where the current user comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserSource {
+    /// settings.yaml:user (override)
+    Settings,
+    /// Git identity matched against users.yaml
+    Git(GitIdentity),
+    /// No user: settings.yaml:user is empty and the git identity is not in users.yaml
+    None,
+}
+
+/// Current user and the git identity
+pub struct CurrentUser {
+    /// ID of users.yaml, '' if unknown
+    pub id: String,
+    pub source: UserSource,
+    pub git_name: Option<String>,
+    pub git_email: Option<String>,
+}
+
+/// Current user: settings.yaml:user if set, otherwise the user of users.yaml matching the git identity
+pub fn whoami() -> Cmd<CurrentUser> {
+    let (settings, infos) = load_settings_file()?;
+    let users = load_users()?;
+    let git = GitUserConfig::read();
+
+    let (id, source) = match git.find_user(&users) {
+        _ if !settings.user.is_empty() => (settings.user, UserSource::Settings),
+        Some((user, identity)) => (user.id.clone(), UserSource::Git(identity)),
+        None => (String::new(), UserSource::None),
+    };
+
+    Ok(CmdResult {
+        value: CurrentUser {
+            id,
+            source,
+            git_name: git.name,
+            git_email: git.email,
+        },
+        infos,
+    })
+}
+
+/// Add the git identity (`user.name`, `user.email`) as new user to users.yaml and commit it
+/// - id: ID of the new user [default: derived from the email, otherwise from the name]
+/// - returns the ID of the new user
+pub fn register_user(id: Option<String>) -> Cmd<String> {
+    let config = load_config()?;
+    let users = load_users()?;
+
+    let git = GitUserConfig::read();
+    let name = git.name.as_deref().unwrap_or_default();
+    let email = git.email.as_deref().unwrap_or_default();
+
+    if name.is_empty() && email.is_empty() {
+        return Err("No git identity: set `git config user.name` and `git config user.email` first".to_string());
+    }
+
+    if let Some((user, identity)) = git.find_user(&users) {
+        return Err(format!("Already registered: {identity} matches user \"{}\" in users.yaml", user.id));
+    }
+
+    let id = match id {
+        Some(id) => id.trim().to_string(),
+        None => suggest_id(name, email),
+    };
+
+    check_new_id(&users, &id)?;
+
+    // Append to users.yaml, keeping the comments
+    let path = users_path()?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+
+    let mut item = format!("id: {}", yaml_scalar(&id));
+
+    if !name.is_empty() && name != id {
+        item.push_str(&format!("\nname: {}", yaml_scalar(name)));
+    }
+
+    if !email.is_empty() {
+        item.push_str(&format!("\nemails:\n  - {}", yaml_scalar(email)));
+    }
+
+    let content = append_to_yaml_list(&content, "users", &[item])?;
+    serde_yaml::from_str::<Users>(&content).map_err(|e| format!("Failed to update users.yaml: {e}"))?;
+
+    let mut transaction = Transaction::new();
+    transaction.write(path.clone(), content);
+
+    let applied = transaction.apply()?;
+    journal::record(&format!("register user {id}"), &applied)?;
+
+    let infos = match config.commit_auto {
+        true => run_git(&format!("[issue] register user -- {id}"), &Trailers::new("user", &[]), &[path])?,
+        false => vec![],
+    };
+
+    Ok(CmdResult { value: id, infos })
+}
+
+/// Hint to register the git identity if there is no current user, `None` otherwise
+pub fn register_hint() -> Result<Option<String>, String> {
+    let current = whoami()?.value;
+
+    if current.source != UserSource::None || (current.git_name.is_none() && current.git_email.is_none()) {
+        return Ok(None);
+    }
+
+    Ok(Some(format!(
+        "Info: git user \"{}\" is not in users.yaml: register with `git issue whoami --register` or set settings.yaml:user",
+        git_identity(&current)
+    )))
+}
+
+/// Git identity as `name <email>`
+pub fn git_identity(current: &CurrentUser) -> String {
+    match (&current.git_name, &current.git_email) {
+        (Some(name), Some(email)) => format!("{name} <{email}>"),
+        (Some(name), None) => name.clone(),
+        (None, Some(email)) => format!("<{email}>"),
+        (None, None) => String::new(),
+    }
+}
+
+/// ID for a new user: local part of the email, otherwise the name; lowercase, only `a-z`, `0-9`, `.`, `_` and `-`
+pub fn suggest_id(name: &str, email: &str) -> String {
+    let base = match email.split('@').next().filter(|local| !local.is_empty()) {
+        Some(local) => local.to_string(),
+        None => name.split_whitespace().collect::<Vec<_>>().join("-"),
+    };
+
+    base.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        .collect()
+}
+
+fn check_new_id(users: &Users, id: &str) -> Result<(), String> {
+    if id.is_empty() || id == "me" || id.starts_with('@') || id.contains(',') || id.contains('*') {
+        return Err(format!(
+            "Invalid user ID \"{id}\": must not be empty, 'me', start with '@' or contain ',' or '*'"
+        ));
+    }
+
+    if users.find(id).is_some() {
+        return Err(format!("User \"{id}\" already exists in users.yaml: choose another one with --id"));
+    }
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
+use std::path::PathBuf;
 use std::process::Command;
 use std::{fs, path::Path};
 
-use crate::model::{Meta, Settings, Users, gitissues_base, is_valid_user, issues_dir, load_config};
+use crate::model::{Meta, Settings, Users, gitissues_base, is_valid_user, load_config};
+use crate::trailers::Trailers;
 
 pub(crate) fn user_handle_me(users: &Users, settings: &Settings, value: &mut String) -> Result<(), String> {
     if *value != "me" {
@@ -17,13 +19,37 @@ pub(crate) fn user_handle_me(users: &Users, settings: &Settings, value: &mut Str
     Ok(())
 }
 
+/// Resolve a user for a new assignment: ID, alias or email of an active user of users.yaml, 'me' or ''
+/// - field: name of the meta field, used in error messages
+pub(crate) fn resolve_user(users: &Users, settings: &Settings, value: &str, field: &str) -> Result<String, String> {
+    let mut value = value.to_string();
+
+    user_handle_me(users, settings, &mut value)?;
+
+    if value.is_empty() {
+        return Ok(value);
+    }
+
+    match users.find(&value) {
+        Some(user) if user.active => Ok(user.id.clone()),
+        Some(user) => Err(format!(
+            "Invalid {field} \"{value}\": user \"{}\" is inactive | Configurable in users.yaml:users:active",
+            user.id
+        )),
+        None => Err(format!(
+            "Invalid {field} \"{value}\". Valid options: {:?} | Configurable in users.yaml:users",
+            users.active_ids().into_iter().chain(["me", ""]).collect::<Vec<_>>()
+        )),
+    }
+}
+
 pub(crate) fn dash_if_empty(value: &str) -> String {
     if value.is_empty() { "-".to_string() } else { value.to_string() }
 }
 
 /// Git commit based on template and config
-/// This commits all issue changes (.gitissues/issues/)
-pub(crate) fn git_commit(id: u32, title: String, action: &str) -> Result<Vec<String>, String> {
+/// This commits only the given paths (e.g. the changed issue directories)
+pub(crate) fn git_commit(id: u32, title: String, action: &str, trailers: Trailers, paths: &[PathBuf]) -> Result<Vec<String>, String> {
     let config = load_config()?;
 
     // Check if auto-commit is enabled
@@ -39,22 +65,51 @@ pub(crate) fn git_commit(id: u32, title: String, action: &str) -> Result<Vec<Str
         .replace("{id}", &format!("{id}"))
         .replace("{title}", &title);
 
-    run_git(&commit_message, &issues_dir()?.to_string_lossy())
+    run_git(&commit_message, &trailers, paths)
+}
+
+/// Single git commit for many issues, based on the bulk template and config
+/// This commits only the given paths (e.g. the changed issue directories)
+pub(crate) fn git_commit_bulk(ids: &[u32], action: &str, trailers: Trailers, paths: &[PathBuf]) -> Result<Vec<String>, String> {
+    let config = load_config()?;
+
+    // Check if auto-commit is enabled
+    if !config.commit_auto {
+        return Ok(vec![]);
+    }
+
+    // Prepare commit message
+    let mut ids = ids.to_vec();
+    ids.sort();
+
+    let ids_joined = ids.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(",");
+
+    let commit_message = config
+        .commit_message_bulk
+        .replace("{action}", action)
+        .replace("{count}", &ids.len().to_string())
+        .replace("{ids}", &ids_joined);
+
+    run_git(&commit_message, &trailers, paths)
 }
 
 /// Simple commit message not based on template or config
 /// This commits all changes (.gitissues/)
-pub(crate) fn git_commit_non_templated(msg: &str) -> Result<Vec<String>, String> {
+pub(crate) fn git_commit_non_templated(msg: &str, trailers: Trailers) -> Result<Vec<String>, String> {
     // Prepare commit message
     let commit_message = format!("[issue] {msg}");
 
-    run_git(&commit_message, &gitissues_base()?.to_string_lossy())
+    run_git(&commit_message, &trailers, &[gitissues_base()?])
 }
 
-fn run_git(commit_message: &str, staging_dir: &str) -> Result<Vec<String>, String> {
+/// Commit the given paths; the trailers are appended to the message
+pub(crate) fn run_git(commit_message: &str, trailers: &Trailers, paths: &[PathBuf]) -> Result<Vec<String>, String> {
+    let commit_message = format!("{commit_message}\n\n{}", trailers.render());
+
     // Execute git add
     let add_result = Command::new("git")
-        .args(["add", staging_dir])
+        .args(["add", "--"])
+        .args(paths)
         .output()
         .map_err(|e| format!("Failed to stage .gitissues: {e}"))?;
 
@@ -63,9 +118,10 @@ fn run_git(commit_message: &str, staging_dir: &str) -> Result<Vec<String>, Strin
         return Err(format!("Failed to stage .gitissues: {}", stderr.trim()));
     }
 
-    // Execute git commit
+    // Execute git commit (only the given paths, other staged changes are left alone)
     let commit_result = Command::new("git")
-        .args(["commit", "-m", commit_message])
+        .args(["commit", "-m", &commit_message, "--"])
+        .args(paths)
         .output()
         .map_err(|e| format!("Failed to commit: {e}"))?;
 
@@ -0,0 +1,86 @@
+use crate::cmd::util::{git_commit, load_meta, resolve_user, user_handle_me};
+use crate::journal;
+use crate::model::{issue_dir, issue_meta_path, load_settings, load_users};
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
+use crate::{Cmd, CmdResult};
+
+/// Add a user to the watchers of an issue
+/// - user: ID, alias or email of an active user of users.yaml, or 'me'
+/// - Returns false if the user was already watching
+pub fn watch(id: u32, user: &str) -> Cmd<bool> {
+    let (settings, infos) = load_settings()?;
+    let users = load_users()?;
+
+    let user = resolve_user(&users, &settings, user, "watcher")?;
+
+    if user.is_empty() {
+        return Err("No user: set settings.yaml::user or use --user".to_string());
+    }
+
+    update_watchers(id, &user, "watch", infos, |watchers| {
+        if watchers.contains(&user) {
+            return false;
+        }
+
+        watchers.push(user.clone());
+        true
+    })
+}
+
+/// Remove a user from the watchers of an issue
+/// - user: ID, alias or email of a user of users.yaml (inactive users too), or 'me'
+/// - Returns false if the user was not watching
+pub fn unwatch(id: u32, user: &str) -> Cmd<bool> {
+    let (settings, infos) = load_settings()?;
+    let users = load_users()?;
+
+    let mut user = user.trim().to_string();
+    user_handle_me(&users, &settings, &mut user)?;
+
+    if let Some(found) = users.find(&user) {
+        user = found.id.clone();
+    }
+
+    if user.is_empty() {
+        return Err("No user: set settings.yaml::user or use --user".to_string());
+    }
+
+    update_watchers(id, &user, "unwatch", infos, |watchers| {
+        let len = watchers.len();
+        watchers.retain(|w| *w != user);
+        watchers.len() != len
+    })
+}
+
+/// Change the watchers of an issue and commit it
+/// The `updated` timestamp is kept: watching is no change of the issue itself.
+fn update_watchers(id: u32, user: &str, action: &str, mut infos: Vec<String>, change: impl FnOnce(&mut Vec<String>) -> bool) -> Cmd<bool> {
+    // Precondition: .gitissues/issues/ID must exist
+    if !issue_dir(id)?.exists() {
+        return Err(format!("Not available: ID #{id} does not exist."));
+    }
+
+    let mut meta = load_meta(&issue_meta_path(id)?)?;
+
+    if !change(&mut meta.watchers) {
+        return Ok(CmdResult { value: false, infos });
+    }
+
+    let mut transaction = Transaction::new();
+    transaction.write_meta(&meta)?;
+
+    let applied = transaction.apply()?;
+    journal::record(&format!("{action} #{id}"), &applied)?;
+
+    let info_commit = git_commit(
+        id,
+        meta.title,
+        &format!("{action} {user}"),
+        Trailers::new(action, &[id]).with_fields(&["watchers"]),
+        &[issue_meta_path(id)?],
+    )?;
+    infos.extend(info_commit);
+
+    Ok(CmdResult { value: true, infos })
+}
@@ -0,0 +1,188 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, Utc};
+
+use crate::cmd::util::{git_commit, user_handle_me};
+use crate::journal;
+use crate::model::{
+    WorkLogEntry, current_timestamp, format_duration, is_valid_iso_date, is_valid_user, issue_dir, issue_title, issue_worklog_dir,
+    load_settings, load_settings_or_default, load_users, parse_duration,
+};
+use crate::storage::Storage;
+use crate::trailers::Trailers;
+use crate::transaction::Transaction;
+use crate::{Cmd, CmdResult};
+
+/// Time logged by a user within a range of days, per issue and day
+pub struct Timesheet {
+    pub user: String,
+    /// All days of the range, first to last
+    pub days: Vec<NaiveDate>,
+    /// Issues with logged time, sorted by ID
+    pub rows: Vec<TimesheetRow>,
+    /// Minutes per day (same order as `days`) of all issues
+    pub totals: Vec<u32>,
+    pub total: u32,
+}
+
+pub struct TimesheetRow {
+    pub id: u32,
+    pub title: String,
+    /// Minutes per day (same order as `days`)
+    pub minutes: Vec<u32>,
+    pub total: u32,
+}
+
+/// Log time spent on an issue
+/// - Author is settings.yaml:user
+/// - Every entry is a new file in the `worklog/` directory of the issue, so concurrent entries never conflict on merge
+/// - duration: e.g. 2h30m, 2h or 45m
+/// - date: day the work was done [default: today]
+pub fn log_time(id: u32, duration: &str, note: Option<String>, date: Option<String>) -> Cmd<String> {
+    let (settings, mut infos) = load_settings()?;
+    let users = load_users()?;
+
+    // Precondition: .gitissues/issues/ID must exist
+    if !issue_dir(id)?.exists() {
+        return Err(format!("Not available: ID #{id} does not exist."));
+    }
+
+    if settings.user.is_empty() || settings.user == "me" || !is_valid_user(&users, &settings.user) {
+        return Err(format!(
+            "Invalid author \"{}\": settings.yaml::user must be part of users.yaml:users to log time",
+            settings.user
+        ));
+    }
+
+    let minutes = parse_duration(duration)?;
+
+    if minutes == 0 {
+        return Err(format!("Invalid duration \"{duration}\": must be more than 0m"));
+    }
+
+    let date = date.unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+
+    if date.is_empty() || !is_valid_iso_date(&date)? {
+        return Err("Invalid date format: Use 'YYYY-MM-DD'".to_string());
+    }
+
+    let spent = format_duration(minutes);
+
+    let entry = WorkLogEntry {
+        _version: 1,
+        author: settings.user.clone(),
+        date,
+        spent: spent.clone(),
+        note: note.unwrap_or_default(),
+        created: current_timestamp(),
+    };
+
+    // File name: creation time and author, with a counter in the rare case of a collision
+    let dir = issue_worklog_dir(id)?;
+    let stem = format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%SZ"), entry.author);
+
+    let mut path = dir.join(format!("{stem}.yaml"));
+    let mut counter = 2;
+
+    while path.exists() {
+        path = dir.join(format!("{stem}-{counter}.yaml"));
+        counter += 1;
+    }
+
+    let yaml = serde_yaml::to_string(&entry).map_err(|_| "Failed to serialize work log entry".to_string())?;
+
+    let mut transaction = Transaction::new();
+    transaction.write(path.clone(), yaml);
+
+    let applied = transaction.apply()?;
+    journal::record(&format!("log-time #{id}"), &applied)?;
+
+    let info_commit = git_commit(
+        id,
+        issue_title(id)?,
+        &format!("log-time {spent}"),
+        Trailers::new("log-time", &[id]),
+        &[path],
+    )?;
+    infos.extend(info_commit);
+
+    Ok(CmdResult { value: spent, infos })
+}
+
+/// Monday to Sunday of the ISO week of a day
+pub fn week_of(day: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+    (monday, monday + Duration::days(6))
+}
+
+/// Time logged by a user per issue and day
+/// - storage: where to read the issues from (working tree, git revision, ...)
+/// - user: author of the entries, 'me' for settings.yaml:user
+/// - from, to: first and last day (inclusive)
+pub fn timesheet(storage: &dyn Storage, user: &str, from: NaiveDate, to: NaiveDate) -> Cmd<Timesheet> {
+    let (settings, infos) = load_settings_or_default()?;
+    let users = storage.load_users()?;
+
+    let mut user = user.to_string();
+    user_handle_me(&users, &settings, &mut user)?;
+
+    if let Some(found) = users.find(&user) {
+        user = found.id.clone();
+    }
+
+    if user.is_empty() {
+        return Err("No user: set settings.yaml::user or use --user".to_string());
+    }
+
+    if from > to {
+        return Err(format!("Invalid range: from {from} is after to {to}"));
+    }
+
+    let days: Vec<NaiveDate> = from.iter_days().take_while(|day| *day <= to).collect();
+
+    let mut metas = storage.load_metas()?;
+    metas.sort_by_key(|meta| meta.id);
+
+    let mut rows = Vec::new();
+
+    for meta in metas {
+        let mut minutes = vec![0; days.len()];
+
+        for entry in storage.load_worklog(meta.id)? {
+            if entry.author != user {
+                continue;
+            }
+
+            let Ok(date) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") else {
+                continue;
+            };
+
+            if let Some(index) = days.iter().position(|day| *day == date) {
+                minutes[index] += parse_duration(&entry.spent)?;
+            }
+        }
+
+        let total = minutes.iter().sum();
+
+        if total > 0 {
+            rows.push(TimesheetRow {
+                id: meta.id,
+                title: meta.title,
+                minutes,
+                total,
+            });
+        }
+    }
+
+    let totals: Vec<u32> = (0..days.len()).map(|i| rows.iter().map(|row| row.minutes[i]).sum()).collect();
+    let total = totals.iter().sum();
+
+    Ok(CmdResult {
+        value: Timesheet {
+            user,
+            days,
+            rows,
+            totals,
+            total,
+        },
+        infos,
+    })
+}
@@ -0,0 +1,316 @@
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Meta, current_timestamp, gitissues_base, load_config, load_settings};
+
+/// config.yaml:hooks -- local commands run after issue events
+/// - Every command is run by the shell in the root of the repository, with the event as JSON on stdin
+/// - Hooks run after the change is written (and committed); a failing hook doesn't undo it
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct Hooks {
+    /// Seconds after which a hook is killed, incl. the processes it started
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    /// Issue created (`new`, `import`)
+    #[serde(default)]
+    pub on_new: Vec<String>,
+    /// State changed (`set`)
+    #[serde(default)]
+    pub on_state_change: Vec<String>,
+    /// Assignee changed (`new` and `import` with an assignee, `set`)
+    #[serde(default)]
+    pub on_assign: Vec<String>,
+    /// Description changed (`edit`), comments are part of the description
+    #[serde(default)]
+    pub on_comment: Vec<String>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Hooks {
+            timeout: default_timeout(),
+            on_new: vec![],
+            on_state_change: vec![],
+            on_assign: vec![],
+            on_comment: vec![],
+        }
+    }
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// Issue created (`new`, `import`)
+    New,
+    /// `state` changed (`set`)
+    StateChange,
+    /// `assignee` changed (`new` with an assignee, `set`)
+    Assign,
+    /// Description changed (`edit`): comments are part of description.md
+    Comment,
+}
+
+/// Payload of a hook, written as JSON to stdin
+#[derive(Debug, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+    /// settings.yaml:user who triggered the event
+    pub user: String,
+    pub timestamp: String,
+    /// Meta data of the issue after the change
+    pub issue: Meta,
+    /// Previous value: state for `state_change`, assignee for `assign`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// New value: state for `state_change`, assignee for `assign`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Text added to the description for `comment` (everything after the unchanged beginning)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Event {
+    pub fn new(event: EventKind, issue: &Meta) -> Self {
+        Event {
+            event,
+            user: String::new(),
+            timestamp: current_timestamp(),
+            issue: issue.clone(),
+            from: None,
+            to: None,
+            text: None,
+        }
+    }
+
+    /// Event of a changed value
+    pub fn change(event: EventKind, issue: &Meta, from: &str, to: &str) -> Self {
+        Event {
+            from: Some(from.to_string()),
+            to: Some(to.to_string()),
+            ..Event::new(event, issue)
+        }
+    }
+}
+
+/// Run the hooks of config.yaml for the events
+/// Returns info messages of failed hooks (exit code, timeout), never an error: the change itself succeeded
+pub fn run(mut events: Vec<Event>) -> Vec<String> {
+    let Ok(config) = load_config() else {
+        return vec![];
+    };
+
+    let hooks = config.hooks;
+
+    if events.iter().all(|event| commands(&hooks, event.event).is_empty()) {
+        return vec![];
+    }
+
+    let user = load_settings().map(|(settings, _)| settings.user).unwrap_or_default();
+    let root = gitissues_base().ok().and_then(|base| base.parent().map(|root| root.to_path_buf()));
+
+    let mut infos = Vec::new();
+
+    for event in &mut events {
+        event.user = user.clone();
+
+        let payload = match serde_json::to_string(event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                infos.push(format!("Warning: Failed to serialize hook event: {e}"));
+                continue;
+            }
+        };
+
+        for command in commands(&hooks, event.event) {
+            if let Err(e) = run_command(command, event.event, &payload, root.as_deref(), hooks.timeout) {
+                infos.push(format!(
+                    "Warning: hook {} `{command}` of #{}: {e}",
+                    hook_name(event.event),
+                    event.issue.id
+                ));
+            }
+        }
+    }
+
+    infos
+}
+
+fn commands(hooks: &Hooks, event: EventKind) -> &[String] {
+    match event {
+        EventKind::New => &hooks.on_new,
+        EventKind::StateChange => &hooks.on_state_change,
+        EventKind::Assign => &hooks.on_assign,
+        EventKind::Comment => &hooks.on_comment,
+    }
+}
+
+fn hook_name(event: EventKind) -> &'static str {
+    match event {
+        EventKind::New => "on_new",
+        EventKind::StateChange => "on_state_change",
+        EventKind::Assign => "on_assign",
+        EventKind::Comment => "on_comment",
+    }
+}
+
+/// Run a hook command with the payload on stdin and wait at most `timeout` seconds
+fn run_command(command: &str, event: EventKind, payload: &str, root: Option<&std::path::Path>, timeout: u64) -> Result<(), String> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", command]);
+        process
+    };
+
+    if let Some(root) = root {
+        process.current_dir(root);
+    }
+
+    // Own process group, so that a timeout kills the processes started by the shell as well
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut process, 0);
+
+    let mut child = process
+        .env("GIT_ISSUE_EVENT", hook_name(event))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to start: {e}"))?;
+
+    #[cfg(windows)]
+    let job = windows::Job::assign(&child);
+
+    // Write stdin in the background: a hook which doesn't read it would block on a full pipe, so the timeout must apply.
+    // The hook may exit without reading stdin: a broken pipe is no error
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = payload.to_string();
+        thread::spawn(move || {
+            let _ = stdin.write_all(payload.as_bytes());
+        });
+    }
+
+    // Read stderr in the background, so a chatty hook can't block on a full pipe
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+
+    if let Some(mut stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = stderr_tx.send(output);
+        });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+
+    let status = loop {
+        match child.try_wait().map_err(|e| format!("failed to wait: {e}"))? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                #[cfg(unix)]
+                kill_process_group(child.id());
+                #[cfg(windows)]
+                if let Some(job) = &job {
+                    job.terminate();
+                }
+
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {timeout}s"));
+            }
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+
+    if status.success() {
+        return Ok(());
+    }
+
+    // A process started in the background by the hook may keep stderr open: wait at most until the deadline
+    let stderr = stderr_rx
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .unwrap_or_default();
+
+    match stderr.trim().lines().last() {
+        Some(line) => Err(format!("failed ({status}): {line}")),
+        None => Err(format!("failed ({status})")),
+    }
+}
+
+/// Kill all processes of the hook's process group, i.e. the shell and everything it started
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    let Ok(pgid) = libc::pid_t::try_from(pid) else {
+        return;
+    };
+
+    // SAFETY: plain system call, a negative PID addresses the process group of the hook (see `run_command`)
+    unsafe {
+        libc::kill(-pgid, libc::SIGKILL);
+    }
+}
+
+/// Job object containing the hook and the processes it starts, so that a timeout kills all of them
+#[cfg(windows)]
+mod windows {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW, TerminateJobObject};
+
+    pub struct Job(HANDLE);
+
+    impl Job {
+        /// Job for the child process, `None` if it can't be created (the timeout then only kills the shell)
+        pub fn assign(child: &Child) -> Option<Job> {
+            // SAFETY: the handles are valid for the duration of the calls, the job handle is owned by `Job`
+            unsafe {
+                let job = CreateJobObjectW(ptr::null(), ptr::null());
+
+                if job.is_null() {
+                    return None;
+                }
+
+                let job = Job(job);
+
+                if AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) == 0 {
+                    return None;
+                }
+
+                Some(job)
+            }
+        }
+
+        pub fn terminate(&self) {
+            // SAFETY: the job handle is valid until `Job` is dropped
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            // SAFETY: the job handle is owned by `Job` and closed only once
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use askama::Template;
+use pulldown_cmark::{CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd, html};
+use regex::Regex;
+
+/// Cell of the issue list: column name and value
+pub struct Data {
+    pub key: String,
+    pub value: String,
+}
+
+/// Issue list page (`list.html`)
+/// - site: rendered for the static export (relative links, no column/filter forms)
+/// - inbox: only the watched issues of the user which others changed since the last look (`inbox`)
+#[derive(Template)]
+#[template(path = "list.html")]
+pub struct ListTemplate {
+    pub ids: Vec<u32>,
+    pub rows: Vec<Vec<Data>>,
+    pub user: String,
+    pub columns: Vec<String>,
+    pub filters: Vec<String>,
+    pub site: bool,
+    pub inbox: bool,
+}
+
+/// Issue page (`show.html`)
+/// - html: markdown of `show_markdown` rendered with `markdown_to_html`
+/// - site: rendered for the static export (relative links)
+#[derive(Template)]
+#[template(path = "show.html")]
+pub struct ShowTemplate {
+    pub id: u32,
+    pub html: String,
+    pub site: bool,
+}
+
+/// Render markdown to sanitized HTML (tables, strikethrough, task list checkboxes)
+/// - base: prefix of relative link/image URLs, e.g. `attachments/a.png` of an issue description
+/// - issue_url: target of issue references like `#123` outside of code, links and the title (h1)
+///
+/// Raw HTML in the markdown is sanitized: scripts, event handlers and the like are removed.
+pub fn markdown_to_html(markdown: &str, base: &str, issue_url: &dyn Fn(u32) -> String) -> Result<String, String> {
+    let re = Regex::new(r"#(\d+)").map_err(|e| format!("Invalid regex: {e}"))?;
+
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+
+    let mut events = Vec::new();
+    let mut in_link = 0;
+    let mut in_code_block = false;
+    let mut in_title = false;
+
+    for event in Parser::new_ext(markdown, options) {
+        let event = match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                in_link += 1;
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url: resolve_url(base, dest_url),
+                    title,
+                    id,
+                })
+            }
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: resolve_url(base, dest_url),
+                title,
+                id,
+            }),
+            event => event,
+        };
+
+        match event {
+            Event::End(TagEnd::Link) => in_link -= 1,
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Start(Tag::Heading {
+                level: HeadingLevel::H1, ..
+            }) => in_title = true,
+            Event::End(TagEnd::Heading(HeadingLevel::H1)) => in_title = false,
+            Event::Text(ref text) if in_link == 0 && !in_code_block && !in_title && re.is_match(text) => {
+                let mut last = 0;
+
+                for caps in re.captures_iter(text) {
+                    let (Some(all), Some(id)) = (caps.get(0), caps.get(1)) else {
+                        continue;
+                    };
+
+                    // Too large for an issue ID: keep as text
+                    let Ok(id_num) = id.as_str().parse::<u32>() else {
+                        continue;
+                    };
+
+                    events.push(Event::Text(CowStr::from(text[last..all.start()].to_string())));
+                    events.push(Event::InlineHtml(CowStr::from(format!(
+                        "<a href=\"{}\">#{}</a>",
+                        issue_url(id_num),
+                        id_num
+                    ))));
+                    last = all.end();
+                }
+
+                events.push(Event::Text(CowStr::from(text[last..].to_string())));
+                continue;
+            }
+            _ => {}
+        }
+
+        events.push(event);
+    }
+
+    let mut output = String::new();
+    html::push_html(&mut output, events.into_iter());
+
+    Ok(sanitize(&output))
+}
+
+/// Prefix relative URLs with `base`, keep URLs with a scheme (`https:`, `mailto:`, ...), anchors and absolute paths
+fn resolve_url<'a>(base: &str, url: CowStr<'a>) -> CowStr<'a> {
+    let scheme = url.split_once(':').map(|(scheme, _)| scheme).unwrap_or_default();
+    let has_scheme = !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+    let is_absolute = url.starts_with('/') || url.starts_with('#') || has_scheme;
+
+    if base.is_empty() || url.is_empty() || is_absolute {
+        return url;
+    }
+
+    CowStr::from(format!("{base}{url}"))
+}
+
+fn sanitize(html: &str) -> String {
+    ammonia::Builder::default()
+        // Task list checkboxes
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tag_attributes("td", ["style"])
+        .add_tag_attributes("th", ["style"])
+        // Column alignment of tables
+        .filter_style_properties(HashSet::from(["text-align"]))
+        .link_rel(None)
+        .clean(html)
+        .to_string()
+}
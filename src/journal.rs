@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{current_timestamp, gitissues_base, issue_tmp_dir, load_config};
+use crate::transaction::{Applied, Change};
+
+/// Local record of changes made while `commit_auto` is disabled, so that `undo` works without git history
+/// - Every entry is a directory `.gitissues/.tmp/journal/NNNNNNNNNN/` with an `entry.yaml`
+/// - The content of each file before and after the change is stored next to it (`N.before`, `N.after`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    pub action: String,
+    pub timestamp: String,
+    pub files: Vec<EntryFile>,
+    #[serde(default)]
+    pub created_dirs: Vec<String>,
+}
+
+/// Changed file, path relative to .gitissues; before/after are file names inside the entry directory (None: file does not exist)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntryFile {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+pub fn journal_dir() -> Result<PathBuf, String> {
+    Ok(issue_tmp_dir()?.join("journal"))
+}
+
+/// Record an applied transaction in the journal if auto-commit is disabled (otherwise git history is used)
+pub fn record(action: &str, applied: &Applied) -> Result<(), String> {
+    if load_config()?.commit_auto {
+        return Ok(());
+    }
+
+    record_changes(action, applied.changes(), applied.created_dirs())
+}
+
+/// Record changed files in the journal (unconditionally)
+pub fn record_changes(action: &str, changes: &[Change], created_dirs: &[PathBuf]) -> Result<(), String> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let base = gitissues_base()?;
+    let dir = next_entry_dir()?;
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+
+    let mut files = Vec::new();
+
+    for (i, change) in changes.iter().enumerate() {
+        let before = write_content(&dir, &format!("{i}.before"), change.before.as_deref())?;
+        let after = write_content(&dir, &format!("{i}.after"), change.after.as_deref())?;
+
+        files.push(EntryFile {
+            path: relative_path(&base, &change.path)?,
+            before,
+            after,
+        });
+    }
+
+    let entry = Entry {
+        action: action.to_string(),
+        timestamp: current_timestamp(),
+        files,
+        created_dirs: created_dirs.iter().map(|d| relative_path(&base, d)).collect::<Result<_, _>>()?,
+    };
+
+    let yaml = serde_yaml::to_string(&entry).map_err(|_| "Failed to serialize journal entry".to_string())?;
+    let path = dir.join("entry.yaml");
+    fs::write(&path, yaml).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+    Ok(())
+}
+
+/// Most recent journal entry and its directory
+pub fn last() -> Result<Option<(PathBuf, Entry)>, String> {
+    let Some(dir) = entry_dirs()?.pop() else {
+        return Ok(None);
+    };
+
+    let path = dir.join("entry.yaml");
+    let raw = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let entry: Entry = serde_yaml::from_str(&raw).map_err(|e| format!("Journal entry malformatted: {}: {e}", path.display()))?;
+
+    Ok(Some((dir, entry)))
+}
+
+/// Read the content stored for a file of an entry (None: file did not exist)
+pub fn read_content(dir: &Path, name: Option<&str>) -> Result<Option<Vec<u8>>, String> {
+    match name {
+        Some(name) => {
+            let path = dir.join(name);
+            fs::read(&path)
+                .map(Some)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn remove_entry(dir: &Path) -> Result<(), String> {
+    fs::remove_dir_all(dir).map_err(|e| format!("Failed to remove {}: {e}", dir.display()))
+}
+
+/// Entry directories, oldest first
+fn entry_dirs() -> Result<Vec<PathBuf>, String> {
+    let dir = journal_dir()?;
+
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut dirs: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.join("entry.yaml").exists())
+        .collect();
+
+    dirs.sort();
+
+    Ok(dirs)
+}
+
+fn next_entry_dir() -> Result<PathBuf, String> {
+    let next = match entry_dirs()?.last() {
+        Some(dir) => {
+            let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            name.parse::<u32>()
+                .map_err(|_| format!("Invalid journal entry: {}", dir.display()))?
+                + 1
+        }
+        None => 1,
+    };
+
+    Ok(journal_dir()?.join(format!("{next:010}")))
+}
+
+fn write_content(dir: &Path, name: &str, content: Option<&[u8]>) -> Result<Option<String>, String> {
+    let Some(content) = content else {
+        return Ok(None);
+    };
+
+    let path = dir.join(name);
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+    Ok(Some(name.to_string()))
+}
+
+fn relative_path(base: &Path, path: &Path) -> Result<String, String> {
+    let relative = path
+        .strip_prefix(base)
+        .map_err(|_| format!("Path outside of .gitissues: {}", path.display()))?;
+
+    Ok(relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
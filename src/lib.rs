@@ -1,16 +1,48 @@
 #![deny(warnings, clippy::unwrap_used, clippy::expect_used)]
 
 pub mod cmd;
+pub mod hooks;
+pub mod html;
+pub mod journal;
 pub mod model;
+pub mod notify;
+pub mod storage;
+pub mod trailers;
+pub mod transaction;
 
+pub use crate::cmd::diff;
+pub use crate::cmd::diff::diff;
 pub use crate::cmd::edit::edit_end;
 pub use crate::cmd::edit::edit_start;
+pub use crate::cmd::export::export_html;
+pub use crate::cmd::flow;
+pub use crate::cmd::flow::flow;
+pub use crate::cmd::graph;
+pub use crate::cmd::graph::{graph, graph_render};
+pub use crate::cmd::import;
+pub use crate::cmd::import::import;
+pub use crate::cmd::inbox;
+pub use crate::cmd::inbox::inbox;
 pub use crate::cmd::init::init;
 pub use crate::cmd::link::link;
 pub use crate::cmd::list;
 pub use crate::cmd::list::list;
+pub use crate::cmd::milestone;
+pub use crate::cmd::milestone::{milestone_close, milestone_list, milestone_new, milestone_show};
 pub use crate::cmd::new::new;
+pub use crate::cmd::report;
+pub use crate::cmd::report::changelog;
 pub use crate::cmd::set::set;
 pub use crate::cmd::show::show;
+pub use crate::cmd::show::show_markdown;
+pub use crate::cmd::stats;
+pub use crate::cmd::stats::stats;
+pub use crate::cmd::tree::tree;
+pub use crate::cmd::undo::undo;
+pub use crate::cmd::user;
+pub use crate::cmd::user::{register_user, whoami};
+pub use crate::cmd::watch::{unwatch, watch};
+pub use crate::cmd::worklog;
+pub use crate::cmd::worklog::{log_time, timesheet};
 
 pub use crate::cmd::{Cmd, CmdResult};
@@ -1,16 +1,20 @@
 use std::fmt;
 use std::fs;
 use std::path::PathBuf;
+use std::process::Command;
 use std::str::FromStr;
 
 use chrono::{NaiveDate, Utc};
 use clap::ValueEnum;
 use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::cmd::util::load_meta;
+use crate::hooks::Hooks;
+use crate::notify::Notifications;
 
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ValueEnum, JsonSchema)]
 pub enum Priority {
     // clap default to lower case, so add aliases for upper case too
     #[serde(rename = "")]
@@ -191,37 +195,107 @@ pub struct Meta {
     pub assignee: String,
     pub priority: Priority,
     pub due_date: String,
+    #[serde(default)]
+    pub milestone: String,
+    #[serde(default)]
+    pub estimate: String,
+    /// Users notified about changes, see `inbox`
+    #[serde(default)]
+    pub watchers: Vec<String>,
     pub relationships: IndexMap<String, Vec<u32>>,
     pub created: String,
     pub updated: String,
 }
 
-#[derive(Debug, Deserialize)]
+impl Meta {
+    /// Add a user to the watchers; returns false if the user is '' or already watching
+    pub fn add_watcher(&mut self, user: &str) -> bool {
+        if user.is_empty() || self.watchers.iter().any(|w| w == user) {
+            return false;
+        }
+
+        self.watchers.push(user.to_string());
+        true
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Relationship {
+    /// Name of the reciprocal relationship: same name = symmetric, other name = asymmetric, null = unidirectional
     pub link: Option<String>,
+    #[serde(default)]
+    pub kind: Option<RelationshipKind>,
+}
+
+/// Semantics of a relationship, set on the side whose targets are the blockers/parents
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationshipKind {
+    /// Issue depends on (is blocked by) its targets, no cycles allowed
+    Dependency,
+    /// Issue is a child of its targets, no cycles allowed
+    Hierarchy,
 }
 
-#[derive(Debug, Deserialize)]
+impl fmt::Display for RelationshipKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelationshipKind::Dependency => write!(f, "dependency"),
+            RelationshipKind::Hierarchy => write!(f, "hierarchy"),
+        }
+    }
+}
+
+/// Strategy to generate the ID of new issues
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum IdGeneration {
-    Sequential, // Sequential numbers (1, 2, 3, ...)
-    Timestamp,  // Timestamps in seconds since 2025-01-01
+    /// Sequential numbers (1, 2, 3, ...)
+    Sequential,
+    /// Timestamps in seconds since 2025-01-01 (in teams this reduces the chance of merge conflicts)
+    Timestamp,
 }
 
-#[derive(Debug, Deserialize)]
+/// .gitissues/config.yaml -- shared configuration of the issue tracker (version-controlled)
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Config {
+    /// YAML schema version: don't change manually
     pub _version: u32,
+    /// Automatically create a git commit after mutating commands
     pub commit_auto: bool,
+    /// Commit message template, placeholders: {action}, {id}, {title}
     pub commit_message: String,
+    /// Commit message template of bulk operations with a single commit, placeholders: {action}, {count}, {ids}
+    #[serde(default = "default_commit_message_bulk")]
+    pub commit_message_bulk: String,
+    /// Default columns of `list`, ['*'] for all available columns
     pub list_columns: Vec<String>,
+    /// Available issue states, the first one is the initial state of new issues
     pub states: Vec<String>,
+    /// States which count as done, e.g. a blocker in one of these states no longer blocks
+    #[serde(default = "default_states_done")]
+    pub states_done: Vec<String>,
+    /// Available issue types, the type of new issues is empty per default
     pub types: Vec<String>,
+    /// Available relationships between issues, by name
     pub relationships: IndexMap<String, Relationship>,
     pub id_generation: IdGeneration,
+    /// Priority of new issues
     pub priority_default: Priority,
+    #[serde(default)]
+    pub hooks: Hooks,
+}
+
+fn default_commit_message_bulk() -> String {
+    "[issue] {action} of {count} issues: {ids}".to_string()
 }
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+fn default_states_done() -> Vec<String> {
+    vec!["closed".to_string(), "deleted".to_string()]
+}
+
+/// Color or effect of the terminal output
+#[derive(Debug, Deserialize, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum NamedColor {
     Black,
@@ -243,43 +317,220 @@ pub enum NamedColor {
     Bold,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl NamedColor {
+    /// Hex color for graphical output (graphs, web), None for effects without a color
+    pub fn hex(&self) -> Option<&'static str> {
+        match self {
+            NamedColor::Black => Some("#000000"),
+            NamedColor::BrightBlack => Some("#666666"),
+            NamedColor::Red => Some("#cd3131"),
+            NamedColor::BrightRed => Some("#f14c4c"),
+            NamedColor::Green => Some("#0dbc79"),
+            NamedColor::BrightGreen => Some("#23d18b"),
+            NamedColor::Yellow => Some("#e5e510"),
+            NamedColor::BrightYellow => Some("#f5f543"),
+            NamedColor::Blue => Some("#2472c8"),
+            NamedColor::BrightBlue => Some("#3b8eea"),
+            NamedColor::Magenta => Some("#bc3fbc"),
+            NamedColor::BrightMagenta => Some("#d670d6"),
+            NamedColor::Cyan => Some("#11a8cd"),
+            NamedColor::BrightCyan => Some("#29b8db"),
+            NamedColor::White => Some("#e5e5e5"),
+            NamedColor::BrightWhite => Some("#ffffff"),
+            NamedColor::Bold => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct Colors {
+    /// Header row
     pub header: NamedColor,
+    /// Assignee or reporter matching the current user
     pub me: NamedColor,
+    /// due_date when it is overdue
     pub due_date_overdue: NamedColor,
+    /// Color per state, also used for the nodes of `graph` and the web graph
     pub state: IndexMap<String, NamedColor>,
+    /// Color per priority
     pub priority: IndexMap<String, NamedColor>,
+    /// Color per type
     #[serde(rename = "type")]
     pub type_: IndexMap<String, NamedColor>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Formatting options of `list`
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct ListFormatting {
+    /// Print a dashed line as header row separator
     pub header_separator: bool,
     pub colors: Colors,
 }
 
-#[derive(Debug, Deserialize)]
+/// .gitissues/settings.yaml -- local user settings (not version-controlled)
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Settings {
+    /// YAML schema version: don't change manually
     pub _version: u32,
+    /// External editor of issue descriptions, `git` for the git core.editor
     pub editor: String,
+    /// Current user, the default reporter of new issues: users.yaml:users:id or '' for the git identity (see `whoami`)
     pub user: String,
+    /// Separator of `list --csv` and `import --csv`
     pub export_csv_separator: char,
     pub list_formatting: ListFormatting,
+    #[serde(default)]
+    pub notifications: Notifications,
 }
 
-#[derive(Debug, Deserialize)]
+/// Member of the team
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct User {
+    /// Unique ID, e.g. the value of reporter and assignee
     pub id: String,
-}
-
-#[derive(Debug, Deserialize)]
+    /// Display name in `list`, `show` and web, the ID if empty
+    #[serde(default)]
+    pub name: String,
+    /// Mapped to the user, e.g. git `user.email`
+    #[serde(default)]
+    pub emails: Vec<String>,
+    /// Alternative IDs accepted as input, e.g. the login of an issue tracker
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Inactive users can't be assigned anymore, but stay valid on existing issues
+    #[serde(default = "default_true")]
+    pub active: bool,
+    /// Teams the user is a member of, filterable via `@team`
+    #[serde(default)]
+    pub teams: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// .gitissues/users.yaml -- available users (version-controlled)
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Users {
+    /// YAML schema version: don't change manually
     pub _version: u32,
     pub users: Vec<User>,
 }
 
+impl Users {
+    pub fn get(&self, id: &str) -> Option<&User> {
+        self.users.iter().find(|u| u.id == id)
+    }
+
+    /// User by ID, alias or email (case-insensitive), an exact ID takes precedence
+    pub fn find(&self, value: &str) -> Option<&User> {
+        self.get(value).or_else(|| {
+            self.users.iter().find(|u| {
+                u.id.eq_ignore_ascii_case(value)
+                    || u.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(value))
+                    || u.emails.iter().any(|email| email.eq_ignore_ascii_case(value))
+            })
+        })
+    }
+
+    /// IDs of the members of a team, incl. inactive users
+    pub fn team_members(&self, team: &str) -> Vec<&str> {
+        self.users
+            .iter()
+            .filter(|u| u.teams.iter().any(|t| t == team))
+            .map(|u| u.id.as_str())
+            .collect()
+    }
+
+    /// Name of a user, the value itself if there is no name (e.g. unknown or empty users)
+    pub fn display_name(&self, id: &str) -> String {
+        match self.get(id) {
+            Some(user) if !user.name.is_empty() => user.name.clone(),
+            _ => id.to_string(),
+        }
+    }
+
+    /// IDs of the active users, i.e. valid for new assignments
+    pub fn active_ids(&self) -> Vec<&str> {
+        self.users.iter().filter(|u| u.active).map(|u| u.id.as_str()).collect()
+    }
+}
+
+/// Entry of the work log of an issue, one file per entry (append-only, no merge conflicts)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkLogEntry {
+    pub _version: u32,
+    pub author: String,
+    /// Day the work was done: YYYY-MM-DD
+    pub date: String,
+    /// Logged time, e.g. 2h30m
+    pub spent: String,
+    #[serde(default)]
+    pub note: String,
+    pub created: String,
+}
+
+/// Issues can only be added to open milestones
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MilestoneState {
+    /// Issues can be added
+    #[default]
+    Open,
+    /// Closed with `milestone close`
+    Closed,
+}
+
+impl fmt::Display for MilestoneState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MilestoneState::Open => write!(f, "open"),
+            MilestoneState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// Milestone or sprint
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Milestone {
+    /// Unique name, the value of the issue field `milestone` (without ',' and '*')
+    pub name: String,
+    /// Start date: YYYY-MM-DD or ''
+    #[serde(default)]
+    pub start: String,
+    /// Due date: YYYY-MM-DD or ''
+    #[serde(default)]
+    pub due: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub state: MilestoneState,
+}
+
+/// .gitissues/milestones.yaml -- milestones / sprints, managed with `milestone new|close` (version-controlled)
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Milestones {
+    /// YAML schema version: don't change manually
+    pub _version: u32,
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+}
+
+impl Default for Milestones {
+    fn default() -> Self {
+        Milestones {
+            _version: 1,
+            milestones: vec![],
+        }
+    }
+}
+
+impl Milestones {
+    pub fn get(&self, name: &str) -> Option<&Milestone> {
+        self.milestones.iter().find(|m| m.name == name)
+    }
+}
+
 /// Load users.yaml
 pub fn load_users() -> Result<Users, String> {
     let users_path = users_path()?;
@@ -296,6 +547,22 @@ pub fn load_users() -> Result<Users, String> {
     Ok(users)
 }
 
+/// Load milestones.yaml, no milestones if it does not exist (e.g. initialized before milestones were introduced)
+pub fn load_milestones() -> Result<Milestones, String> {
+    let milestones_path = milestones_path()?;
+    let milestones_raw = match fs::read_to_string(&milestones_path) {
+        Ok(s) => s,
+        Err(_) => return Ok(Milestones::default()),
+    };
+
+    let milestones: Milestones = match serde_yaml::from_str(&milestones_raw) {
+        Ok(m) => m,
+        Err(e) => return Err(format!("milestones.yaml malformatted: {e}")),
+    };
+
+    Ok(milestones)
+}
+
 /// Generate a proper ISO 8601 timestamp using chrono.
 pub fn current_timestamp() -> String {
     Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
@@ -306,6 +573,39 @@ pub fn is_valid_iso_date(s: &str) -> Result<bool, String> {
     Ok(s.is_empty() || NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok())
 }
 
+/// Parse a duration like `2h30m`, `2h` or `45m` into minutes
+pub fn parse_duration(s: &str) -> Result<u32, String> {
+    let invalid = || format!("Invalid duration \"{s}\": Use e.g. '2h30m', '2h' or '45m'");
+
+    let re = regex::Regex::new(r"^(?:(\d+)h)?(?:(\d+)m)?$").map_err(|e| format!("Invalid regex for durations: {e}"))?;
+    let captures = re.captures(s.trim()).filter(|_| !s.trim().is_empty()).ok_or_else(invalid)?;
+
+    let number = |i: usize| -> Result<u32, String> { captures.get(i).map_or(Ok(0), |m| m.as_str().parse::<u32>().map_err(|_| invalid())) };
+
+    number(1)?
+        .checked_mul(60)
+        .and_then(|hours| hours.checked_add(number(2).ok()?))
+        .ok_or_else(invalid)
+}
+
+/// Canonical form of an estimate: '' (none) or a duration like `2h30m`
+pub fn normalize_estimate(value: &str) -> Result<String, String> {
+    if value.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    Ok(format_duration(parse_duration(value)?))
+}
+
+/// Format minutes as duration: `2h30m`, `2h`, `45m` or `0m`
+pub fn format_duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m}m"),
+    }
+}
+
 /// Validate if a state is in the list of valid states from config.
 pub fn is_valid_state(config: &Config, s: &str) -> bool {
     config.states.contains(&s.to_string())
@@ -316,9 +616,14 @@ pub fn is_valid_type(config: &Config, s: &str) -> bool {
     s.is_empty() || config.types.contains(&s.to_string())
 }
 
-/// Validate if an user is in the list of valid users:id from users.yaml.
+/// Validate if a milestone is defined in milestones.yaml and open, or empty.
+pub fn is_valid_milestone(milestones: &Milestones, s: &str) -> bool {
+    s.is_empty() || milestones.get(s).is_some_and(|m| m.state == MilestoneState::Open)
+}
+
+/// Validate if a user is in users.yaml, incl. inactive users (e.g. on existing issues)
 pub fn is_valid_user(users: &Users, s: &str) -> bool {
-    s.is_empty() || s == "me" || users.users.iter().any(|u| u.id == s)
+    s.is_empty() || s == "me" || users.get(s).is_some()
 }
 
 pub fn load_config() -> Result<Config, String> {
@@ -336,7 +641,25 @@ pub fn load_config() -> Result<Config, String> {
     Ok(config)
 }
 
+const DEFAULT_SETTINGS: &str = include_str!("../config/settings-default.yaml");
+
+/// Load settings.yaml; if `user` is empty, it is the user of users.yaml matching the git identity
 pub fn load_settings() -> Result<(Settings, Vec<String>), String> {
+    let (mut settings, info) = load_settings_file()?;
+
+    // No user configured: the user of users.yaml matching the git identity
+    if settings.user.is_empty()
+        && let Ok(users) = load_users()
+        && let Some((user, _)) = GitUserConfig::read().find_user(&users)
+    {
+        settings.user = user.id.clone();
+    }
+
+    Ok((settings, info))
+}
+
+/// Load settings.yaml as written, i.e. `user` without the fallback to the git identity
+pub fn load_settings_file() -> Result<(Settings, Vec<String>), String> {
     let settings_path = settings_path()?;
 
     let info = create_settings_if_missing(true)?;
@@ -354,8 +677,92 @@ pub fn load_settings() -> Result<(Settings, Vec<String>), String> {
     Ok((settings, info))
 }
 
+/// Load settings.yaml, falling back to the default settings when there is no .gitissues directory (e.g. bare repositories)
+pub fn load_settings_or_default() -> Result<(Settings, Vec<String>), String> {
+    if gitissues_base().is_ok() {
+        return load_settings();
+    }
+
+    let settings: Settings = serde_yaml::from_str(DEFAULT_SETTINGS).map_err(|e| format!("settings-default.yaml malformatted: {e}"))?;
+
+    Ok((settings, vec![]))
+}
+
+/// Git config key by which the git identity matched a user of users.yaml
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitIdentity {
+    Email,
+    Name,
+}
+
+impl fmt::Display for GitIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitIdentity::Email => write!(f, "git user.email"),
+            GitIdentity::Name => write!(f, "git user.name"),
+        }
+    }
+}
+
+/// Git identity (`user.name`, `user.email`) of the current repository
+#[derive(Debug, Clone, Default)]
+pub struct GitUserConfig {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl GitUserConfig {
+    /// Read both keys with a single `git config` call, unset or empty keys are `None`
+    /// Not cached: the configuration depends on the current directory and may change while a process runs (web server)
+    pub fn read() -> GitUserConfig {
+        let mut identity = GitUserConfig::default();
+
+        let Ok(output) = Command::new("git")
+            .args(["config", "--get-regexp", r"^user\.(name|email)$"])
+            .output()
+        else {
+            return identity;
+        };
+
+        // The last value of a key wins, like `git config --get`
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+
+            match key {
+                "user.name" => identity.name = value,
+                "user.email" => identity.email = value,
+                _ => {}
+            }
+        }
+
+        identity
+    }
+
+    /// User of users.yaml matching the git identity:
+    /// `user.email` in users.yaml:users:emails, otherwise `user.name` as ID, alias or name (case-insensitive)
+    pub fn find_user<'a>(&self, users: &'a Users) -> Option<(&'a User, GitIdentity)> {
+        if let Some(email) = &self.email
+            && let Some(user) = users.users.iter().find(|u| u.emails.iter().any(|e| e.eq_ignore_ascii_case(email)))
+        {
+            return Some((user, GitIdentity::Email));
+        }
+
+        let name = self.name.as_deref()?;
+
+        users
+            .users
+            .iter()
+            .find(|u| {
+                u.id.eq_ignore_ascii_case(name)
+                    || u.name.eq_ignore_ascii_case(name)
+                    || u.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+            })
+            .map(|user| (user, GitIdentity::Name))
+    }
+}
+
 pub fn create_settings_if_missing(print: bool) -> Result<Vec<String>, String> {
-    const DEFAULT_SETTINGS: &str = include_str!("../config/settings-default.yaml");
     let settings_dst = settings_path()?;
 
     if let Ok(true) = fs::exists(&settings_dst) {
@@ -366,7 +773,14 @@ pub fn create_settings_if_missing(print: bool) -> Result<Vec<String>, String> {
         .map_err(|e| format!("Failed to write default settings to {}: {e}", settings_dst.display()))?;
 
     if print {
-        return Ok(vec![format!("Created default local user settings at {}", settings_dst.display())]);
+        let mut infos = vec![format!("Created default local user settings at {}", settings_dst.display())];
+
+        // First run in this clone: offer to register the git identity
+        if users_path()?.exists() {
+            infos.extend(crate::cmd::user::register_hint()?);
+        }
+
+        return Ok(infos);
     }
 
     Ok(vec![])
@@ -419,6 +833,11 @@ pub fn users_path() -> Result<std::path::PathBuf, String> {
     Ok(gitissues_base()?.join("users.yaml"))
 }
 
+/// Returns the path to the milestones.yaml file.
+pub fn milestones_path() -> Result<std::path::PathBuf, String> {
+    Ok(gitissues_base()?.join("milestones.yaml"))
+}
+
 pub fn issue_dir(id: u32) -> Result<std::path::PathBuf, String> {
     Ok(issues_dir()?.join(padded_id(id)))
 }
@@ -431,6 +850,10 @@ pub fn issue_desc_path(id: u32) -> Result<std::path::PathBuf, String> {
     Ok(issue_dir(id)?.join("description.md"))
 }
 
+pub fn issue_worklog_dir(id: u32) -> Result<std::path::PathBuf, String> {
+    Ok(issue_dir(id)?.join("worklog"))
+}
+
 pub fn issue_attachments_dir(id: u32) -> Result<std::path::PathBuf, String> {
     Ok(issue_dir(id)?.join("attachments"))
 }
@@ -0,0 +1,497 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use askama::Template;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{Message, Transport as _};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::cmd::list::get_column_value;
+use crate::hooks::{Event, EventKind};
+use crate::model::{Meta, Users, gitissues_base, load_settings, load_users};
+
+/// settings.yaml:notifications -- emails on assignment and state changes
+/// - Sending never fails the command: errors are reported as info
+/// - Mails are sent by a background worker, a command waits at most one second for it (see `run`)
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct Notifications {
+    #[serde(default)]
+    pub transport: TransportKind,
+    /// Sender, e.g. `git-issue <issues@example.com>`
+    #[serde(default)]
+    pub from: String,
+    /// Mail the new assignee
+    #[serde(default = "default_true")]
+    pub on_assign: bool,
+    /// Mail the assignee and the watchers
+    #[serde(default = "default_true")]
+    pub on_state_change: bool,
+    #[serde(default)]
+    pub smtp: Smtp,
+    /// Directory of `transport: file`, relative to the repository root
+    #[serde(default = "default_file_dir")]
+    pub file_dir: String,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Notifications {
+            transport: TransportKind::None,
+            from: String::new(),
+            on_assign: true,
+            on_state_change: true,
+            smtp: Smtp::default(),
+            file_dir: default_file_dir(),
+        }
+    }
+}
+
+/// Delivery of the mails
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// Notifications disabled
+    #[default]
+    None,
+    /// Send via the SMTP server of `smtp`
+    Smtp,
+    /// Write the mails as `.eml` files, e.g. to try out the templates
+    File,
+}
+
+/// SMTP server of `transport: smtp`
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct Smtp {
+    #[serde(default)]
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    /// Login, '' for no authentication
+    #[serde(default)]
+    pub username: String,
+    /// Environment variable holding the password, so it's not stored in a file
+    #[serde(default = "default_password_env")]
+    pub password_env: String,
+    /// Seconds after which connecting or sending is given up
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for Smtp {
+    fn default() -> Self {
+        Smtp {
+            host: String::new(),
+            port: default_port(),
+            tls: SmtpTls::default(),
+            username: String::new(),
+            password_env: default_password_env(),
+            timeout: default_timeout(),
+        }
+    }
+}
+
+/// Encryption of the SMTP connection
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Plain text, e.g. a local relay
+    None,
+    /// Upgrade the connection via STARTTLS (usually port 587)
+    #[default]
+    Starttls,
+    /// TLS from the start (usually port 465)
+    Tls,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_file_dir() -> String {
+    ".gitissues/.tmp/mails".to_string()
+}
+
+fn default_port() -> u16 {
+    587
+}
+
+fn default_password_env() -> String {
+    "GIT_ISSUE_SMTP_PASSWORD".to_string()
+}
+
+fn default_timeout() -> u64 {
+    5
+}
+
+/// Rendered email
+pub struct Mail {
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers mails: SMTP server, files, or a fake in tests
+pub trait Transport {
+    fn send(&self, mail: &Mail) -> Result<(), String>;
+}
+
+/// Delivery via an SMTP server (blocking, bounded by `smtp.timeout`)
+pub struct SmtpTransport {
+    transport: lettre::SmtpTransport,
+}
+
+impl SmtpTransport {
+    pub fn new(smtp: &Smtp) -> Result<Self, String> {
+        if smtp.host.is_empty() {
+            return Err("settings.yaml:notifications:smtp:host is empty".to_string());
+        }
+
+        let tls_parameters = || TlsParameters::new(smtp.host.clone()).map_err(|e| format!("Invalid TLS parameters: {e}"));
+
+        let tls = match smtp.tls {
+            SmtpTls::None => Tls::None,
+            SmtpTls::Starttls => Tls::Required(tls_parameters()?),
+            SmtpTls::Tls => Tls::Wrapper(tls_parameters()?),
+        };
+
+        let mut builder = lettre::SmtpTransport::builder_dangerous(&smtp.host)
+            .port(smtp.port)
+            .tls(tls)
+            .timeout(Some(Duration::from_secs(smtp.timeout)));
+
+        if !smtp.username.is_empty() {
+            let password = std::env::var(&smtp.password_env).map_err(|_| format!("SMTP password: ${} is not set", smtp.password_env))?;
+            builder = builder.credentials(Credentials::new(smtp.username.clone(), password));
+        }
+
+        Ok(SmtpTransport {
+            transport: builder.build(),
+        })
+    }
+}
+
+impl Transport for SmtpTransport {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        let message = mail.message()?;
+        self.transport.send(&message).map(|_| ()).map_err(|e| format!("SMTP: {e}"))
+    }
+}
+
+/// Writes every mail into a new `.eml` file of a directory
+pub struct FileTransport {
+    pub dir: PathBuf,
+}
+
+impl Transport for FileTransport {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        let message = mail.message()?;
+
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create {}: {e}", self.dir.display()))?;
+
+        // First free number: mails of one command keep their order
+        let count = fs::read_dir(&self.dir).map(|entries| entries.count()).unwrap_or(0);
+        let mut n = count + 1;
+
+        while self.dir.join(format!("{n:06}.eml")).exists() {
+            n += 1;
+        }
+
+        let path = self.dir.join(format!("{n:06}.eml"));
+        fs::write(&path, message.formatted()).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+}
+
+impl Mail {
+    fn message(&self) -> Result<Message, String> {
+        let mut builder = Message::builder()
+            .from(parse_mailbox(&self.from)?)
+            .subject(&self.subject)
+            .header(ContentType::TEXT_PLAIN);
+
+        for to in &self.to {
+            builder = builder.to(parse_mailbox(to)?);
+        }
+
+        builder.body(self.body.clone()).map_err(|e| format!("Failed to build mail: {e}"))
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, String> {
+    address.parse().map_err(|e| format!("Invalid email address '{address}': {e}"))
+}
+
+/// Mail body of an assignment (`assign.txt`)
+#[derive(Template)]
+#[template(path = "mail/assign.txt")]
+struct AssignTemplate<'a> {
+    issue: &'a Meta,
+    priority: String,
+    user: String,
+    assignee: String,
+}
+
+/// Mail body of a state change (`state_change.txt`)
+#[derive(Template)]
+#[template(path = "mail/state_change.txt")]
+struct StateChangeTemplate<'a> {
+    issue: &'a Meta,
+    priority: String,
+    user: String,
+    assignee: String,
+    from: &'a str,
+    to: &'a str,
+}
+
+/// Send the notifications of settings.yaml for the events
+/// The mails are delivered by the background worker of this module; `run` waits for it at most `DELIVERY_WAIT`,
+/// mails still being sent then are reported as info, their failures go to stderr (see `wait`).
+/// Returns info messages of failed deliveries, never an error: the change itself succeeded
+pub fn run(events: &[Event]) -> Vec<String> {
+    let Ok((settings, _)) = load_settings() else {
+        return vec![];
+    };
+
+    let notifications = settings.notifications;
+
+    let transport: Box<dyn Transport + Send> = match notifications.transport {
+        TransportKind::None => return vec![],
+        TransportKind::Smtp => match SmtpTransport::new(&notifications.smtp) {
+            Ok(transport) => Box::new(transport),
+            Err(e) => return vec![format!("Warning: Notifications not sent: {e}")],
+        },
+        TransportKind::File => {
+            let root = gitissues_base().ok().and_then(|base| base.parent().map(|root| root.to_path_buf()));
+            Box::new(FileTransport {
+                dir: root.unwrap_or_default().join(&notifications.file_dir),
+            })
+        }
+    };
+
+    let users = match load_users() {
+        Ok(users) => users,
+        Err(e) => return vec![format!("Warning: Notifications not sent: {e}")],
+    };
+
+    let (mails, mut infos) = mails(&notifications, &users, &settings.user, events);
+    infos.extend(deliver(transport, mails));
+
+    infos
+}
+
+/// Render and send the mails of the events via a transport, in the calling thread
+/// - user: who made the change, never notified about it
+/// - Users without email or inactive users are skipped
+pub fn send(transport: &dyn Transport, notifications: &Notifications, users: &Users, user: &str, events: &[Event]) -> Vec<String> {
+    let (mails, mut infos) = mails(notifications, users, user, events);
+
+    for (id, mail) in mails {
+        if let Err(e) = transport.send(&mail) {
+            infos.push(failure(id, &mail, &e));
+        }
+    }
+
+    infos
+}
+
+/// Mails of the events with their issue IDs, and info messages of the events whose mail can't be rendered
+fn mails(notifications: &Notifications, users: &Users, user: &str, events: &[Event]) -> (Vec<(u32, Mail)>, Vec<String>) {
+    let mut mails = Vec::new();
+    let mut infos = Vec::new();
+
+    for event in events {
+        match mail(notifications, users, user, event) {
+            Ok(Some(mail)) => mails.push((event.issue.id, mail)),
+            Ok(None) => {}
+            Err(e) => infos.push(format!("Warning: Notification of #{} not sent: {e}", event.issue.id)),
+        }
+    }
+
+    (mails, infos)
+}
+
+fn failure(id: u32, mail: &Mail, error: &str) -> String {
+    format!("Warning: Notification of #{id} to {} not sent: {error}", mail.to.join(", "))
+}
+
+/// How long `run` waits for the background worker, independent of the number of mails
+const DELIVERY_WAIT: Duration = Duration::from_secs(1);
+
+/// Mails of one `run` for the background worker
+struct Delivery {
+    transport: Box<dyn Transport + Send>,
+    mails: Vec<(u32, Mail)>,
+    /// Per mail: the failure, `None` if sent
+    results: mpsc::Sender<Option<String>>,
+}
+
+/// Number of mails handed to the background worker and not delivered yet
+static PENDING: Mutex<usize> = Mutex::new(0);
+static DELIVERED: Condvar = Condvar::new();
+
+/// Queue of the background worker, which is started by the first delivery and runs until the process exits
+fn worker() -> &'static mpsc::Sender<Delivery> {
+    static WORKER: OnceLock<mpsc::Sender<Delivery>> = OnceLock::new();
+
+    WORKER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Delivery>();
+
+        thread::spawn(move || {
+            for delivery in receiver {
+                for (id, mail) in &delivery.mails {
+                    let result = delivery.transport.send(mail).err().map(|e| failure(*id, mail, &e));
+
+                    // `run` doesn't wait anymore: the failure has no other place to go
+                    if let Err(mpsc::SendError(Some(failure))) = delivery.results.send(result) {
+                        eprintln!("{failure}");
+                    }
+
+                    *pending() -= 1;
+                    DELIVERED.notify_all();
+                }
+            }
+        });
+
+        sender
+    })
+}
+
+fn pending() -> MutexGuard<'static, usize> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Hand the mails to the background worker, and wait up to `DELIVERY_WAIT` for their results
+fn deliver(transport: Box<dyn Transport + Send>, mails: Vec<(u32, Mail)>) -> Vec<String> {
+    let count = mails.len();
+
+    if count == 0 {
+        return vec![];
+    }
+
+    let (results, received) = mpsc::channel();
+    *pending() += count;
+
+    if let Err(mpsc::SendError(delivery)) = worker().send(Delivery { transport, mails, results }) {
+        *pending() -= count;
+        return delivery
+            .mails
+            .iter()
+            .map(|(id, mail)| failure(*id, mail, "background worker not running"))
+            .collect();
+    }
+
+    let deadline = Instant::now() + DELIVERY_WAIT;
+    let mut infos = Vec::new();
+
+    for delivered in 0..count {
+        match received.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(result) => infos.extend(result),
+            Err(_) => {
+                infos.push(format!(
+                    "Info: {} notification(s) still being sent in the background",
+                    count - delivered
+                ));
+                break;
+            }
+        }
+    }
+
+    infos
+}
+
+/// Wait until the background worker has delivered all mails, e.g. before the process exits
+/// Every mail is bounded by settings.yaml:notifications:smtp:timeout, failures are printed to stderr
+pub fn wait() {
+    let mut pending = pending();
+
+    while *pending > 0 {
+        pending = DELIVERED.wait(pending).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// Mail of an event, `None` if nobody is to be notified
+fn mail(notifications: &Notifications, users: &Users, user: &str, event: &Event) -> Result<Option<Mail>, String> {
+    let issue = &event.issue;
+    let from = event.from.as_deref().unwrap_or_default();
+    let to = event.to.as_deref().unwrap_or_default();
+
+    // Same text as in `list`
+    let priority = get_column_value("priority", issue)?;
+
+    let (recipients, subject, body) = match event.event {
+        EventKind::Assign if notifications.on_assign => {
+            let template = AssignTemplate {
+                issue,
+                priority,
+                user: users.display_name(user),
+                assignee: users.display_name(to),
+            };
+
+            (
+                vec![to],
+                format!("#{} {}: assigned to you", issue.id, issue.title),
+                template.render(),
+            )
+        }
+        EventKind::StateChange if notifications.on_state_change => {
+            let template = StateChangeTemplate {
+                issue,
+                priority,
+                user: users.display_name(user),
+                assignee: users.display_name(&issue.assignee),
+                from,
+                to,
+            };
+
+            let mut recipients = vec![issue.assignee.as_str()];
+            recipients.extend(issue.watchers.iter().map(String::as_str));
+
+            (
+                recipients,
+                format!("#{} {}: {from} -> {to}", issue.id, issue.title),
+                template.render(),
+            )
+        }
+        _ => return Ok(None),
+    };
+
+    let mut emails: Vec<String> = Vec::new();
+
+    for id in recipients {
+        if id.is_empty() || id == user {
+            continue;
+        }
+
+        let Some(email) = users.get(id).filter(|u| u.active).and_then(|u| u.emails.first()) else {
+            continue;
+        };
+
+        if !emails.contains(email) {
+            emails.push(email.clone());
+        }
+    }
+
+    if emails.is_empty() {
+        return Ok(None);
+    }
+
+    if notifications.from.is_empty() {
+        return Err("settings.yaml:notifications:from is empty".to_string());
+    }
+
+    Ok(Some(Mail {
+        from: notifications.from.clone(),
+        to: emails,
+        subject,
+        body: body.map_err(|e| format!("Failed to render mail: {e}"))?,
+    }))
+}
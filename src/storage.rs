@@ -0,0 +1,444 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::de::DeserializeOwned;
+
+use crate::model::{Config, Meta, Milestones, Users, WorkLogEntry, gitissues_base, padded_id};
+
+/// Entry of a directory listing, relative to its parent directory
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Read access to the contents of `.gitissues/`
+/// All paths are relative to `.gitissues/` and use `/` as separator
+pub trait Storage: Send + Sync {
+    /// Read a file, `None` if it does not exist
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String>;
+
+    /// List the entries directly below a directory, empty if it does not exist
+    fn list_dir(&self, path: &str) -> Result<Vec<DirEntry>, String>;
+
+    /// Human-readable location of a path, used in error messages
+    fn location(&self, path: &str) -> String;
+
+    /// Git history of the issues up to this state, `None` if not tracked in a git repository
+    fn history(&self) -> Option<GitStorage> {
+        None
+    }
+
+    /// Read multiple files at once (backends may batch the reads)
+    fn read_many(&self, paths: &[String]) -> Result<Vec<Option<Vec<u8>>>, String> {
+        paths.iter().map(|path| self.read(path)).collect()
+    }
+
+    /// IDs of all issues, in no particular order
+    fn issue_ids(&self) -> Result<Vec<u32>, String> {
+        Ok(self
+            .list_dir("issues")?
+            .into_iter()
+            .filter(|entry| entry.is_dir)
+            .filter_map(|entry| entry.name.parse::<u32>().ok()) // skip non-numeric directories
+            .collect())
+    }
+
+    fn load_meta(&self, id: u32) -> Result<Meta, String> {
+        let path = issue_path(id, "meta.yaml");
+        let raw = self
+            .read(&path)?
+            .ok_or_else(|| format!("meta.yaml not found: {}", self.location(&path)))?;
+        serde_yaml::from_slice(&raw).map_err(|e| format!("meta.yaml malformatted: {}: {e}", self.location(&path)))
+    }
+
+    /// Load the metadata of all issues
+    fn load_metas(&self) -> Result<Vec<Meta>, String> {
+        let paths: Vec<String> = self.issue_ids()?.into_iter().map(|id| issue_path(id, "meta.yaml")).collect();
+
+        let mut metas = Vec::new();
+
+        for (path, raw) in paths.iter().zip(self.read_many(&paths)?) {
+            let raw = raw.ok_or_else(|| format!("meta.yaml not found: {}", self.location(path)))?;
+            let meta = serde_yaml::from_slice(&raw).map_err(|e| format!("meta.yaml malformatted: {}: {e}", self.location(path)))?;
+            metas.push(meta);
+        }
+
+        Ok(metas)
+    }
+
+    fn load_description(&self, id: u32) -> Result<String, String> {
+        let path = issue_path(id, "description.md");
+        let raw = self
+            .read(&path)?
+            .ok_or_else(|| format!("description.md not found: {}", self.location(&path)))?;
+        Ok(String::from_utf8_lossy(&raw).to_string())
+    }
+
+    /// Names of the attachments of an issue, including files in subdirectories (e.g. `img/a.png`)
+    fn attachments(&self, id: u32) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        let mut pending = vec![String::new()]; // subdirectories relative to attachments/
+
+        while let Some(sub_dir) = pending.pop() {
+            for entry in self.list_dir(&issue_path(id, &format!("attachments/{sub_dir}")))? {
+                let name = format!("{sub_dir}{}", entry.name);
+
+                if entry.is_dir {
+                    pending.push(format!("{name}/"));
+                } else if entry.name != ".gitkeep" {
+                    names.push(name);
+                }
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    fn read_attachment(&self, id: u32, name: &str) -> Result<Option<Vec<u8>>, String> {
+        if name.split('/').any(|part| part == ".." || part.is_empty()) {
+            return Ok(None);
+        }
+
+        self.read(&issue_path(id, &format!("attachments/{name}")))
+    }
+
+    /// Work log of an issue (one file per entry in `worklog/`), sorted by date and creation
+    fn load_worklog(&self, id: u32) -> Result<Vec<WorkLogEntry>, String> {
+        let paths: Vec<String> = self
+            .list_dir(&issue_path(id, "worklog"))?
+            .into_iter()
+            .filter(|entry| !entry.is_dir && entry.name.ends_with(".yaml"))
+            .map(|entry| issue_path(id, &format!("worklog/{}", entry.name)))
+            .collect();
+
+        let mut entries: Vec<WorkLogEntry> = Vec::new();
+
+        for (path, raw) in paths.iter().zip(self.read_many(&paths)?) {
+            let raw = raw.ok_or_else(|| format!("Work log entry not found: {}", self.location(path)))?;
+            let entry = serde_yaml::from_slice(&raw).map_err(|e| format!("Work log entry malformatted: {}: {e}", self.location(path)))?;
+            entries.push(entry);
+        }
+
+        entries.sort_by(|a, b| a.date.cmp(&b.date).then(a.created.cmp(&b.created)));
+
+        Ok(entries)
+    }
+
+    fn load_config(&self) -> Result<Config, String> {
+        load_yaml(self, "config.yaml")
+    }
+
+    fn load_users(&self) -> Result<Users, String> {
+        load_yaml(self, "users.yaml")
+    }
+
+    /// No milestones if milestones.yaml does not exist
+    fn load_milestones(&self) -> Result<Milestones, String> {
+        match self.read("milestones.yaml")? {
+            Some(raw) => serde_yaml::from_slice(&raw).map_err(|e| format!("milestones.yaml malformatted: {e}")),
+            None => Ok(Milestones::default()),
+        }
+    }
+}
+
+/// Path of a file inside an issue directory, relative to `.gitissues/`
+pub fn issue_path(id: u32, file: &str) -> String {
+    format!("issues/{}/{file}", padded_id(id))
+}
+
+fn load_yaml<T: DeserializeOwned, S: Storage + ?Sized>(storage: &S, path: &str) -> Result<T, String> {
+    let raw = storage.read(path)?.ok_or_else(|| format!("{path} not found."))?;
+    serde_yaml::from_slice(&raw).map_err(|e| format!("{path} malformatted: {e}"))
+}
+
+/// Storage backed by the `.gitissues/` directory of the working tree
+pub struct FsStorage {
+    base: PathBuf,
+}
+
+impl FsStorage {
+    pub fn new(base: PathBuf) -> Self {
+        FsStorage { base }
+    }
+
+    /// Use the `.gitissues/` directory found from the current directory upwards
+    pub fn discover() -> Result<Self, String> {
+        Ok(FsStorage::new(gitissues_base()?))
+    }
+
+    fn path(&self, path: &str) -> PathBuf {
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .fold(self.base.clone(), |acc, part| acc.join(part))
+    }
+}
+
+impl Storage for FsStorage {
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let full_path = self.path(path);
+
+        if !full_path.is_file() {
+            return Ok(None);
+        }
+
+        fs::read(&full_path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read {}: {e}", full_path.display()))
+    }
+
+    fn list_dir(&self, path: &str) -> Result<Vec<DirEntry>, String> {
+        let dir = self.path(path);
+
+        if !dir.is_dir() {
+            return Ok(vec![]);
+        }
+
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))? {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+            let file_type = entry.file_type().map_err(|e| format!("Failed to read file type: {e}"))?;
+
+            entries.push(DirEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir: file_type.is_dir(),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn location(&self, path: &str) -> String {
+        self.path(path).display().to_string()
+    }
+
+    /// HEAD of the repository containing the directory
+    fn history(&self) -> Option<GitStorage> {
+        let mut storage = GitStorage::open(&self.base, "HEAD").ok()?;
+
+        let prefix = storage.git(&["rev-parse", "--show-prefix"]).ok()?;
+        storage.prefix = String::from_utf8_lossy(&prefix).trim().trim_end_matches('/').to_string();
+
+        Some(storage)
+    }
+}
+
+/// Storage backed by the git objects of a revision (no checkout needed, works with bare repositories)
+#[derive(Clone)]
+pub struct GitStorage {
+    repo: PathBuf,
+    rev: String,
+    prefix: String,
+}
+
+impl GitStorage {
+    /// Read `.gitissues/` at the root of the tree of `rev` in the repository at `repo` (may be bare)
+    pub fn open(repo: &Path, rev: &str) -> Result<Self, String> {
+        let storage = GitStorage {
+            repo: repo.to_path_buf(),
+            rev: rev.to_string(),
+            prefix: ".gitissues".to_string(),
+        };
+
+        storage.resolve_rev()?;
+
+        Ok(storage)
+    }
+
+    /// Read `.gitissues/` of the current repository (found from the current directory) at `rev`
+    pub fn discover(rev: &str) -> Result<Self, String> {
+        let mut storage = GitStorage::open(Path::new("."), rev)?;
+        storage.prefix = storage.gitissues_tree_prefix()?;
+        Ok(storage)
+    }
+
+    /// Same repository and `.gitissues/` path at another revision
+    pub fn at(&self, rev: &str) -> GitStorage {
+        GitStorage {
+            repo: self.repo.clone(),
+            rev: rev.to_string(),
+            prefix: self.prefix.clone(),
+        }
+    }
+
+    pub fn rev(&self) -> &str {
+        &self.rev
+    }
+
+    /// Path of .gitissues relative to the root of the repository
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Resolve the revision to a commit hash
+    pub fn resolve_rev(&self) -> Result<String, String> {
+        let spec = format!("{}^{{commit}}", self.rev);
+        let output = self
+            .git(&["rev-parse", "--verify", "--quiet", &spec])
+            .map_err(|_| format!("Unknown revision: {}", self.rev))?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    pub(crate) fn git(&self, args: &[&str]) -> Result<Vec<u8>, String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run git: {e}"))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("git {} failed: {}", args.first().unwrap_or(&""), stderr.trim()));
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Path of .gitissues relative to the root of the repository
+    fn gitissues_tree_prefix(&self) -> Result<String, String> {
+        let toplevel = self.git(&["rev-parse", "--show-toplevel"])?;
+        let toplevel = String::from_utf8_lossy(&toplevel).trim().to_string();
+
+        let base = match gitissues_base() {
+            Ok(base) => base,
+            Err(_) => return Ok(".gitissues".to_string()), // .gitissues may only exist on other revisions
+        };
+
+        let toplevel = fs::canonicalize(&toplevel).map_err(|e| format!("Failed to resolve {toplevel}: {e}"))?;
+        let base = fs::canonicalize(&base).map_err(|e| format!("Failed to resolve {}: {e}", base.display()))?;
+
+        match base.strip_prefix(&toplevel) {
+            Ok(relative) => Ok(relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/")),
+            Err(_) => Ok(".gitissues".to_string()),
+        }
+    }
+
+    fn tree_path(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            self.prefix.clone()
+        } else {
+            format!("{}/{path}", self.prefix)
+        }
+    }
+}
+
+impl Storage for GitStorage {
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.read_many(&[path.to_string()])?.pop().flatten())
+    }
+
+    /// Read all blobs via a single `git cat-file --batch` process
+    fn read_many(&self, paths: &[String]) -> Result<Vec<Option<Vec<u8>>>, String> {
+        if paths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run git: {e}"))?;
+
+        let input = paths
+            .iter()
+            .map(|path| format!("{}:{}\n", self.rev, self.tree_path(path)))
+            .collect::<String>();
+
+        // Write stdin on a separate thread, otherwise a full stdout pipe could dead-lock us
+        let mut stdin = child.stdin.take().ok_or("Failed to open stdin of git cat-file")?;
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+        let output = child.wait_with_output().map_err(|e| format!("Failed to run git cat-file: {e}"))?;
+
+        writer
+            .join()
+            .map_err(|_| "Failed to write to git cat-file".to_string())?
+            .map_err(|e| format!("Failed to write to git cat-file: {e}"))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("git cat-file failed: {}", stderr.trim()));
+        }
+
+        let stdout = output.stdout;
+        let mut blobs = Vec::with_capacity(paths.len());
+        let mut pos = 0;
+
+        for _ in paths {
+            let header_end = stdout[pos..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|i| pos + i)
+                .ok_or("Unexpected output of git cat-file")?;
+            let header = String::from_utf8_lossy(&stdout[pos..header_end]).to_string();
+            pos = header_end + 1;
+
+            if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+                blobs.push(None);
+                continue;
+            }
+
+            // <oid> <type> <size>
+            let mut parts = header.split(' ');
+            let object_type = parts.nth(1).unwrap_or_default();
+            let size = parts
+                .next()
+                .and_then(|s| s.parse::<usize>().ok())
+                .ok_or_else(|| format!("Unexpected output of git cat-file: {header}"))?;
+
+            if pos + size > stdout.len() {
+                return Err("Unexpected end of git cat-file output".to_string());
+            }
+
+            blobs.push(if object_type == "blob" {
+                Some(stdout[pos..pos + size].to_vec())
+            } else {
+                None
+            });
+            pos += size + 1; // content is followed by a newline
+        }
+
+        Ok(blobs)
+    }
+
+    fn list_dir(&self, path: &str) -> Result<Vec<DirEntry>, String> {
+        let tree = format!("{}/", self.tree_path(path));
+        let listing = self.git(&["ls-tree", "--full-tree", &self.rev, "--", &tree])?;
+        let listing = String::from_utf8_lossy(&listing);
+
+        let mut entries = Vec::new();
+
+        // <mode> SP <type> SP <object> TAB <path>
+        for line in listing.lines() {
+            let Some((info, full_path)) = line.split_once('\t') else { continue };
+
+            entries.push(DirEntry {
+                name: full_path.rsplit('/').next().unwrap_or(full_path).to_string(),
+                is_dir: info.split(' ').nth(1) == Some("tree"),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn location(&self, path: &str) -> String {
+        format!("{}:{}", self.rev, self.tree_path(path))
+    }
+
+    fn history(&self) -> Option<GitStorage> {
+        Some(self.clone())
+    }
+}
@@ -0,0 +1,92 @@
+/// Machine-readable git trailers added to every commit created by git-issue
+/// - Identify issue commits regardless of the configured commit message templates
+/// - Example:
+///   ```text
+///   Issue-Action: set
+///   Issue-Id: 1
+///   Issue-Id: 2
+///   Issue-Fields: state,priority
+///   ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Trailers {
+    /// Command which created the commit: `init`, `new`, `set`, `edit`, `link`, `import`, `milestone`, `log-time`, `user`, `watch`, `unwatch`, `undo`
+    pub action: String,
+    /// Changed issues
+    pub ids: Vec<u32>,
+    /// Changed fields, e.g. meta fields for `set`, relationships for `link`, `description` for `edit`
+    pub fields: Vec<String>,
+    /// Commits reverted by `undo`
+    pub reverts: Vec<String>,
+}
+
+pub const TRAILER_ACTION: &str = "Issue-Action";
+pub const TRAILER_ID: &str = "Issue-Id";
+pub const TRAILER_FIELDS: &str = "Issue-Fields";
+pub const TRAILER_REVERTS: &str = "Issue-Reverts";
+
+impl Trailers {
+    pub fn new(action: &str, ids: &[u32]) -> Self {
+        Trailers {
+            action: action.to_string(),
+            ids: ids.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_fields<S: AsRef<str>>(mut self, fields: &[S]) -> Self {
+        for field in fields {
+            let field = field.as_ref().to_string();
+            if !self.fields.contains(&field) {
+                self.fields.push(field);
+            }
+        }
+        self
+    }
+
+    pub fn with_reverts(mut self, hashes: &[String]) -> Self {
+        self.reverts.extend(hashes.iter().cloned());
+        self
+    }
+
+    /// Trailer block to be appended to a commit message (separated by a blank line)
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!("{TRAILER_ACTION}: {}", self.action)];
+
+        lines.extend(self.ids.iter().map(|id| format!("{TRAILER_ID}: {id}")));
+
+        if !self.fields.is_empty() {
+            lines.push(format!("{TRAILER_FIELDS}: {}", self.fields.join(",")));
+        }
+
+        lines.extend(self.reverts.iter().map(|hash| format!("{TRAILER_REVERTS}: {hash}")));
+
+        lines.join("\n")
+    }
+
+    /// Parse the trailers of a full commit message; None if it was not created by git-issue
+    pub fn parse(message: &str) -> Option<Trailers> {
+        // Trailers are the last paragraph of the message
+        let paragraph = message.trim_end().rsplit("\n\n").next().unwrap_or_default();
+
+        let mut trailers = Trailers::default();
+
+        for line in paragraph.lines() {
+            let Some((key, value)) = line.split_once(": ") else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key {
+                TRAILER_ACTION => trailers.action = value.to_string(),
+                TRAILER_ID => trailers.ids.extend(value.parse::<u32>().ok()),
+                TRAILER_FIELDS => trailers
+                    .fields
+                    .extend(value.split(',').filter(|f| !f.is_empty()).map(|f| f.to_string())),
+                TRAILER_REVERTS => trailers.reverts.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        if trailers.action.is_empty() { None } else { Some(trailers) }
+    }
+}
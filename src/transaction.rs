@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use crate::model::{Meta, issue_meta_path};
+
+/// Collects file writes and removals and applies them all-or-nothing
+/// - Nothing touches the disk before `apply()`, so all validation can happen up front
+/// - Every file is first written to a temporary file next to its target and then renamed over it
+/// - If any step fails, all files already replaced are restored and newly created directories are removed
+#[derive(Default)]
+pub struct Transaction {
+    dirs: Vec<PathBuf>,
+    writes: IndexMap<PathBuf, Option<Vec<u8>>>,
+}
+
+/// A transaction which has been applied to disk and can still be rolled back
+pub struct Applied {
+    created_dirs: Vec<PathBuf>,
+    changes: Vec<Change>,
+}
+
+/// Content of a file before and after applying a transaction (None: file does not exist)
+pub struct Change {
+    pub path: PathBuf,
+    pub before: Option<Vec<u8>>,
+    pub after: Option<Vec<u8>>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stage creating a directory (incl. missing parents)
+    pub fn create_dir(&mut self, path: PathBuf) {
+        if !self.dirs.contains(&path) {
+            self.dirs.push(path);
+        }
+    }
+
+    /// Stage writing a file; a later write to the same path replaces the earlier one
+    pub fn write(&mut self, path: PathBuf, content: impl Into<Vec<u8>>) {
+        self.writes.insert(path, Some(content.into()));
+    }
+
+    /// Stage removing a file; removing a file which does not exist is a no-op
+    pub fn remove(&mut self, path: PathBuf) {
+        self.writes.insert(path, None);
+    }
+
+    /// Stage writing the meta.yaml of an issue
+    pub fn write_meta(&mut self, meta: &Meta) -> Result<(), String> {
+        let yaml = serde_yaml::to_string(meta).map_err(|_| "Failed to serialize meta.yaml".to_string())?;
+        self.write(issue_meta_path(meta.id)?, yaml);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty() && self.writes.is_empty()
+    }
+
+    /// Paths of all staged files (written or removed)
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.writes.keys().cloned().collect()
+    }
+
+    /// Write all staged changes to disk, or nothing at all
+    pub fn apply(self) -> Result<Applied, String> {
+        let mut applied = Applied {
+            created_dirs: Vec::new(),
+            changes: Vec::new(),
+        };
+
+        if let Err(e) = self.apply_into(&mut applied) {
+            return match applied.rollback() {
+                Ok(()) => Err(e),
+                Err(rollback_error) => Err(format!("{e} (rollback failed: {rollback_error})")),
+            };
+        }
+
+        Ok(applied)
+    }
+
+    fn apply_into(self, applied: &mut Applied) -> Result<(), String> {
+        // Step 1: Create directories, remember which ones are new
+        for dir in &self.dirs {
+            create_dir_tracked(dir, &mut applied.created_dirs)?;
+        }
+
+        for (path, _) in self.writes.iter().filter(|(_, content)| content.is_some()) {
+            if let Some(parent) = path.parent() {
+                create_dir_tracked(parent, &mut applied.created_dirs)?;
+            }
+        }
+
+        // Step 2: Write all temporary files; the targets are not touched yet
+        let mut temps = Vec::new();
+
+        for (path, content) in &self.writes {
+            let Some(content) = content else {
+                temps.push(None);
+                continue;
+            };
+
+            let temp = temp_path(path);
+
+            if let Err(e) = fs::write(&temp, content) {
+                for temp in temps.iter().flatten() {
+                    let _ = fs::remove_file(temp);
+                }
+                return Err(format!("Failed to write {}: {e}", temp.display()));
+            }
+
+            temps.push(Some(temp));
+        }
+
+        // Step 3: Replace (or remove) the targets, backing up their previous content
+        let mut result = Ok(());
+
+        for ((path, content), temp) in self.writes.into_iter().zip(temps) {
+            if result.is_err() {
+                if let Some(temp) = temp {
+                    let _ = fs::remove_file(temp);
+                }
+                continue;
+            }
+
+            let before = match path.exists() {
+                true => match fs::read(&path) {
+                    Ok(content) => Some(content),
+                    Err(e) => {
+                        result = Err(format!("Failed to read {}: {e}", path.display()));
+                        if let Some(temp) = temp {
+                            let _ = fs::remove_file(temp);
+                        }
+                        continue;
+                    }
+                },
+                false => None,
+            };
+
+            let replaced = match &temp {
+                Some(temp) => fs::rename(temp, &path),
+                None if before.is_some() => fs::remove_file(&path),
+                None => continue,
+            };
+
+            match replaced {
+                Ok(()) => applied.changes.push(Change {
+                    path,
+                    before,
+                    after: content,
+                }),
+                Err(e) => {
+                    result = Err(format!("Failed to write {}: {e}", path.display()));
+                    if let Some(temp) = temp {
+                        let _ = fs::remove_file(temp);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Applied {
+    /// Paths of all written or removed files
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.changes.iter().map(|change| change.path.clone()).collect()
+    }
+
+    /// Content of all written or removed files before and after applying
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Directories which did not exist before applying
+    pub fn created_dirs(&self) -> &[PathBuf] {
+        &self.created_dirs
+    }
+
+    /// Restore the previous content of all written or removed files and remove newly created directories
+    pub fn rollback(self) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        for Change { path, before, .. } in self.changes.into_iter().rev() {
+            let result = match before {
+                Some(content) => {
+                    let temp = temp_path(&path);
+                    fs::write(&temp, content).and_then(|_| fs::rename(&temp, &path))
+                }
+                None => fs::remove_file(&path),
+            };
+
+            if let Err(e) = result {
+                errors.push(format!("{}: {e}", path.display()));
+            }
+        }
+
+        for dir in self.created_dirs.into_iter().rev() {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                errors.push(format!("{}: {e}", dir.display()));
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors.join(", ")) }
+    }
+}
+
+/// Create a directory incl. missing parents and record every directory that did not exist before
+fn create_dir_tracked(dir: &Path, created_dirs: &mut Vec<PathBuf>) -> Result<(), String> {
+    let mut missing = Vec::new();
+    let mut current = Some(dir);
+
+    while let Some(path) = current
+        && !path.as_os_str().is_empty()
+        && !path.exists()
+    {
+        missing.push(path.to_path_buf());
+        current = path.parent();
+    }
+
+    for path in missing.into_iter().rev() {
+        fs::create_dir(&path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+        created_dirs.push(path);
+    }
+
+    Ok(())
+}
+
+/// Temporary file next to the target, so that the final rename stays on the same file system
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!(".{name}.tmp"))
+}
@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// Command line of the web server, also used by `git issue manpages` for git-issue-web(1)
+#[derive(Parser)]
+#[command(name = "git-issue-web")]
+#[command(about = "Web server for git-issue", long_about = None)]
+#[command(version = concat!("v", env!("CARGO_PKG_VERSION")))]
+pub(crate) struct Args {
+    /// Serve the issues from the git objects of this repository (may be bare) instead of the working tree
+    #[arg(long)]
+    pub(crate) repo: Option<PathBuf>,
+
+    /// Serve the issues of a git revision (branch, tag, commit) [default with --repo: HEAD]
+    #[arg(long = "ref", value_name = "REV")]
+    pub(crate) rev: Option<String>,
+}
@@ -0,0 +1,218 @@
+use git_issue::stats::PeriodCount;
+
+const BAR_HEIGHT: usize = 22;
+const LABEL_WIDTH: usize = 140;
+const BAR_WIDTH: usize = 360;
+pub const COLOR_OPEN: &str = "#fd7e14";
+const COLOR_CLOSED: &str = "#198754";
+
+/// Horizontal bar chart, one bar per (label, count)
+pub fn bar_chart<'a>(data: impl IntoIterator<Item = (&'a String, &'a usize)>) -> String {
+    let data: Vec<(&String, &usize)> = data.into_iter().collect();
+
+    if data.is_empty() {
+        return "<p>No data.</p>".to_string();
+    }
+
+    let max = data.iter().map(|(_, count)| **count).max().unwrap_or(0).max(1);
+    let width = LABEL_WIDTH + BAR_WIDTH + 50;
+    let height = data.len() * BAR_HEIGHT;
+
+    let mut svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-size="13">"#);
+
+    for (i, (label, count)) in data.iter().enumerate() {
+        let y = i * BAR_HEIGHT;
+        let bar = BAR_WIDTH * **count / max;
+
+        svg.push_str(&format!(
+            r##"<text x="{}" y="{}" text-anchor="end">{}</text><rect x="{LABEL_WIDTH}" y="{}" width="{bar}" height="{}" fill="#0d6efd" rx="2"/><text x="{}" y="{}">{count}</text>"##,
+            LABEL_WIDTH - 8,
+            y + 15,
+            escape(label),
+            y + 3,
+            BAR_HEIGHT - 6,
+            LABEL_WIDTH + bar + 6,
+            y + 15,
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Line chart of the open and closed issues per period
+pub fn open_closed_chart(periods: &[PeriodCount]) -> String {
+    let labels: Vec<String> = periods.iter().map(|p| p.period.clone()).collect();
+    let series = [
+        Series::new("open", COLOR_OPEN, periods.iter().map(|p| p.open).collect()),
+        Series::new("closed", COLOR_CLOSED, periods.iter().map(|p| p.closed).collect()),
+    ];
+
+    line_chart(&labels, &series)
+}
+
+/// Named values of a line or area chart, one value per label
+pub struct Series {
+    pub name: String,
+    pub color: String,
+    pub values: Vec<usize>,
+}
+
+impl Series {
+    pub fn new(name: &str, color: &str, values: Vec<usize>) -> Self {
+        Series {
+            name: name.to_string(),
+            color: color.to_string(),
+            values,
+        }
+    }
+}
+
+/// Fallback colors for series without a configured color
+pub const PALETTE: [&str; 6] = ["#0d6efd", "#6f42c1", "#20c997", "#ffc107", "#dc3545", "#6c757d"];
+
+/// Line chart, one line per series
+pub fn line_chart(labels: &[String], series: &[Series]) -> String {
+    let max = series.iter().flat_map(|s| s.values.iter().copied()).max().unwrap_or(0);
+
+    chart(labels, series, max, |plot, svg| {
+        for s in series {
+            let points: Vec<(usize, usize)> = s.values.iter().copied().enumerate().collect();
+            svg.push_str(&format!(
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                plot.points(&points),
+                escape(&s.color)
+            ));
+        }
+    })
+}
+
+/// Stacked area chart, the first series at the bottom
+pub fn stacked_area_chart(labels: &[String], series: &[Series]) -> String {
+    let mut totals = vec![0; labels.len()];
+    let mut layers = Vec::new();
+
+    for s in series {
+        let bottom = totals.clone();
+        for (total, value) in totals.iter_mut().zip(&s.values) {
+            *total += value;
+        }
+        layers.push((bottom, totals.clone()));
+    }
+
+    let max = totals.iter().copied().max().unwrap_or(0);
+
+    chart(labels, series, max, |plot, svg| {
+        for (s, (bottom, top)) in series.iter().zip(&layers) {
+            // Along the top edge and back along the bottom edge
+            let mut points: Vec<(usize, usize)> = top.iter().copied().enumerate().collect();
+            points.extend(bottom.iter().copied().enumerate().rev());
+
+            svg.push_str(&format!(
+                r#"<polygon points="{}" fill="{}" fill-opacity="0.8" stroke="none"/>"#,
+                plot.points(&points),
+                escape(&s.color)
+            ));
+        }
+    })
+}
+
+/// Plot area of a chart with one x position per label
+struct Plot {
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+    step: f64,
+    max: usize,
+}
+
+impl Plot {
+    fn x(&self, i: usize) -> f64 {
+        self.left as f64 + i as f64 * self.step
+    }
+
+    fn y(&self, value: usize) -> f64 {
+        (self.top + self.height) as f64 - (value * self.height) as f64 / self.max as f64
+    }
+
+    /// SVG points of (label index, value)
+    fn points(&self, points: &[(usize, usize)]) -> String {
+        points
+            .iter()
+            .map(|(i, value)| format!("{:.1},{:.1}", self.x(*i), self.y(*value)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Chart with axes, labels and legend, `draw` adds the data
+fn chart(labels: &[String], series: &[Series], max: usize, draw: impl Fn(&Plot, &mut String)) -> String {
+    if labels.is_empty() {
+        return "<p>No data.</p>".to_string();
+    }
+
+    let (width, height, left, bottom, top) = (700usize, 260usize, 40usize, 40usize, 20usize);
+
+    let plot = Plot {
+        left,
+        top,
+        width: width - left - 20,
+        height: height - bottom - top,
+        step: if labels.len() > 1 {
+            (width - left - 20) as f64 / (labels.len() - 1) as f64
+        } else {
+            0.0
+        },
+        max: max.max(1),
+    };
+
+    let mut svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-size="12">"#);
+
+    draw(&plot, &mut svg);
+
+    // Axes with the maximum on the y axis
+    svg.push_str(&format!(
+        r##"<line x1="{left}" y1="{top}" x2="{left}" y2="{0}" stroke="#adb5bd"/><line x1="{left}" y1="{0}" x2="{1}" y2="{0}" stroke="#adb5bd"/><text x="{2}" y="{3}" text-anchor="end">{4}</text><text x="{2}" y="{0}" text-anchor="end">0</text>"##,
+        top + plot.height,
+        left + plot.width,
+        left - 6,
+        top + 4,
+        plot.max,
+    ));
+
+    // Labels, at most ~12 to stay readable
+    let every = labels.len().div_ceil(12);
+    for (i, label) in labels.iter().enumerate().filter(|(i, _)| i % every == 0) {
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{}" text-anchor="middle">{}</text>"#,
+            plot.x(i),
+            top + plot.height + 18,
+            escape(label)
+        ));
+    }
+
+    // Legend
+    let mut x = left + 10;
+    for s in series {
+        svg.push_str(&format!(
+            r#"<rect x="{x}" y="{}" width="12" height="12" fill="{}"/><text x="{}" y="{}">{}</text>"#,
+            height - 14,
+            escape(&s.color),
+            x + 16,
+            height - 4,
+            escape(&s.name),
+        ));
+        x += 16 + 8 * s.name.chars().count() + 20;
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,15 +1,28 @@
 #![deny(warnings, clippy::unwrap_used, clippy::expect_used)]
 use askama::Template;
-use axum::extract::Query;
-use axum::http::header;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, HeaderValue, header};
 use axum::{Json, Router, extract::Path, response::Html, response::IntoResponse, routing::get};
-use regex::Regex;
+use clap::{Parser, ValueEnum};
 use serde::Deserialize;
 use serde_json::{self, json};
-use std::fs;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use git_issue::model::{Filter, load_settings};
+use git_issue::flow::Interval;
+use git_issue::graph::GraphFormat;
+use git_issue::html::{Data, ListTemplate, ShowTemplate};
+use git_issue::milestone::MilestoneProgress;
+use git_issue::model::{Filter, MilestoneState, Operator, load_settings_or_default};
+use git_issue::stats::{Durations, Period, Stats};
+use git_issue::storage::{FsStorage, GitStorage, Storage};
+
+mod args;
+mod charts;
+
+use args::Args;
+
+type AppState = Arc<dyn Storage>;
 
 enum ApiError {
     NotFound,
@@ -40,26 +53,53 @@ async fn ping() -> impl IntoResponse {
     }))
 }
 
-struct Data {
-    key: String,
-    value: String,
+#[derive(Template)]
+#[template(path = "graph.html")]
+struct GraphTemplate {
+    content: String,
+    empty: bool,
+    filters: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "stats.html")]
+struct StatsTemplate {
+    stats: Stats,
+    period: Period,
+    charts: Vec<(String, String)>,
+    over_time: String,
+    durations: Vec<(String, Durations)>,
 }
 
 #[derive(Template)]
-#[template(path = "list.html")]
-struct ListTemplate {
-    ids: Vec<u32>,
-    rows: Vec<Vec<Data>>,
-    user: String,
-    columns: Vec<String>,
+#[template(path = "flow.html")]
+struct FlowTemplate {
+    cumulative: String,
+    burndown: String,
+    from: String,
+    to: String,
+    interval: Interval,
     filters: Vec<String>,
 }
 
 #[derive(Template)]
-#[template(path = "show.html")]
-struct ShowTemplate {
-    id: u32,
-    content: String,
+#[template(path = "milestones.html")]
+struct MilestonesTemplate {
+    milestones: Vec<MilestoneProgress>,
+}
+
+#[derive(Deserialize)]
+struct FlowQuery {
+    from: Option<String>,
+    to: Option<String>,
+    interval: Option<String>,
+    #[serde(default, deserialize_with = "comma_separated")]
+    filters: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    period: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -68,12 +108,37 @@ struct ListColumnsQuery {
     columns: Vec<String>,
 }
 
+#[derive(Deserialize)]
+struct ListViewQuery {
+    view: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct ListFiltersQuery {
     #[serde(default, deserialize_with = "comma_separated")]
     filters: Vec<String>,
 }
 
+fn parse_filters(filters: &[String]) -> Result<Vec<Filter>, ApiError> {
+    let mut filters_parsed: Vec<Filter> = Vec::new();
+
+    for filter in filters {
+        // Skip empty filter strings
+        if filter.trim().is_empty() {
+            continue;
+        }
+
+        match Filter::from_str(filter) {
+            Ok(f) => filters_parsed.push(f),
+            Err(_) => {
+                return Err(ApiError::BadRequest(format!("Invalid filter format: {}", filter)));
+            }
+        }
+    }
+
+    Ok(filters_parsed)
+}
+
 fn comma_separated<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -82,29 +147,38 @@ where
     Ok(s.unwrap_or_default().split(',').map(|s| s.to_string()).collect())
 }
 
-async fn list(Query(columns): Query<ListColumnsQuery>, Query(filters): Query<ListFiltersQuery>) -> Result<Html<String>, ApiError> {
+async fn list(
+    State(storage): State<AppState>,
+    Query(columns): Query<ListColumnsQuery>,
+    Query(filters): Query<ListFiltersQuery>,
+    Query(view): Query<ListViewQuery>,
+) -> Result<Html<String>, ApiError> {
     let columns = if columns.columns.is_empty() { None } else { Some(columns.columns) };
 
-    let mut filters_parsed: Vec<Filter> = Vec::new();
+    let mut filters_parsed = parse_filters(&filters.filters)?;
 
-    for filter in &filters.filters {
-        // Skip empty filter strings
-        if filter.trim().is_empty() {
-            continue;
-        }
+    // Inbox view: watched issues of the current user which others changed since the last `git issue inbox`
+    let inbox = view.view.as_deref() == Some("inbox");
 
-        let parsed = Filter::from_str(filter);
-        match parsed {
-            Ok(f) => filters_parsed.push(f),
-            Err(_) => {
-                return Err(ApiError::BadRequest(format!("Invalid filter format: {}", filter)));
-            }
+    if inbox {
+        let result = git_issue::inbox(storage.as_ref(), "me", None).map_err(ApiError::BadRequest)?;
+
+        for info in result.infos {
+            println!("{}", info);
         }
+
+        let ids: Vec<String> = result.value.items.iter().map(|item| item.id.to_string()).collect();
+
+        filters_parsed.push(Filter {
+            field: "id".to_string(),
+            operator: Operator::Eq,
+            value: ids.join(","),
+        });
     }
 
-    let result = git_issue::list(columns, Some(filters_parsed), None);
+    let result = git_issue::list(storage.as_ref(), columns, Some(filters_parsed), None);
 
-    let result = match result {
+    let mut result = match result {
         Ok(result) => result,
         Err(_) => {
             return Err(ApiError::InternalServerError);
@@ -115,6 +189,9 @@ async fn list(Query(columns): Query<ListColumnsQuery>, Query(filters): Query<Lis
         println!("{}", info);
     }
 
+    let users = storage.load_users().map_err(|_| ApiError::InternalServerError)?;
+    git_issue::list::display_names(&mut result.value, &users);
+
     let columns = result.value.columns;
 
     let mut ids: Vec<u32> = Vec::new();
@@ -135,7 +212,7 @@ async fn list(Query(columns): Query<ListColumnsQuery>, Query(filters): Query<Lis
         rows.push(issue_rows);
     }
 
-    let (settings, _) = match load_settings() {
+    let (settings, _) = match load_settings_or_default() {
         Ok(settings) => settings,
         Err(_) => {
             return Err(ApiError::InternalServerError);
@@ -145,9 +222,11 @@ async fn list(Query(columns): Query<ListColumnsQuery>, Query(filters): Query<Lis
     let issue_collection = ListTemplate {
         ids,
         rows,
-        user: settings.user.to_string(),
+        user: users.display_name(&settings.user),
         columns,
         filters: filters.filters,
+        site: false,
+        inbox,
     };
 
     let html = issue_collection.render().map_err(|_| ApiError::InternalServerError)?;
@@ -155,42 +234,239 @@ async fn list(Query(columns): Query<ListColumnsQuery>, Query(filters): Query<Lis
     Ok(Html(html))
 }
 
-async fn show(Path(id): Path<u32>) -> Result<Html<String>, ApiError> {
+async fn show(State(storage): State<AppState>, Path(id): Path<u32>) -> Result<Html<String>, ApiError> {
     if id == 0 {
         return Err(ApiError::BadRequest("ID does not exist".to_string()));
     }
 
-    let result = git_issue::show(id);
+    let result = git_issue::show_markdown(storage.as_ref(), id);
 
-    let md_path = match result {
+    let content = match result {
         Ok(result) => result.value,
         Err(_) => {
             return Err(ApiError::InternalServerError);
         }
     };
 
-    let mut content = match fs::read_to_string(&md_path) {
-        Ok(content) => content,
+    // Relative URLs of the description point to the attachments of the issue
+    let html = git_issue::html::markdown_to_html(&content, &format!("/show/{id}/"), &|other| format!("/show/{other}"))
+        .map_err(|_| ApiError::InternalServerError)?;
+
+    let template = ShowTemplate { id, html, site: false };
+    let html = template.render().map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(Html(html))
+}
+
+async fn attachment(State(storage): State<AppState>, Path((id, name)): Path<(u32, String)>) -> Result<impl IntoResponse, ApiError> {
+    let bytes = match storage.read_attachment(id, &name) {
+        Ok(Some(bytes)) => bytes,
+        Ok(None) => return Err(ApiError::NotFound),
+        Err(_) => return Err(ApiError::InternalServerError),
+    };
+
+    let content_type = content_type(&name);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+
+    // Attachments are committed by anyone: only raster images and plain text are shown as pages of this origin.
+    // SVG can carry scripts, so it is sandboxed (embedding via <img> still works); everything else is downloaded.
+    if !is_inline(content_type) {
+        headers.insert(header::CONTENT_SECURITY_POLICY, HeaderValue::from_static("sandbox"));
+
+        if content_type != "image/svg+xml" {
+            headers.insert(header::CONTENT_DISPOSITION, HeaderValue::from_static("attachment"));
+        }
+    }
+
+    Ok((headers, bytes))
+}
+
+/// Content types which cannot run scripts
+fn is_inline(content_type: &str) -> bool {
+    matches!(
+        content_type,
+        "image/png" | "image/jpeg" | "image/gif" | "image/webp" | "text/plain; charset=utf-8"
+    )
+}
+
+fn content_type(name: &str) -> &'static str {
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "txt" | "log" | "md" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn graph(State(storage): State<AppState>, Query(filters): Query<ListFiltersQuery>) -> Result<Html<String>, ApiError> {
+    let filters_parsed = parse_filters(&filters.filters)?;
+
+    let result = git_issue::graph_render(storage.as_ref(), None, Some(filters_parsed), GraphFormat::Svg);
+
+    let result = match result {
+        Ok(result) => result,
         Err(_) => {
             return Err(ApiError::InternalServerError);
         }
     };
 
-    let md_start = "<!-- READ-ONLY VIEW -->\n\n# Issue ";
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    let template = GraphTemplate {
+        // No node in the SVG: no issues
+        empty: !result.value.contains(r#"class="node""#),
+        content: result.value,
+        filters: filters.filters,
+    };
+    let html = template.render().map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(Html(html))
+}
+
+async fn graph_mermaid(State(storage): State<AppState>, Query(filters): Query<ListFiltersQuery>) -> Result<impl IntoResponse, ApiError> {
+    let filters_parsed = parse_filters(&filters.filters)?;
+
+    // Mermaid source of the same graph, for viewers which render Mermaid themselves (mermaid.js is not bundled)
+    let result = git_issue::graph_render(storage.as_ref(), None, Some(filters_parsed), GraphFormat::Mermaid)
+        .map_err(|_| ApiError::InternalServerError)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], result.value))
+}
 
-    // Prevent title from being formatted --> remove # from title issue id
-    let re = Regex::new(&format!("^({md_start})#(\\d+)")).map_err(|_| ApiError::InternalServerError)?;
-    content = re.replace(&content, "$1$2").to_string();
+async fn stats(State(storage): State<AppState>, Query(query): Query<StatsQuery>) -> Result<Html<String>, ApiError> {
+    let period = match query.period.as_deref() {
+        None | Some("") => Period::Month,
+        Some(period) => Period::from_str(period, true).map_err(|_| ApiError::BadRequest(format!("Invalid period: {period}")))?,
+    };
 
-    // Format issue ids with links to the corresponding show page
-    let re = Regex::new(r"#(\d+)").map_err(|_| ApiError::InternalServerError)?;
-    content = re.replace_all(&content, "[#$1](http://localhost:7878/show/$1)").to_string();
+    let result = match git_issue::stats(storage.as_ref(), period) {
+        Ok(result) => result,
+        Err(_) => {
+            return Err(ApiError::InternalServerError);
+        }
+    };
 
-    // Put back the # in front of the title issue id
-    let re = Regex::new(&format!("^({md_start})(\\d+)")).map_err(|_| ApiError::InternalServerError)?;
-    content = re.replace(&content, "$1#$2").to_string();
+    for info in result.infos {
+        println!("{}", info);
+    }
 
-    let template = ShowTemplate { id, content };
+    let mut stats = result.value;
+
+    let charts = vec![
+        ("State".to_string(), charts::bar_chart(&stats.by_state)),
+        ("Type".to_string(), charts::bar_chart(&stats.by_type)),
+        ("Assignee".to_string(), charts::bar_chart(&stats.by_assignee)),
+        ("Priority".to_string(), charts::bar_chart(&stats.by_priority)),
+        ("Label".to_string(), charts::bar_chart(&stats.by_label)),
+    ];
+
+    let mut durations = vec![("all".to_string(), std::mem::take(&mut stats.cycle_time))];
+    durations.extend(stats.lead_time.drain(..));
+
+    let template = StatsTemplate {
+        over_time: charts::open_closed_chart(&stats.over_time),
+        stats,
+        period,
+        charts,
+        durations,
+    };
+    let html = template.render().map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(Html(html))
+}
+
+async fn flow(State(storage): State<AppState>, Query(query): Query<FlowQuery>) -> Result<Html<String>, ApiError> {
+    let date = |date: &Option<String>| match date.as_deref() {
+        None | Some("") => Ok(None),
+        Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| ApiError::BadRequest(format!("Invalid date: {date}"))),
+    };
+    let (from, to) = (date(&query.from)?, date(&query.to)?);
+
+    let interval = match query.interval.as_deref() {
+        None | Some("") => Interval::Day,
+        Some(interval) => Interval::from_str(interval, true).map_err(|_| ApiError::BadRequest(format!("Invalid interval: {interval}")))?,
+    };
+
+    let filters_parsed = parse_filters(&query.filters)?;
+
+    let result = git_issue::flow(storage.as_ref(), from, to, interval, Some(filters_parsed)).map_err(ApiError::BadRequest)?;
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    let (settings, _) = match load_settings_or_default() {
+        Ok(settings) => settings,
+        Err(_) => {
+            return Err(ApiError::InternalServerError);
+        }
+    };
+
+    let flow = result.value;
+    let labels: Vec<String> = flow.points.iter().map(|point| point.date.clone()).collect();
+
+    // Last state (usually done) at the bottom, like a classic cumulative flow diagram
+    let series: Vec<charts::Series> = flow
+        .states
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, state)| {
+            let color = settings.list_formatting.colors.state.get(state).and_then(|color| color.hex());
+            let values = flow
+                .points
+                .iter()
+                .map(|point| point.states.get(state).copied().unwrap_or(0))
+                .collect();
+            charts::Series::new(state, color.unwrap_or(charts::PALETTE[i % charts::PALETTE.len()]), values)
+        })
+        .collect();
+
+    let remaining = flow.points.iter().map(|point| point.remaining).collect();
+
+    let template = FlowTemplate {
+        cumulative: charts::stacked_area_chart(&labels, &series),
+        burndown: charts::line_chart(&labels, &[charts::Series::new("remaining", charts::COLOR_OPEN, remaining)]),
+        from: labels.first().cloned().unwrap_or_default(),
+        to: labels.last().cloned().unwrap_or_default(),
+        interval,
+        filters: query.filters,
+    };
+    let html = template.render().map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(Html(html))
+}
+
+async fn milestones(State(storage): State<AppState>) -> Result<Html<String>, ApiError> {
+    let result = match git_issue::milestone_list(storage.as_ref()) {
+        Ok(result) => result,
+        Err(_) => {
+            return Err(ApiError::InternalServerError);
+        }
+    };
+
+    for info in result.infos {
+        println!("{}", info);
+    }
+
+    let template = MilestonesTemplate { milestones: result.value };
     let html = template.render().map_err(|_| ApiError::InternalServerError)?;
 
     Ok(Html(html))
@@ -205,19 +481,44 @@ async fn not_found() -> impl IntoResponse {
     ApiError::NotFound
 }
 
-fn create_app() -> Router {
+fn create_app(storage: AppState) -> Router {
     Router::new()
         .route("/ping", get(ping))
         .route("/", get(list))
         .route("/list", get(list))
         .route("/show/{id}", get(show))
+        .route("/show/{id}/attachments/{*name}", get(attachment))
+        .route("/graph", get(graph))
+        .route("/graph.mmd", get(graph_mermaid))
+        .route("/stats", get(stats))
+        .route("/flow", get(flow))
+        .route("/milestones", get(milestones))
         .route("/favicon.ico", get(favicon))
         .fallback(not_found)
+        .with_state(storage)
+}
+
+fn open_storage(args: &Args) -> Result<AppState, String> {
+    match (&args.repo, &args.rev) {
+        (Some(repo), rev) => Ok(Arc::new(GitStorage::open(repo, rev.as_deref().unwrap_or("HEAD"))?)),
+        (None, Some(rev)) => Ok(Arc::new(GitStorage::discover(rev)?)),
+        (None, None) => Ok(Arc::new(FsStorage::discover()?)),
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    let app = create_app();
+    let args = Args::parse();
+
+    let storage = match open_storage(&args) {
+        Ok(storage) => storage,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let app = create_app(storage);
 
     let listener = match tokio::net::TcpListener::bind("127.0.0.1:7878").await {
         Ok(listener) => listener,
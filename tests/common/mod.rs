@@ -118,6 +118,16 @@ pub fn disable_auto_commit() {
     save_yaml_values(config_path, &config);
 }
 
+/// Initialize .gitissues without auto-commit and create `count` issues titled "Issue <n>"
+pub fn setup_issues(count: u32) {
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    for i in 1..=count {
+        run_command(&["new", &format!("Issue {i}")]).expect("new failed");
+    }
+}
+
 pub fn load_yaml_values(path: &str) -> Value {
     let content = fs::read_to_string(path).unwrap_or_else(|_| panic!("Failed to read {path}"));
     serde_yaml::from_str::<Value>(&content).expect("Failed to parse meta.yaml")
@@ -127,3 +137,45 @@ pub fn save_yaml_values(path: &str, value: &Value) {
     let content = serde_yaml::to_string(value).unwrap_or_else(|_| panic!("Failed to serialize {path}"));
     fs::write(path, content).unwrap_or_else(|_| panic!("Failed to write {path}"));
 }
+
+/// Set settings.yaml:user, the current user
+pub fn set_user(user: &str) {
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["user"] = serde_yaml::Value::String(user.to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+}
+
+/// Initialize a git repository in the current directory with a local test identity
+pub fn init_git_repo() {
+    run_git(&["init", "--quiet", "--initial-branch=main"]);
+    run_git(&["config", "user.name", "Test User"]);
+    run_git(&["config", "user.email", "test@example.com"]);
+    run_git(&["config", "commit.gpgsign", "false"]);
+}
+
+/// Run a git command in the current directory and return its stdout
+pub fn run_git(args: &[&str]) -> String {
+    let output = Command::new("git").args(args).output().expect("Failed to execute git");
+
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Completion candidates of a command line (last word: the one being completed), as the fish shell gets them: `value\thelp`
+pub fn run_completion(words: &[&str]) -> Vec<String> {
+    let output = Command::new(get_binary_path())
+        .env("COMPLETE", "fish")
+        .arg("--")
+        .arg("git-issue")
+        .args(words)
+        .output()
+        .expect("Failed to execute completion");
+
+    String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect()
+}
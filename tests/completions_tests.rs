@@ -0,0 +1,65 @@
+mod common;
+use common::{TestEnv, disable_auto_commit, run_command, run_completion};
+
+const USERS: &str = r#"_version: 2
+users:
+  - id: alice
+    name: Alice Smith
+  - id: bob
+  - id: carol
+    active: false
+"#;
+
+#[test]
+fn test_completions_script() {
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let output = run_command(&["completions", shell]).expect("completions failed");
+        let script = String::from_utf8_lossy(&output.stdout);
+        assert!(script.contains("COMPLETE"), "{shell}");
+        assert!(script.contains("git-issue"), "{shell}");
+    }
+
+    // `git issue ...` via git's bash completion
+    let output = run_command(&["completions", "bash"]).expect("completions failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("_git_issue()"));
+
+    assert!(run_command(&["completions", "tcsh"]).is_err());
+}
+
+#[test]
+fn test_completions_values() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+    std::fs::write(".gitissues/users.yaml", USERS).expect("write users.yaml failed");
+
+    run_command(&["new", "Login broken", "--labels", "ui,backend"]).expect("new failed");
+    run_command(&["new", "Add dark mode", "--labels", "ui"]).expect("new failed");
+
+    // Issue IDs with titles, newest first
+    assert_eq!(run_completion(&["show", ""])[..2], ["2\tAdd dark mode", "1\tLogin broken"]);
+    assert_eq!(run_completion(&["set", "1,"])[..2], ["1,2\tAdd dark mode", "1,1\tLogin broken"]);
+
+    // config.yaml and users.yaml
+    assert_eq!(run_completion(&["set", "1", "--state", "a"]), ["active"]);
+    assert_eq!(run_completion(&["new", "A", "--type", "f"]), ["feature"]);
+    assert_eq!(
+        run_completion(&["new", "A", "--assignee", ""]),
+        ["me\tsettings.yaml:user or git identity", "alice\tAlice Smith", "bob"]
+    );
+    assert_eq!(run_completion(&["watch", "1", "--user", "b"]), ["bob"]);
+
+    // Existing labels
+    assert_eq!(run_completion(&["set", "2", "--labels-add", "ui,b"]), ["ui,backend"]);
+
+    // Columns, filters and sorting
+    assert_eq!(run_completion(&["list", "--columns", "id,ti"]), ["id,title"]);
+    assert_eq!(run_completion(&["list", "--filter", "sta"]), ["state="]);
+    assert_eq!(run_completion(&["list", "--filter", "state=a"]), ["state=active"]);
+    assert_eq!(run_completion(&["list", "--filter", "labels=ui,b"]), ["labels=ui,backend"]);
+    assert_eq!(run_completion(&["list", "--sort", "prio"]), ["priority=asc", "priority=desc"]);
+
+    // Relationships, then IDs
+    assert_eq!(run_completion(&["link", "1", "--add", "rel"]), ["related="]);
+    assert_eq!(run_completion(&["link", "1", "--add", "related=2"]), ["related=2\tAdd dark mode"]);
+}
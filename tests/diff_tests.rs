@@ -0,0 +1,118 @@
+mod common;
+use common::{TestEnv, init_git_repo, run_command, run_git};
+
+#[test]
+fn test_list_ref() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    run_command(&["new", "Issue 1", "--assignee", "alice"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2", "--assignee", "bob"]).expect("new 2 failed");
+    run_git(&["tag", "v1"]);
+
+    // Work on a feature branch
+    run_git(&["checkout", "--quiet", "-b", "feature"]);
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+    run_command(&["set", "1", "--state", "active"]).expect("set failed");
+    run_git(&["checkout", "--quiet", "main"]);
+
+    // Working tree (main) does not know issue 3
+    let output = run_command(&["list", "--columns", "id,title,state"]).expect("list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Issue 1"));
+    assert!(stdout.contains("Issue 2"));
+    assert!(!stdout.contains("Issue 3"));
+    assert!(!stdout.contains("active"));
+
+    // Read issues from the feature branch without checking it out
+    let output = run_command(&["list", "--columns", "id,title,state", "--ref", "feature"]).expect("list --ref failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Issue 1"));
+    assert!(stdout.contains("Issue 2"));
+    assert!(stdout.contains("Issue 3"));
+    assert!(stdout.contains("active"));
+
+    // Filters work on the revision too
+    let output = run_command(&["list", "--columns", "title", "--ref", "feature", "--filter", "state=active"]).expect("list --ref failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Issue 1"));
+    assert!(!stdout.contains("Issue 2"));
+    assert!(!stdout.contains("Issue 3"));
+
+    let output =
+        run_command(&["list", "--columns", "title", "--ref", "feature", "--filter", "description=*Repro*"]).expect("list --ref failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Issue 1"));
+    assert!(stdout.contains("Issue 3"));
+
+    // Tags work as well
+    let output = run_command(&["list", "--columns", "title", "--ref", "v1"]).expect("list --ref failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Issue 1"));
+    assert!(!stdout.contains("Issue 3"));
+
+    // Unknown revisions are rejected
+    run_command(&["list", "--ref", "does-not-exist"]).expect_err("list --ref with unknown revision should fail");
+}
+
+#[test]
+fn test_diff() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    run_command(&["new", "Issue 1", "--assignee", "alice"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+
+    run_git(&["checkout", "--quiet", "-b", "feature"]);
+    run_command(&["new", "Issue 4"]).expect("new 4 failed");
+    run_command(&["set", "1", "--state", "active", "--assignee", "bob"]).expect("set failed");
+    run_command(&["link", "2", "--add", "related=3"]).expect("link failed");
+    run_git(&["rm", "--quiet", "-r", ".gitissues/issues/0000000003"]);
+    run_git(&["commit", "--quiet", "-m", "delete issue 3"]);
+
+    let output = run_command(&["diff", "main", "feature"]).expect("diff failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let created = stdout.split("Created:").nth(1).expect("no created section");
+    assert!(created.trim_start().starts_with("#4 -- Issue 4"));
+
+    let deleted = stdout.split("Deleted:").nth(1).expect("no deleted section");
+    assert!(deleted.trim_start().starts_with("#3 -- Issue 3"));
+
+    let changed = stdout.split("Changed:").nth(1).expect("no changed section");
+    assert!(changed.contains("#1 -- Issue 1"));
+    assert!(changed.contains("state: new -> active"));
+    assert!(changed.contains("assignee: alice -> bob"));
+    assert!(changed.contains("#2 -- Issue 2"));
+    assert!(changed.contains("related: - -> 3"));
+    assert!(!changed.contains("updated:"));
+
+    // Reverse direction
+    let output = run_command(&["diff", "feature", "main"]).expect("diff failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout
+            .split("Created:")
+            .nth(1)
+            .expect("no created section")
+            .contains("#3 -- Issue 3")
+    );
+    assert!(
+        stdout
+            .split("Deleted:")
+            .nth(1)
+            .expect("no deleted section")
+            .contains("#4 -- Issue 4")
+    );
+    assert!(stdout.contains("state: active -> new"));
+
+    // No changes
+    let output = run_command(&["diff", "main", "main"]).expect("diff failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No issue changes"));
+}
@@ -0,0 +1,52 @@
+use std::fs;
+
+mod common;
+use common::{TestEnv, disable_auto_commit, run_command};
+
+#[test]
+fn test_export_html() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "Crash on <startup>"]).expect("new 1 failed");
+    run_command(&["new", "Second"]).expect("new 2 failed");
+    run_command(&["link", "2", "--add", "related=1"]).expect("link failed");
+
+    fs::write(
+        ".gitissues/issues/0000000001/description.md",
+        "See ![log](attachments/img/log.png) and #2.\n\n- [x] done\n- [ ] todo\n\n```\n#3 in code\n```\n",
+    )
+    .expect("Failed to write description");
+    fs::create_dir_all(".gitissues/issues/0000000001/attachments/img").expect("Failed to create attachments");
+    fs::write(".gitissues/issues/0000000001/attachments/img/log.png", "png").expect("Failed to write attachment");
+
+    let output = run_command(&["export", "--html", "site"]).expect("export failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Exported 2 issue(s) to site"));
+
+    // Index: relative links, no server-only forms
+    let index = fs::read_to_string("site/index.html").expect("index.html missing");
+    assert!(index.contains("href=\"issues/1/index.html\""));
+    assert!(index.contains("href=\"issues/2/index.html\""));
+    assert!(index.contains("Crash on &#60;startup&#62;"));
+    assert!(!index.contains("columnsForm"));
+    assert!(!index.contains("href=\"/"));
+    assert!(fs::exists("site/favicon.ico").unwrap_or(false));
+
+    // Issue page: markdown rendered, no CDN
+    let page = fs::read_to_string("site/issues/1/index.html").expect("issue page missing");
+    assert!(page.contains("<img src=\"attachments/img/log.png\" alt=\"log\">"));
+    assert!(page.contains("<a href=\"../2/index.html\">#2</a>"));
+    assert!(page.contains("#3 in code"));
+    assert!(!page.contains("../3/index.html"));
+    assert!(page.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\">"));
+    assert!(page.contains("href=\"../../index.html\""));
+    assert!(!page.contains("cdn.jsdelivr.net"));
+    assert_eq!(
+        fs::read_to_string("site/issues/1/attachments/img/log.png").expect("attachment missing"),
+        "png"
+    );
+
+    let page = fs::read_to_string("site/issues/2/index.html").expect("issue page missing");
+    assert!(page.contains("<a href=\"../1/index.html\">#1</a>"));
+}
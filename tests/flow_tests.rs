@@ -0,0 +1,64 @@
+mod common;
+use common::{TestEnv, init_git_repo, run_command};
+
+fn flow_json(args: &[&str]) -> serde_json::Value {
+    let output = run_command(&[&["flow", "--json"], args].concat()).expect("flow failed");
+    serde_json::from_slice(&output.stdout).expect("flow output is no valid JSON")
+}
+
+fn today() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+#[test]
+fn test_flow() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    run_command(&["new", "Crash", "--type", "bug"]).expect("new 1 failed");
+    run_command(&["new", "Dark mode", "--type", "feature"]).expect("new 2 failed");
+    run_command(&["new", "Docs", "--type", "bug"]).expect("new 3 failed");
+    run_command(&["set", "1", "--state", "closed"]).expect("set 1 failed");
+    run_command(&["set", "2", "--state", "active"]).expect("set 2 failed");
+
+    let flow = flow_json(&[]);
+
+    assert_eq!(flow["states"], serde_json::json!(["new", "active", "closed", "deleted"]));
+
+    let points = flow["points"].as_array().expect("points missing");
+    assert_eq!(points.len(), 1); // all created today
+    assert_eq!(points[0]["date"], today());
+    assert_eq!(points[0]["states"]["new"], 1);
+    assert_eq!(points[0]["states"]["active"], 1);
+    assert_eq!(points[0]["states"]["closed"], 1);
+    assert_eq!(points[0]["remaining"], 2);
+
+    // Filter: burndown of the bugs only
+    let flow = flow_json(&["--filter", "type=bug"]);
+    assert_eq!(flow["points"][0]["states"]["closed"], 1);
+    assert_eq!(flow["points"][0]["remaining"], 1);
+
+    // Range before the issues existed: no issues
+    let flow = flow_json(&["--from", "2000-01-01", "--to", "2000-01-15", "--interval", "week"]);
+    let points = flow["points"].as_array().expect("points missing");
+    let dates: Vec<&str> = points.iter().filter_map(|point| point["date"].as_str()).collect();
+    assert_eq!(dates, ["2000-01-01", "2000-01-08", "2000-01-15"]);
+    assert!(points.iter().all(|point| point["remaining"] == 0));
+
+    // CSV
+    let output = run_command(&["flow", "--csv"]).expect("flow csv failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "date,new,active,closed,deleted,remaining");
+    assert_eq!(lines[1], format!("{},1,1,1,0,2", today()));
+
+    // Table
+    let output = run_command(&["flow"]).expect("flow table failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("date        new  active  closed  deleted  remaining"));
+
+    // Invalid range
+    let result = run_command(&["flow", "--from", "2000-02-01", "--to", "2000-01-01"]);
+    assert!(result.is_err());
+}
@@ -0,0 +1,124 @@
+mod common;
+use common::{TestEnv, run_command, setup_issues};
+
+#[test]
+fn test_link_rejects_cycles() {
+    let _env = TestEnv::new();
+    setup_issues(3);
+
+    // Dependency: 1 blocked by 2, 2 blocked by 3
+    run_command(&["link", "1", "--add", "blocked-by=2"]).expect("link 1 failed");
+    run_command(&["link", "2", "--add", "blocked-by=3"]).expect("link 2 failed");
+
+    // 3 blocked by 1 closes the cycle, also via the reciprocal relationship
+    let err = run_command(&["link", "3", "--add", "blocked-by=1"]).expect_err("dependency cycle should be rejected");
+    assert!(err.contains("dependency cycle: #3 -> #1 -> #2 -> #3"));
+    run_command(&["link", "1", "--add", "blocks=3"]).expect_err("dependency cycle via reciprocal should be rejected");
+
+    // Hierarchy: 2 child of 1, 1 child of 2 is rejected
+    run_command(&["link", "1", "--add", "child=2"]).expect("link child failed");
+    run_command(&["link", "1", "--add", "parent=2"]).expect_err("hierarchy cycle should be rejected");
+
+    // Relationships without kind can form cycles
+    run_command(&["link", "1", "--add", "related=2"]).expect("link related failed");
+}
+
+#[test]
+fn test_list_blocked() {
+    let _env = TestEnv::new();
+    setup_issues(3);
+
+    run_command(&["link", "1", "--add", "blocked-by=2,3"]).expect("link failed");
+    run_command(&["set", "2", "--state", "closed"]).expect("set failed");
+
+    let output = run_command(&["list", "--columns", "id,blocked", "--filter", "blocked=yes"]).expect("list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("blocked"));
+    assert_eq!(stdout.lines().filter(|l| l.contains("yes")).count(), 1);
+
+    // The last open blocker is resolved
+    run_command(&["set", "3", "--state", "closed"]).expect("set failed");
+
+    let output = run_command(&["list", "--filter", "blocked=yes"]).expect("list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Issue 1"));
+}
+
+#[test]
+fn test_graph() {
+    let _env = TestEnv::new();
+    setup_issues(4);
+
+    run_command(&["link", "1", "--add", "blocked-by=2,3"]).expect("link 1 failed");
+    run_command(&["link", "3", "--add", "blocked-by=4"]).expect("link 3 failed");
+    run_command(&["link", "2", "--add", "blocked-by=4"]).expect("link 2 failed");
+
+    let output = run_command(&["graph", "1"]).expect("graph failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let expected = "\
+#1 Issue 1 [new]
+├── #2 Issue 2 [new]
+│   └── #4 Issue 4 [new]
+└── #3 Issue 3 [new]
+    └── #4 Issue 4 [new] (*)
+";
+    assert_eq!(stdout, expected);
+
+    run_command(&["graph", "99"]).expect_err("graph of unknown ID should fail");
+}
+
+#[test]
+fn test_graph_dot_mermaid() {
+    let _env = TestEnv::new();
+    setup_issues(4);
+
+    run_command(&["link", "1", "--add", "blocked-by=2", "related=3"]).expect("link failed");
+    run_command(&["link", "3", "--add", "child=4"]).expect("link child failed");
+    run_command(&["set", "2", "--state", "closed"]).expect("set failed");
+    run_command(&["set", "4", "--title", "Quote \"me\""]).expect("set title failed");
+
+    // Reciprocal relationships are collapsed into a single edge named after the side defining the kind
+    let output = run_command(&["graph", "--format", "dot"]).expect("graph dot failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("digraph issues {"));
+    assert!(stdout.contains("  1 -> 2 [label=\"blocked-by\"];"));
+    assert!(!stdout.contains("label=\"blocks\""));
+    assert!(stdout.contains("  1 -> 3 [label=\"related\", dir=none];"));
+    assert!(stdout.contains("  4 -> 3 [label=\"parent\"];"));
+    assert!(stdout.contains("#4 Quote \\\"me\\\"\\n[new]"));
+    assert_eq!(stdout.matches(" -> ").count(), 3);
+
+    let output = run_command(&["graph", "--format", "mermaid"]).expect("graph mermaid failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("flowchart TD"));
+    assert!(stdout.contains("  i1[\"#35;1 Issue 1 [new]\"]"));
+    assert!(stdout.contains("  i1 -->|blocked-by| i2"));
+    assert!(stdout.contains("  i1 ---|related| i3"));
+    assert!(stdout.contains("Quote #quot;me#quot;"));
+
+    // SVG (web graph): self-contained, one node per issue, arrows only for directed edges
+    let output = run_command(&["graph", "--format", "svg"]).expect("graph svg failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(!stdout.contains("<script") && !stdout.contains("href"));
+    assert_eq!(stdout.matches("class=\"node\"").count(), 4);
+    assert_eq!(stdout.matches("marker-end").count(), 2);
+    assert!(stdout.contains(">related</text>"));
+    assert!(stdout.contains("#4 Quote &quot;me&quot;"));
+
+    // Filter: only edges between the selected issues
+    let output = run_command(&["graph", "--format", "dot", "--filter", "state=new"]).expect("graph filter failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("  2 ["));
+    assert!(!stdout.contains("1 -> 2"));
+    assert!(stdout.contains("1 -> 3"));
+
+    // Issue: the issue and its dependencies
+    let output = run_command(&["graph", "1", "--format", "dot"]).expect("graph id failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1 -> 2"));
+    assert!(!stdout.contains("  3 ["));
+
+    run_command(&["graph", "--format", "tree"]).expect_err("tree without ID should fail");
+}
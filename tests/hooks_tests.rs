@@ -0,0 +1,127 @@
+// Hooks are run by `sh` in these tests
+#![cfg(unix)]
+
+mod common;
+use common::{TestEnv, disable_auto_commit, load_yaml_values, run_command, save_yaml_values};
+
+/// Hook script: appends the event name and the JSON payload (one line each) to events.log in the repository root
+const HOOK: &str = "#!/bin/sh\necho \"$GIT_ISSUE_EVENT\" >> events.log\ncat >> events.log\necho >> events.log\n";
+
+fn set_hooks(hooks: &str) {
+    let mut config = load_yaml_values(".gitissues/config.yaml");
+    config["hooks"] = serde_yaml::from_str(hooks).expect("hooks malformatted");
+    save_yaml_values(".gitissues/config.yaml", &config);
+}
+
+/// Events of events.log: (hook name, payload)
+fn events() -> Vec<(String, serde_json::Value)> {
+    let log = std::fs::read_to_string("events.log").unwrap_or_default();
+    let lines: Vec<&str> = log.lines().collect();
+
+    lines
+        .chunks(2)
+        .map(|chunk| (chunk[0].to_string(), serde_json::from_str(chunk[1]).expect("payload is no JSON")))
+        .collect()
+}
+
+#[test]
+fn test_hooks_events() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+    std::fs::write("hook.sh", HOOK).expect("write hook failed");
+
+    set_hooks("{on_new: [sh hook.sh], on_state_change: [sh hook.sh], on_assign: [sh hook.sh], on_comment: [sh hook.sh]}");
+
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["user"] = serde_yaml::Value::String("alice".to_string());
+    // Editor appending a comment to the description
+    settings["editor"] = serde_yaml::Value::String("sh -c 'printf \"\\nLGTM\\n\" >> \"$0\"'".to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    run_command(&["new", "A", "--assignee", "bob"]).expect("new failed");
+    run_command(&["set", "1", "--state", "active", "--priority", "P1"]).expect("set failed");
+    run_command(&["set", "1", "--priority", "P2"]).expect("set failed"); // no event
+    run_command(&["edit", "1"]).expect("edit failed");
+
+    let events = events();
+    let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["on_new", "on_assign", "on_state_change", "on_comment"]);
+
+    let (_, new) = &events[0];
+    assert_eq!(new["event"], "new");
+    assert_eq!(new["user"], "alice");
+    assert_eq!(new["issue"]["id"], 1);
+    assert_eq!(new["issue"]["title"], "A");
+
+    let (_, assign) = &events[1];
+    assert_eq!(assign["from"], "");
+    assert_eq!(assign["to"], "bob");
+
+    let (_, state) = &events[2];
+    assert_eq!(state["event"], "state_change");
+    assert_eq!(state["from"], "new");
+    assert_eq!(state["to"], "active");
+    assert_eq!(state["issue"]["priority"], "P1");
+
+    let (_, comment) = &events[3];
+    assert_eq!(comment["event"], "comment");
+    assert_eq!(comment["text"], "LGTM");
+}
+
+#[test]
+fn test_hooks_failure_timeout() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    set_hooks("{timeout: 1, on_new: ['echo boom >&2; exit 3', 'sleep 5', 'true']}");
+
+    // Failures are reported, the issue is created anyway
+    let output = run_command(&["new", "A"]).expect("new failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("Warning: hook on_new `echo boom >&2; exit 3` of #1: failed (exit status: 3): boom"));
+    assert!(stdout.contains("Warning: hook on_new `sleep 5` of #1: timed out after 1s"));
+    assert!(!stdout.contains("`true`"));
+    assert!(stdout.contains("Created issue #1"));
+}
+
+#[test]
+fn test_hooks_timeout_without_reading_stdin() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    set_hooks("{timeout: 1, on_new: ['sleep 5']}");
+
+    // Payload larger than the pipe buffer, the hook never reads it
+    let title = "x".repeat(100_000);
+    let start = std::time::Instant::now();
+    let output = run_command(&["new", &title]).expect("new failed");
+
+    assert!(start.elapsed() < std::time::Duration::from_secs(4));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("timed out after 1s"));
+}
+
+#[test]
+fn test_hooks_timeout_kills_subprocesses() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    // A sub-shell outlives a killed `sh`, a background process keeps stderr open after the hook exited
+    set_hooks("{timeout: 1, on_new: ['(sleep 3; touch survived)', 'sleep 10 >&2 & exit 4']}");
+
+    let start = std::time::Instant::now();
+    let output = run_command(&["new", "A"]).expect("new failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    assert!(stdout.contains("Warning: hook on_new `(sleep 3; touch survived)` of #1: timed out after 1s"));
+    assert!(stdout.contains("Warning: hook on_new `sleep 10 >&2 & exit 4` of #1: failed (exit status: 4)"));
+
+    // The sub-shell was killed together with the hook
+    std::thread::sleep(std::time::Duration::from_secs(3));
+    assert!(!std::fs::exists("survived").unwrap_or(true));
+}
@@ -0,0 +1,42 @@
+use git_issue::html::markdown_to_html;
+
+fn render(markdown: &str) -> String {
+    markdown_to_html(markdown, "/show/1/", &|id| format!("/show/{id}")).expect("markdown_to_html failed")
+}
+
+#[test]
+fn test_markdown_issue_links() {
+    let html = render("# Issue #1 -- Title\n\nSee #2 and [#3](https://example.com), `#4`\n\n```\n#5\n```\n");
+
+    assert!(html.contains("<h1>Issue #1 -- Title</h1>")); // title not linked
+    assert!(html.contains("See <a href=\"/show/2\">#2</a> and"));
+    assert!(html.contains("<a href=\"https://example.com\">#3</a>"));
+    assert!(html.contains("<code>#4</code>"));
+    assert!(html.contains("<pre><code>#5\n</code></pre>"));
+}
+
+#[test]
+fn test_markdown_relative_urls() {
+    let html = render("![a](attachments/a.png) [b](attachments/b.txt) [c](https://example.com/c) [d](/list) [e](#top)");
+
+    assert!(html.contains("<img src=\"/show/1/attachments/a.png\" alt=\"a\">"));
+    assert!(html.contains("<a href=\"/show/1/attachments/b.txt\">b</a>"));
+    assert!(html.contains("<a href=\"https://example.com/c\">c</a>"));
+    assert!(html.contains("<a href=\"/list\">d</a>"));
+    assert!(html.contains("<a href=\"#top\">e</a>"));
+}
+
+#[test]
+fn test_markdown_sanitized() {
+    let html = render(
+        "<script>alert(1)</script><b onclick=\"x()\">bold</b>\n\n[x](javascript:alert(1))\n\n- [x] done\n- [ ] todo\n\n| a |\n| :-: |\n| 1 |\n",
+    );
+
+    assert!(!html.contains("script"));
+    assert!(!html.contains("onclick"));
+    assert!(!html.contains("javascript:"));
+    assert!(html.contains("<a>x</a>"));
+    assert!(html.contains("<b>bold</b>"));
+    assert!(html.contains("<input disabled=\"\" type=\"checkbox\" checked=\"\">"));
+    assert!(html.contains("<td style=\"text-align:center\">1</td>"));
+}
@@ -0,0 +1,255 @@
+use std::fs;
+
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_git};
+
+fn setup() {
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    // An existing issue, imported IDs continue after it
+    run_command(&["new", "Existing"]).expect("new failed");
+}
+
+fn write_fixture(name: &str, content: &str) {
+    fs::write(name, content).expect("Failed to write fixture");
+}
+
+fn meta(id: u32) -> serde_yaml::Value {
+    load_yaml_values(&format!(".gitissues/issues/{id:010}/meta.yaml"))
+}
+
+#[test]
+fn test_import_github() {
+    let _env = TestEnv::new();
+    setup();
+    write_fixture("github.json", include_str!("includes/import_github.json"));
+
+    // 'octocat' is not in users.yaml
+    let err = run_command(&["import", "--from", "github-json", "github.json"]).expect_err("import with unknown user should fail");
+    assert!(err.contains("users in users.yaml:users [\"octocat\"]"));
+    assert!(!fs::exists(".gitissues/issues/0000000002").unwrap_or(true));
+
+    let output = run_command(&["import", "--from", "github-json", "github.json", "--create-missing"]).expect("import failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Imported 12 as #2"));
+    assert!(stdout.contains("Imported 13 as #3"));
+
+    let users = fs::read_to_string(".gitissues/users.yaml").expect("Failed to read users.yaml");
+    assert!(users.contains("# List of valid users")); // comments are kept
+    assert!(users.contains("  - id: octocat"));
+
+    let meta2 = meta(2);
+    assert_eq!(meta2["title"].as_str(), Some("Crash on startup"));
+    assert_eq!(meta2["state"].as_str(), Some("new"));
+    assert_eq!(meta2["reporter"].as_str(), Some("alice"));
+    assert_eq!(meta2["assignee"].as_str(), Some("octocat"));
+    assert_eq!(meta2["labels"][1].as_str(), Some("ui"));
+    assert_eq!(meta2["created"].as_str(), Some("2025-03-01T08:30:00Z"));
+    assert_eq!(meta2["updated"].as_str(), Some("2025-03-02T08:00:00Z"));
+    assert_eq!(meta2["relationships"]["related"][0].as_u64(), Some(3));
+
+    let meta3 = meta(3);
+    assert_eq!(meta3["state"].as_str(), Some("closed"));
+    assert_eq!(meta3["relationships"]["related"][0].as_u64(), Some(2));
+
+    let description = fs::read_to_string(".gitissues/issues/0000000002/description.md").expect("Failed to read description.md");
+    assert!(description.starts_with("# Description\n\nThe app crashes on startup."));
+    assert!(description.contains("# Comments\n\n## bob -- 2025-03-02T10:00:00Z\n\nReproduced on Linux.\n"));
+
+    // ID map
+    let imports: Vec<_> = fs::read_dir(".gitissues/imports").expect("imports missing").collect();
+    assert_eq!(imports.len(), 1);
+    let path = imports[0].as_ref().expect("entry").path();
+    assert!(path.to_string_lossy().ends_with("-github-json.yaml"));
+    let id_map = load_yaml_values(&path.to_string_lossy());
+    assert_eq!(id_map["ids"]["12"].as_u64(), Some(2));
+    assert_eq!(id_map["ids"]["13"].as_u64(), Some(3));
+}
+
+#[test]
+fn test_import_gitlab() {
+    let _env = TestEnv::new();
+    setup();
+    write_fixture("gitlab.json", include_str!("includes/import_gitlab.json"));
+
+    run_command(&["import", "--from", "gitlab-json", "gitlab.json"]).expect("import failed");
+
+    let meta2 = meta(2);
+    assert_eq!(meta2["state"].as_str(), Some("new"));
+    assert_eq!(meta2["due_date"].as_str(), Some("2025-06-30"));
+    assert_eq!(meta2["relationships"]["related"][0].as_u64(), Some(3));
+
+    let description = fs::read_to_string(".gitissues/issues/0000000002/description.md").expect("Failed to read description.md");
+    assert!(description.contains("Looking into it."));
+    assert!(!description.contains("changed the description")); // system notes are skipped
+}
+
+#[test]
+fn test_import_jira() {
+    let _env = TestEnv::new();
+    setup();
+    write_fixture("jira.csv", include_str!("includes/import_jira.csv"));
+    write_fixture("mapping.yaml", include_str!("includes/import_mapping.yaml"));
+
+    let err = run_command(&["import", "--from", "jira-csv", "jira.csv"]).expect_err("unmapped values should fail");
+    assert!(err.contains("states in config.yaml:states [\"in progress\", \"to do\", \"done\"]"));
+
+    run_command(&["import", "--from", "jira-csv", "jira.csv", "--mapping", "mapping.yaml"]).expect("import failed");
+
+    // PROJ-1 -> #2, PROJ-2 -> #3, PROJ-3 -> #4
+    let meta2 = meta(2);
+    assert_eq!(meta2["state"].as_str(), Some("active"));
+    assert_eq!(meta2["type"].as_str(), Some("feature"));
+    assert_eq!(meta2["reporter"].as_str(), Some("alice"));
+    assert_eq!(meta2["created"].as_str(), Some("2025-03-12T10:15:00Z"));
+
+    let meta3 = meta(3);
+    assert_eq!(meta3["assignee"].as_str(), Some("bob"));
+    assert_eq!(meta3["labels"].as_sequence().map(|l| l.len()), Some(2));
+    assert_eq!(meta3["relationships"]["parent"][0].as_u64(), Some(2)); // via 'Issue id'
+    assert_eq!(meta3["relationships"]["blocked-by"][0].as_u64(), Some(4));
+    assert_eq!(meta3["relationships"]["related"][0].as_u64(), Some(2));
+
+    let meta4 = meta(4);
+    assert_eq!(meta4["state"].as_str(), Some("closed"));
+    assert_eq!(meta4["type"].as_str(), Some("task"));
+    assert_eq!(meta4["relationships"]["parent"][0].as_u64(), Some(2)); // via key
+    assert_eq!(meta4["relationships"]["blocks"][0].as_u64(), Some(3));
+
+    let children = meta2["relationships"]["child"].as_sequence().map(|c| c.len());
+    assert_eq!(children, Some(2));
+
+    let description = fs::read_to_string(".gitissues/issues/0000000003/description.md").expect("Failed to read description.md");
+    assert!(description.contains("## Bob Jones -- 13/Mar/25 8:00 AM\n\nNeeds the API first"));
+}
+
+#[test]
+fn test_import_create_missing_config() {
+    let _env = TestEnv::new();
+    setup();
+    write_fixture("jira.csv", include_str!("includes/import_jira.csv"));
+
+    run_command(&["import", "--from", "jira-csv", "jira.csv", "--create-missing"]).expect("import failed");
+
+    let config = load_yaml_values(".gitissues/config.yaml");
+    let states: Vec<&str> = config["states"]
+        .as_sequence()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.as_str())
+        .collect();
+    assert!(states.ends_with(&["in progress", "to do", "done"]));
+    let types: Vec<&str> = config["types"]
+        .as_sequence()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.as_str())
+        .collect();
+    assert!(types.ends_with(&["epic", "story"]));
+
+    let output = run_command(&["list", "--filter", "state=to do", "--columns", "id"]).expect("list failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("3"));
+}
+
+#[test]
+fn test_import_csv_round_trip() {
+    let _env = TestEnv::new();
+    setup();
+    run_command(&["new", "Second", "--labels", "ui"]).expect("new failed");
+
+    let output = run_command(&[
+        "list",
+        "--csv",
+        "--columns",
+        "id,title,state,labels,priority,created",
+        "--sort",
+        "id=asc",
+    ])
+    .expect("list --csv failed");
+    let csv = String::from_utf8_lossy(&output.stdout).to_string();
+
+    // Triage in a spreadsheet: edit #1, keep #2, add a new issue
+    let csv = csv.replacen("1,Existing,new,-,-", "1,\"Existing, \"\"renamed\"\"\",active,\"a,b\",P1", 1);
+    let csv = format!("{csv},New from sheet,closed,-,P2,\r\n");
+    write_fixture("triage.csv", &csv);
+
+    let output = run_command(&["import", "--csv", "triage.csv"]).expect("import --csv failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Ignored read-only column(s): created"));
+    assert!(stdout.contains("Created 1 and updated 1 issue(s)"));
+
+    let meta1 = meta(1);
+    assert_eq!(meta1["title"].as_str(), Some("Existing, \"renamed\""));
+    assert_eq!(meta1["state"].as_str(), Some("active"));
+    assert_eq!(meta1["labels"][1].as_str(), Some("b"));
+    assert_eq!(meta1["priority"].as_str(), Some("P1"));
+
+    let meta3 = meta(3);
+    assert_eq!(meta3["title"].as_str(), Some("New from sheet"));
+    assert_eq!(meta3["state"].as_str(), Some("closed"));
+    assert_eq!(meta3["priority"].as_str(), Some("P2"));
+
+    // Invalid rows are rejected before anything is written
+    write_fixture("invalid.csv", "id,title\r\n1,Changed\r\n99,Unknown\r\n");
+    run_command(&["import", "--csv", "invalid.csv"]).expect_err("unknown ID should fail");
+    write_fixture("invalid.csv", "id,title,state\r\n1,Changed,new\r\n,Another,resolved\r\n");
+    let err = run_command(&["import", "--csv", "invalid.csv"]).expect_err("unknown state should fail");
+    assert!(err.contains("Invalid state \"resolved\" in line 3"));
+    assert_eq!(meta(1)["title"].as_str(), Some("Existing, \"renamed\""));
+
+    run_command(&["import", "--csv", "--from", "github-json", "triage.csv"]).expect_err("--csv and --from conflict");
+}
+
+#[test]
+fn test_import_csv_unchanged() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "No labels"]).expect("new failed");
+    run_command(&["new", "With labels", "--labels", "ui,backend", "--priority", "P1"]).expect("new failed");
+    run_command(&["new", "Assigned", "--assignee", "me"]).expect("new failed");
+
+    run_command(&["list", "--csv", "--columns", "*", "--output", "all.csv"]).expect("list --csv failed");
+    let commits = run_git(&["rev-list", "--count", "HEAD"]);
+
+    // Re-importing an unchanged export changes nothing
+    let output = run_command(&["import", "--csv", "all.csv"]).expect("import --csv failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Created 0 and updated 0 issue(s)"));
+    assert_eq!(run_git(&["rev-list", "--count", "HEAD"]), commits);
+    assert_eq!(meta(1)["labels"].as_sequence().map(Vec::len), Some(0));
+}
+
+#[test]
+fn test_import_csv_single_commit_undo() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "First"]).expect("new failed");
+    run_command(&["new", "Second"]).expect("new failed");
+    let commits: u32 = run_git(&["rev-list", "--count", "HEAD"])
+        .trim()
+        .parse()
+        .expect("Invalid commit count");
+
+    write_fixture(
+        "triage.csv",
+        "id,title,state\r\n1,First renamed,active\r\n2,Second,closed\r\n,New from sheet,closed\r\n",
+    );
+    let output = run_command(&["import", "--csv", "triage.csv"]).expect("import --csv failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Created 1 and updated 2 issue(s)"));
+
+    // One commit for all rows, incl. the state of the new issue
+    let count: u32 = run_git(&["rev-list", "--count", "HEAD"])
+        .trim()
+        .parse()
+        .expect("Invalid commit count");
+    assert_eq!(count, commits + 1);
+    assert_eq!(meta(3)["state"].as_str(), Some("closed"));
+
+    // A single undo reverts the whole import
+    run_command(&["undo"]).expect("undo failed");
+    assert_eq!(meta(1)["title"].as_str(), Some("First"));
+    assert_eq!(meta(2)["state"].as_str(), Some("new"));
+    assert!(!fs::exists(".gitissues/issues/0000000003").unwrap_or(true));
+}
@@ -514,3 +514,31 @@ fn test_list_sort() {
     assert!(position2 < position1);
     assert!(position1 < position3);
 }
+
+#[test]
+fn test_list_csv() {
+    let _env = TestEnv::new();
+
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "Say \"hello\", world", "--labels", "ui,cli"]).expect("new 1 failed");
+    run_command(&["new", "Plain"]).expect("new 2 failed");
+
+    // Quoted only if needed, embedded quotes doubled, no trailing separator, CRLF
+    let output = run_command(&["list", "--csv", "--columns", "id,title,labels", "--sort", "id=asc"]).expect("list --csv failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "id,title,labels\r\n1,\"Say \"\"hello\"\", world\",\"ui,cli\"\r\n2,Plain,-\r\n"
+    );
+
+    // Separator from settings.yaml
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["export_csv_separator"] = serde_yaml::Value::String(";".to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    run_command(&["list", "--csv", "--columns", "id,labels", "--output", "out.csv"]).expect("list --csv --output failed");
+    let content = fs::read_to_string("out.csv").expect("Failed to read out.csv");
+    assert!(content.contains("\r\n1;ui,cli\r\n"));
+}
@@ -0,0 +1,49 @@
+mod common;
+use common::{TestEnv, run_command};
+
+#[test]
+fn test_manpages() {
+    let _env = TestEnv::new();
+
+    let output = run_command(&["manpages", "man"]).expect("manpages failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("man pages to man"));
+
+    for page in [
+        "man/man1/git-issue.1",
+        "man/man1/git-issue-set.1",
+        "man/man1/git-issue-milestone-new.1",
+        "man/man1/git-issue-web.1",
+        "man/man5/git-issue-config.5",
+        "man/man5/git-issue-settings.5",
+        "man/man5/git-issue-users.5",
+        "man/man5/git-issue-milestones.5",
+    ] {
+        assert!(std::path::Path::new(page).exists(), "{page}");
+    }
+
+    // No page for clap's `help` subcommand
+    assert!(!std::path::Path::new("man/man1/git-issue-help.1").exists());
+
+    let main = std::fs::read_to_string("man/man1/git-issue.1").expect("read failed");
+    assert!(main.contains("git\\-issue\\-set(1)"));
+    assert!(main.contains(".SH FILES"));
+    assert!(main.contains("git\\-issue\\-config(5)"));
+    assert!(main.contains("git\\-issue\\-milestones(5)"));
+
+    // YAML files: nested keys, maps, enums and defaults
+    let config = std::fs::read_to_string("man/man5/git-issue-config.5").expect("read failed");
+    assert!(config.contains("\\fBcommit_auto\\fR (boolean)"));
+    assert!(config.contains("\\fBrelationships.<name>.kind\\fR"));
+    assert!(config.contains("\\fBhooks.timeout\\fR"));
+    assert!(config.contains("[default: [closed, deleted]]"));
+
+    let settings = std::fs::read_to_string("man/man5/git-issue-settings.5").expect("read failed");
+    assert!(settings.contains("\\fBnotifications.smtp.port\\fR (integer) [default: 587]"));
+    assert!(settings.contains("\\fBstarttls\\fR"));
+
+    let users = std::fs::read_to_string("man/man5/git-issue-users.5").expect("read failed");
+    assert!(users.contains("\\fBusers[].emails\\fR"));
+
+    let milestones = std::fs::read_to_string("man/man5/git-issue-milestones.5").expect("read failed");
+    assert!(milestones.contains("\\fBmilestones[].state\\fR (enum) [default: open]"));
+}
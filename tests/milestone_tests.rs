@@ -0,0 +1,127 @@
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_git, save_yaml_values};
+
+#[test]
+fn test_milestone_new_list_show_close() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&[
+        "milestone",
+        "new",
+        "Sprint 1",
+        "--start",
+        "2000-01-01",
+        "--due",
+        "2000-01-14",
+        "--description",
+        "First sprint",
+    ])
+    .expect("milestone new failed");
+    run_command(&["milestone", "new", "Sprint 2"]).expect("milestone new failed");
+
+    let yaml = load_yaml_values(".gitissues/milestones.yaml");
+    assert_eq!(yaml["milestones"][0]["name"], "Sprint 1");
+    assert_eq!(yaml["milestones"][0]["due"], "2000-01-14");
+    assert_eq!(yaml["milestones"][0]["state"], "open");
+    assert_eq!(yaml["milestones"][1]["name"], "Sprint 2");
+
+    // Invalid milestones
+    assert!(run_command(&["milestone", "new", "Sprint 1"]).is_err()); // duplicate
+    assert!(run_command(&["milestone", "new", "a,b"]).is_err());
+    assert!(run_command(&["milestone", "new", "Sprint 3", "--due", "2000-13-01"]).is_err());
+    assert!(run_command(&["milestone", "new", "Sprint 3", "--start", "2000-02-01", "--due", "2000-01-01"]).is_err());
+
+    // Issues: #1 done, #2 overdue via the milestone's due date, #3 with its own due date in the future
+    run_command(&["new", "Crash", "--milestone", "Sprint 1"]).expect("new 1 failed");
+    run_command(&["new", "Docs", "--milestone", "Sprint 1"]).expect("new 2 failed");
+    run_command(&["new", "Later", "--due-date", "2999-01-01"]).expect("new 3 failed");
+    run_command(&["set", "3", "--milestone", "Sprint 1"]).expect("set milestone failed");
+    run_command(&["set", "1", "--state", "closed"]).expect("set state failed");
+    run_command(&["new", "Other", "--milestone", "Sprint 2"]).expect("new 4 failed");
+
+    assert!(run_command(&["new", "Unknown", "--milestone", "Sprint 9"]).is_err());
+
+    let meta = load_yaml_values(".gitissues/issues/0000000003/meta.yaml");
+    assert_eq!(meta["milestone"], "Sprint 1");
+
+    let output = run_command(&["milestone", "list"]).expect("milestone list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Sprint 1  open   2000-01-01  2000-01-14  1/3 (33%)  1"));
+    assert!(stdout.contains("Sprint 2  open   -           -           0/1 (0%)   0"));
+
+    let output = run_command(&["milestone", "show", "Sprint 1"]).expect("milestone show failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Done:      1/3 (33%)"));
+    assert!(stdout.contains("Overdue:   #2"));
+    assert!(stdout.contains("First sprint"));
+    assert!(stdout.contains("Crash") && stdout.contains("Docs") && stdout.contains("Later"));
+    assert!(!stdout.contains("Other"));
+
+    assert!(run_command(&["milestone", "show", "Sprint 9"]).is_err());
+
+    // Closed: issues keep it, but no issues can be added
+    run_command(&["milestone", "close", "Sprint 2"]).expect("milestone close failed");
+    assert!(run_command(&["milestone", "close", "Sprint 2"]).is_err());
+
+    let result = run_command(&["set", "1", "--milestone", "Sprint 2"]);
+    assert!(result.is_err_and(|e| e.contains("is closed")));
+
+    run_command(&["set", "4", "--title", "Other issue"]).expect("set of an issue in a closed milestone failed");
+    run_command(&["set", "4", "--milestone", ""]).expect("removing the milestone failed");
+
+    let output = run_command(&["milestone", "list"]).expect("milestone list failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Sprint 2  closed  -           -           0/0 (0%)   0"));
+}
+
+#[test]
+fn test_milestone_list_column_filter_sort() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    // Defined in this order: sorting follows milestones.yaml, not the names
+    run_command(&["milestone", "new", "Sprint 10"]).expect("milestone new failed");
+    run_command(&["milestone", "new", "Sprint 9"]).expect("milestone new failed");
+
+    run_command(&["new", "A", "--milestone", "Sprint 9"]).expect("new 1 failed");
+    run_command(&["new", "B", "--milestone", "Sprint 10"]).expect("new 2 failed");
+    run_command(&["new", "C"]).expect("new 3 failed");
+
+    let output = run_command(&["list", "--columns", "id,milestone", "--sort", "milestone=asc", "--csv"]).expect("list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["id,milestone", "3,-", "2,Sprint 10", "1,Sprint 9"]);
+
+    let output = run_command(&["list", "--columns", "id", "--filter", "milestone=Sprint 9", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "1"]);
+
+    let output = run_command(&["list", "--columns", "id", "--filter", "milestone=", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "3"]);
+
+    // Show: 'cat' as editor prints the markdown
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["editor"] = serde_yaml::Value::String(if cfg!(windows) { "type" } else { "cat" }.to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    let output = run_command(&["show", "1"]).expect("show failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("| **milestone**     | Sprint 9"));
+}
+
+#[test]
+fn test_milestone_commit() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    run_command(&["milestone", "new", "v1.0"]).expect("milestone new failed");
+
+    let log = run_git(&["log", "-1", "--format=%s%n%b"]);
+    assert!(log.contains("[issue] milestone new -- v1.0"));
+    assert!(log.contains("Issue-Action: milestone"));
+
+    run_command(&["undo"]).expect("undo failed");
+    let yaml = load_yaml_values(".gitissues/milestones.yaml");
+    assert!(yaml["milestones"].as_sequence().is_some_and(|m| m.is_empty()));
+}
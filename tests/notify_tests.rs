@@ -0,0 +1,220 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+mod common;
+use common::{TestEnv, disable_auto_commit, load_yaml_values, run_command, save_yaml_values};
+
+const USERS: &str = r#"_version: 2
+users:
+  - id: alice
+    emails: [alice@example.com]
+  - id: bob
+    name: Bob Builder
+    emails: [bob@example.com, bob@home.example.com]
+  - id: carol
+    emails: [carol@example.com]
+  - id: dave
+"#;
+
+fn setup(notifications: &str) {
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+    std::fs::write(".gitissues/users.yaml", USERS).expect("write users.yaml failed");
+
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["user"] = serde_yaml::Value::String("alice".to_string());
+    settings["notifications"] = serde_yaml::from_str(notifications).expect("notifications malformatted");
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+}
+
+/// Mails written by `transport: file`, oldest first
+fn mails() -> Vec<String> {
+    let mut paths: Vec<_> = std::fs::read_dir("mails")
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| std::fs::read_to_string(path).expect("read mail failed"))
+        .collect()
+}
+
+/// Minimal SMTP server accepting every mail, greeting after `delay`; sends the raw DATA of each mail through the channel
+fn fake_smtp_server(delay: Duration) -> (u16, mpsc::Receiver<(Vec<String>, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind failed");
+    let port = listener.local_addr().expect("no address").port();
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().expect("clone failed"));
+            let mut recipients = Vec::new();
+
+            thread::sleep(delay);
+            let _ = stream.write_all(b"220 fake ESMTP\r\n");
+
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    break;
+                }
+
+                let command = line.trim_end().to_uppercase();
+
+                if command.starts_with("RCPT TO:") {
+                    recipients.push(line.trim_end()[8..].trim_matches(['<', '>']).to_string());
+                    let _ = stream.write_all(b"250 OK\r\n");
+                } else if command == "DATA" {
+                    let _ = stream.write_all(b"354 Go ahead\r\n");
+
+                    let mut data = String::new();
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&line);
+                    }
+
+                    let _ = sender.send((std::mem::take(&mut recipients), data));
+                    let _ = stream.write_all(b"250 OK\r\n");
+                } else if command == "QUIT" {
+                    let _ = stream.write_all(b"221 Bye\r\n");
+                    break;
+                } else {
+                    let _ = stream.write_all(b"250 OK\r\n");
+                }
+            }
+        }
+    });
+
+    (port, receiver)
+}
+
+#[test]
+fn test_notify_file() {
+    let _env = TestEnv::new();
+    setup("{transport: file, from: 'git-issue <issues@example.com>', file_dir: mails}");
+
+    // Assignment: mail to the assignee only
+    run_command(&["new", "Login broken", "--assignee", "bob", "--priority", "P1"]).expect("new failed");
+
+    // Assigned to yourself, or to a user without email: no mail
+    run_command(&["new", "Mine", "--assignee", "me"]).expect("new failed");
+    run_command(&["set", "2", "--assignee", "dave"]).expect("set failed");
+    assert_eq!(mails().len(), 1);
+
+    let mail = &mails()[0];
+    assert!(mail.contains("From: git-issue <issues@example.com>"));
+    assert!(mail.contains("To: bob@example.com"));
+    assert!(mail.contains("Subject: #1 Login broken: assigned to you"));
+    assert!(mail.contains("Hi Bob Builder,"));
+    assert!(mail.contains("alice assigned issue #1 to you."));
+    assert!(mail.contains("Priority:  P1"));
+
+    // State change: assignee and watchers, except the user who made the change
+    run_command(&["watch", "1", "--user", "carol"]).expect("watch failed");
+    run_command(&["set", "1", "--state", "active"]).expect("set failed");
+
+    let mails = mails();
+    assert_eq!(mails.len(), 2);
+    assert!(mails[1].contains("To: bob@example.com, carol@example.com"));
+    assert!(mails[1].contains("Subject: #1 Login broken: new -> active"));
+    assert!(mails[1].contains("alice changed the state of issue #1: new -> active"));
+    assert!(mails[1].contains("Assignee:  Bob Builder"));
+}
+
+#[test]
+fn test_notify_smtp() {
+    let _env = TestEnv::new();
+    let (port, receiver) = fake_smtp_server(Duration::ZERO);
+
+    setup(&format!(
+        "{{transport: smtp, from: issues@example.com, on_state_change: false, smtp: {{host: 127.0.0.1, port: {port}, tls: none}}}}"
+    ));
+
+    run_command(&["new", "A", "--assignee", "carol"]).expect("new failed");
+    run_command(&["set", "1", "--state", "active"]).expect("set failed"); // disabled
+
+    let (recipients, data) = receiver.recv_timeout(Duration::from_secs(5)).expect("no mail received");
+    assert_eq!(recipients, ["carol@example.com"]);
+    assert!(data.contains("Subject: #1 A: assigned to you"));
+    assert!(data.contains("Hi carol,"));
+
+    assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+}
+
+#[test]
+fn test_notify_import() {
+    let _env = TestEnv::new();
+    setup("{transport: file, from: issues@example.com, file_dir: mails}");
+
+    // Imported issues notify their assignees like `new`
+    std::fs::write(
+        "github.json",
+        r#"[{"number": 7, "title": "From GitHub", "state": "open", "author": {"login": "alice"}, "assignees": [{"login": "carol"}]}]"#,
+    )
+    .expect("write github.json failed");
+    run_command(&["import", "--from", "github-json", "github.json"]).expect("import failed");
+
+    std::fs::write("triage.csv", "title,assignee\r\nFrom sheet,bob\r\n").expect("write triage.csv failed");
+    run_command(&["import", "--csv", "triage.csv"]).expect("import --csv failed");
+
+    let mails = mails();
+    assert_eq!(mails.len(), 2);
+    assert!(mails[0].contains("To: carol@example.com"));
+    assert!(mails[0].contains("Subject: #1 From GitHub: assigned to you"));
+    assert!(mails[1].contains("To: bob@example.com"));
+    assert!(mails[1].contains("Subject: #2 From sheet: assigned to you"));
+}
+
+#[test]
+fn test_notify_slow_server() {
+    let _env = TestEnv::new();
+    let (port, receiver) = fake_smtp_server(Duration::from_secs(3));
+
+    setup(&format!(
+        "{{transport: smtp, from: issues@example.com, smtp: {{host: 127.0.0.1, port: {port}, tls: none, timeout: 10}}}}"
+    ));
+
+    // The command waits only briefly for the server, the CLI finishes the delivery before it exits
+    let output = run_command(&["new", "A", "--assignee", "carol"]).expect("new failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Info: 1 notification(s) still being sent in the background"));
+    assert!(stdout.contains("Created issue #1"));
+
+    let (recipients, _) = receiver.recv_timeout(Duration::from_secs(1)).expect("no mail received");
+    assert_eq!(recipients, ["carol@example.com"]);
+}
+
+#[test]
+fn test_notify_failure() {
+    let _env = TestEnv::new();
+
+    // Nothing listens on the port: the command succeeds anyway
+    let port = TcpListener::bind("127.0.0.1:0")
+        .expect("bind failed")
+        .local_addr()
+        .expect("no address")
+        .port();
+
+    setup(&format!(
+        "{{transport: smtp, from: issues@example.com, smtp: {{host: 127.0.0.1, port: {port}, tls: none, timeout: 1}}}}"
+    ));
+
+    // The failed delivery is reported by the command which caused it
+    let output = run_command(&["new", "A", "--assignee", "bob"]).expect("new failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Warning: Notification of #1 to bob@example.com not sent: SMTP:"));
+    assert!(stdout.contains("Created issue #1"));
+    assert!(Path::new(".gitissues/issues/0000000001/meta.yaml").exists());
+
+    let output = run_command(&["set", "1", "--title", "B"]).expect("set failed");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Warning"));
+}
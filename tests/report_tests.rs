@@ -0,0 +1,107 @@
+use std::fs;
+
+mod common;
+use common::{TestEnv, init_git_repo, run_command, run_git};
+
+fn setup() {
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    run_command(&["new", "Crash on startup", "--type", "bug"]).expect("new 1 failed");
+    run_command(&["new", "Dark mode", "--type", "feature", "--labels", "ui"]).expect("new 2 failed");
+    run_command(&["new", "Reopened", "--type", "bug"]).expect("new 3 failed");
+    run_command(&["new", "Closed before", "--type", "task"]).expect("new 4 failed");
+    run_command(&["set", "4", "--state", "closed"]).expect("set 4 failed");
+    run_git(&["tag", "v1"]);
+
+    run_command(&["set", "1,2", "--state", "closed"]).expect("set 1,2 failed");
+    run_command(&["set", "3", "--state", "closed"]).expect("set 3 closed failed");
+    run_command(&["set", "3", "--state", "active"]).expect("set 3 active failed");
+    run_command(&["new", "Docs", "--labels", "docs,ui"]).expect("new 5 failed");
+    run_command(&["set", "5", "--state", "closed"]).expect("set 5 failed");
+    run_command(&["set", "4", "--title", "Closed before, renamed"]).expect("set 4 title failed");
+}
+
+#[test]
+fn test_report_changelog() {
+    let _env = TestEnv::new();
+    setup();
+
+    assert!(fs::exists(".gitissues/changelog.md").unwrap_or(false));
+
+    let output = run_command(&["report", "changelog", "--since", "v1"]).expect("report failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Closed before the range (#4) and reopened (#3) are left out; groups in the order of config.yaml:types
+    let date = chrono::Local::now().format("%Y-%m-%d");
+    let expected = format!(
+        "## [Unreleased] - {date}\n\n### Bug\n\n- Crash on startup (#1)\n\n### Feature\n\n- Dark mode (#2)\n\n### Other\n\n- Docs (#5)\n"
+    );
+    assert_eq!(stdout, expected);
+
+    // Group by label: an issue appears in each of its label groups
+    let output = run_command(&["report", "changelog", "--since", "v1", "--group-by", "labels"]).expect("report by labels failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("### Docs\n\n- Docs (#5)\n\n### Ui\n\n- Dark mode (#2)\n- Docs (#5)\n\n### Other\n\n- Crash on startup (#1)\n")
+    );
+
+    // Date range covering the whole history, until a revision
+    let output = run_command(&["report", "changelog", "--since", "2000-01-01", "--until", "v1"]).expect("report by date failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("### Task\n\n- Closed before (#4)\n"));
+    assert!(!stdout.contains("#1"));
+
+    // Nothing in the range
+    let output = run_command(&["report", "changelog", "--since", "HEAD"]).expect("empty report failed");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No issues transitioned into"));
+
+    run_command(&["report", "changelog", "--since", "no-such-rev"]).expect_err("unknown revision should fail");
+}
+
+#[test]
+fn test_report_changelog_template() {
+    let _env = TestEnv::new();
+    setup();
+
+    fs::write(
+        "template.md",
+        "# Release {until}\n{#group}\n{group}:\n{#issue}\n* #{id} {title} [{labels}] closed {closed}\n{/issue}\n{/group}\nThanks!\n",
+    )
+    .expect("Failed to write template");
+
+    let output = run_command(&[
+        "report",
+        "changelog",
+        "--since",
+        "v1",
+        "--until",
+        "HEAD",
+        "--template",
+        "template.md",
+    ])
+    .expect("report with template failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    assert!(stdout.starts_with("# Release HEAD\nBug:\n* #1 Crash on startup [] closed "));
+    assert!(stdout.contains(&format!("* #5 Docs [docs, ui] closed {today}\n")));
+    assert!(stdout.ends_with("Thanks!\n"));
+
+    fs::write("broken.md", "no blocks").expect("Failed to write template");
+    run_command(&["report", "changelog", "--since", "v1", "--template", "broken.md"]).expect_err("template without blocks should fail");
+}
+
+#[test]
+fn test_report_changelog_placeholders_in_values() {
+    let _env = TestEnv::new();
+    setup();
+
+    // Values are inserted as they are, placeholders within them are not filled
+    run_command(&["set", "1", "--title", "Crash in {assignee} and {closed}"]).expect("set 1 title failed");
+    fs::write("template.md", "{#group}\n{#issue}\n* {title} by {assignee}\n{/issue}\n{/group}\n").expect("Failed to write template");
+
+    let output = run_command(&["report", "changelog", "--since", "v1", "--template", "template.md"]).expect("report failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("* Crash in {assignee} and {closed} by \n"));
+}
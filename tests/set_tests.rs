@@ -1,5 +1,7 @@
 mod common;
-use common::{TestEnv, disable_auto_commit, load_yaml_values, run_command, run_command_with_stdin, save_yaml_values};
+use common::{
+    TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_command_with_stdin, run_git, save_yaml_values,
+};
 
 #[test]
 fn test_set_labels() {
@@ -635,3 +637,42 @@ fn test_set_bulk_wildcard() {
     assert!(stdout.contains("bob"));
     assert!(stdout.contains("carol"));
 }
+
+#[test]
+fn test_set_batch_commit() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+
+    let commit_count = || run_git(&["rev-list", "--count", "HEAD"]).trim().parse::<u32>().unwrap();
+    let commits_before = commit_count();
+
+    // Explicit IDs: one commit per issue
+    run_command(&["set", "1,2", "--state", "active"]).expect("set failed");
+    assert_eq!(commit_count(), commits_before + 2);
+
+    // Explicit IDs with --batch: single commit
+    run_command(&["set", "1,2", "--state", "closed", "--priority", "p1", "--batch"]).expect("set --batch failed");
+    assert_eq!(commit_count(), commits_before + 3);
+
+    let message = run_git(&["log", "-1", "--format=%s"]);
+    assert_eq!(message.trim(), "[issue] set state,priority of 2 issues: #1,#2");
+
+    // Wildcard: single commit by default
+    run_command(&["list"]).expect("list failed");
+    run_command_with_stdin(&["set", "*", "--assignee", "alice"], "y\n").expect("bulk set with wildcard failed");
+    assert_eq!(commit_count(), commits_before + 4);
+
+    let files = run_git(&["show", "--name-only", "--format=", "HEAD"]);
+    assert!(files.contains(".gitissues/issues/0000000001/meta.yaml"));
+    assert!(files.contains(".gitissues/issues/0000000003/meta.yaml"));
+
+    // Wildcard with --no-batch: one commit per issue
+    run_command(&["list"]).expect("list failed");
+    run_command_with_stdin(&["set", "*", "--assignee", "bob", "--no-batch"], "y\n").expect("bulk set with --no-batch failed");
+    assert_eq!(commit_count(), commits_before + 7);
+}
@@ -0,0 +1,106 @@
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, save_yaml_values};
+
+fn stats_json(args: &[&str]) -> serde_json::Value {
+    let output = run_command(&[&["stats", "--json"], args].concat()).expect("stats failed");
+    serde_json::from_slice(&output.stdout).expect("stats output is no valid JSON")
+}
+
+fn create_issues() {
+    run_command(&["new", "Crash", "--type", "bug", "--assignee", "alice", "--priority", "P1"]).expect("new 1 failed");
+    run_command(&["new", "Dark mode", "--type", "feature", "--labels", "ui,design"]).expect("new 2 failed");
+    run_command(&["new", "Docs", "--labels", "ui", "--due-date", "2000-01-01"]).expect("new 3 failed");
+    run_command(&["new", "Later", "--due-date", "2999-01-01"]).expect("new 4 failed");
+    run_command(&["set", "1,2", "--state", "closed"]).expect("set failed");
+}
+
+#[test]
+fn test_stats() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    create_issues();
+
+    let stats = stats_json(&[]);
+
+    assert_eq!(stats["total"], 4);
+    assert_eq!(stats["open"], 2);
+    assert_eq!(stats["closed"], 2);
+    assert_eq!(stats["by_state"]["new"], 2);
+    assert_eq!(stats["by_state"]["active"], 0);
+    assert_eq!(stats["by_state"]["closed"], 2);
+    assert_eq!(stats["by_type"]["bug"], 1);
+    assert_eq!(stats["by_type"]["-"], 2);
+    assert_eq!(stats["by_assignee"]["alice"], 1);
+    assert_eq!(stats["by_priority"]["P1"], 1);
+    assert_eq!(stats["by_label"]["ui"], 2);
+    assert_eq!(stats["by_label"]["design"], 1);
+
+    // Times from the git history: both closed right after creation
+    assert_eq!(stats["cycle_time"]["count"], 2);
+    assert_eq!(stats["cycle_time"]["median_days"], 0.0);
+    assert_eq!(stats["lead_time"]["bug"]["count"], 1);
+    assert_eq!(stats["lead_time"]["feature"]["count"], 1);
+    assert!(stats["lead_time"].get("task").is_none());
+
+    let over_time = stats["over_time"].as_array().expect("over_time missing");
+    let last = over_time.last().expect("over_time is empty");
+    assert_eq!(last["open"], 2);
+    assert_eq!(last["closed"], 2);
+
+    assert_eq!(stats["due"]["overdue"], 1);
+    assert_eq!(stats["due"]["due_later"], 1);
+    assert_eq!(stats["due"]["no_due_date"], 0); // closed issues are not counted
+
+    // Reopened: no longer done
+    run_command(&["set", "2", "--state", "active"]).expect("reopen failed");
+    let stats = stats_json(&["--period", "week"]);
+    assert_eq!(stats["cycle_time"]["count"], 1);
+    assert!(stats["over_time"][0]["period"].as_str().is_some_and(|p| p.contains("-W")));
+
+    // Table output
+    let output = run_command(&["stats"]).expect("stats table failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Issues: 4 (open: 3, closed: 1)"));
+    assert!(stdout.contains("overdue            1"));
+}
+
+#[test]
+fn test_stats_without_history() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+    create_issues();
+
+    let output = run_command(&["stats", "--json"]).expect("stats failed");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No git history available"));
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stats output is no valid JSON");
+    assert_eq!(stats["closed"], 2);
+    assert_eq!(stats["cycle_time"]["count"], 2); // from 'updated'
+}
+
+#[test]
+fn test_stats_history_from_subdirectory() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "Crash"]).expect("new failed");
+    run_command(&["set", "1", "--state", "closed"]).expect("set failed");
+
+    // 'updated' far in the future: only the git history gives the real cycle time of 0 days
+    let path = ".gitissues/issues/0000000001/meta.yaml";
+    let mut meta = load_yaml_values(path);
+    meta["updated"] = serde_yaml::Value::String("2999-01-01T00:00:00Z".to_string());
+    save_yaml_values(path, &meta);
+
+    std::fs::create_dir("src").expect("create dir failed");
+    std::env::set_current_dir("src").expect("change dir failed");
+
+    let output = run_command(&["stats", "--json"]).expect("stats failed");
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("No git history available"));
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stats output is no valid JSON");
+    assert_eq!(stats["cycle_time"]["count"], 1);
+    assert_eq!(stats["cycle_time"]["median_days"], 0.0);
+}
@@ -0,0 +1,102 @@
+use std::env;
+use std::fs;
+
+use git_issue::storage::{FsStorage, GitStorage, Storage};
+
+mod common;
+use common::{TestEnv, init_git_repo, load_yaml_values, run_command, run_git, save_yaml_values};
+
+#[test]
+fn test_storage_bare_repository() {
+    let _env = TestEnv::new();
+
+    let root = env::current_dir().expect("Failed to get current dir");
+
+    // Create issues in a working repository
+    fs::create_dir("work").expect("Failed to create work dir");
+    env::set_current_dir(root.join("work")).expect("Failed to change dir");
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "Issue 1", "--assignee", "alice"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2", "--assignee", "bob"]).expect("new 2 failed");
+    fs::write(".gitissues/issues/0000000001/attachments/log.txt", "boot failed").expect("Failed to write attachment");
+    run_git(&["add", "."]);
+    run_git(&["commit", "--quiet", "-m", "add attachment"]);
+
+    // Clone it bare: no working tree, no .gitissues directory
+    env::set_current_dir(&root).expect("Failed to change dir");
+    run_git(&["clone", "--quiet", "--bare", "work", "bare.git"]);
+
+    let storage = GitStorage::open(&root.join("bare.git"), "HEAD").expect("open bare repository failed");
+
+    let mut ids = storage.issue_ids().expect("issue_ids failed");
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+
+    assert_eq!(storage.load_meta(2).expect("load_meta failed").assignee, "bob");
+    assert!(
+        storage
+            .load_description(1)
+            .expect("load_description failed")
+            .contains("# Description")
+    );
+    assert_eq!(storage.load_config().expect("load_config failed").states[0], "new");
+    assert_eq!(storage.attachments(1).expect("attachments failed"), vec!["log.txt"]);
+    assert_eq!(
+        storage.read_attachment(1, "log.txt").expect("read_attachment failed"),
+        Some(b"boot failed".to_vec())
+    );
+    assert_eq!(storage.read_attachment(1, "../meta.yaml").expect("read_attachment failed"), None);
+
+    // list works on the bare repository, also with filters
+    let result = git_issue::list(&storage, Some(vec!["id".to_string(), "title".to_string()]), None, None).expect("list failed");
+    assert_eq!(result.value.issues.len(), 2);
+
+    let filter = vec!["assignee=alice".parse().expect("invalid filter")];
+    let result = git_issue::list(&storage, None, Some(filter), None).expect("list failed");
+    assert_eq!(result.value.issues.len(), 1);
+    assert_eq!(result.value.issues[0].id, 1);
+
+    // show renders the markdown from the bare repository
+    let result = git_issue::show_markdown(&storage, 2).expect("show_markdown failed");
+    assert!(result.value.contains("# Issue #2 -- Issue 2"));
+    assert!(git_issue::show_markdown(&storage, 3).is_err());
+
+    // Unknown revisions are rejected
+    assert!(GitStorage::open(&root.join("bare.git"), "does-not-exist").is_err());
+
+    // The file system storage sees the same issues
+    let storage = FsStorage::new(root.join("work").join(".gitissues"));
+    let mut ids = storage.issue_ids().expect("issue_ids failed");
+    ids.sort();
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(storage.attachments(1).expect("attachments failed"), vec!["log.txt"]);
+}
+
+#[test]
+fn test_show_ref() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    // Change settings: Set 'editor' to 'cat' resp. 'cat'-equivalent
+    let editor = if cfg!(windows) { "type" } else { "cat" };
+    let settings_path = ".gitissues/settings.yaml";
+    let mut settings = load_yaml_values(settings_path);
+    settings["editor"] = serde_yaml::Value::String(editor.to_string());
+    save_yaml_values(settings_path, &settings);
+
+    run_command(&["new", "Issue 1"]).expect("new failed");
+    run_git(&["tag", "v1"]);
+    run_command(&["set", "1", "--title", "Renamed issue"]).expect("set failed");
+
+    let output = run_command(&["show", "1"]).expect("show failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("# Issue #1 -- Renamed issue"));
+
+    let output = run_command(&["show", "1", "--ref", "v1"]).expect("show --ref failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("# Issue #1 -- Issue 1"));
+}
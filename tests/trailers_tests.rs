@@ -0,0 +1,64 @@
+use git_issue::trailers::Trailers;
+
+mod common;
+use common::{TestEnv, init_git_repo, load_yaml_values, run_command, run_git, save_yaml_values};
+
+#[test]
+fn test_trailers_render_and_parse() {
+    let trailers = Trailers::new("set", &[1, 2]).with_fields(&["state", "priority", "state"]);
+
+    assert_eq!(
+        trailers.render(),
+        "Issue-Action: set\nIssue-Id: 1\nIssue-Id: 2\nIssue-Fields: state,priority"
+    );
+
+    let message = format!("Custom subject\n\nSome body: text\n\n{}\n", trailers.render());
+    assert_eq!(Trailers::parse(&message), Some(trailers));
+
+    assert_eq!(Trailers::parse("Custom subject\n\nSome body"), None);
+}
+
+#[test]
+fn test_commit_trailers() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    // Custom commit message template without any issue information
+    let mut config = load_yaml_values(".gitissues/config.yaml");
+    config["commit_message"] = serde_yaml::Value::String("Update issues".to_string());
+    save_yaml_values(".gitissues/config.yaml", &config);
+    run_git(&["commit", "--quiet", "-am", "[issue] init"]);
+
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+
+    let trailers = |rev: &str| Trailers::parse(&run_git(&["log", "-1", "--format=%B", rev])).expect("trailers missing");
+
+    let new = trailers("HEAD");
+    assert_eq!(new.action, "new");
+    assert_eq!(new.ids, vec![2]);
+
+    run_command(&["set", "1", "--state", "active", "--priority", "p1"]).expect("set failed");
+
+    let set = trailers("HEAD");
+    assert_eq!(set.action, "set");
+    assert_eq!(set.ids, vec![1]);
+    assert_eq!(set.fields, vec!["state", "priority"]);
+
+    run_command(&["link", "1", "--add", "related=2"]).expect("link failed");
+
+    let link = trailers("HEAD");
+    assert_eq!(link.action, "link");
+    assert_eq!(link.ids, vec![1, 2]);
+    assert_eq!(link.fields, vec!["related"]);
+
+    // Undo identifies issue commits by their trailers, regardless of the template
+    run_command(&["undo"]).expect("undo failed");
+
+    let undo = trailers("HEAD");
+    assert_eq!(undo.action, "undo");
+    assert_eq!(undo.ids, vec![1, 2]);
+    assert_eq!(undo.reverts, vec![run_git(&["rev-parse", "HEAD~1"]).trim().to_string()]);
+}
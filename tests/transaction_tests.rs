@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::PathBuf;
+
+use git_issue::transaction::Transaction;
+
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_git};
+
+#[test]
+fn test_transaction_apply_and_rollback() {
+    let _env = TestEnv::new();
+
+    fs::write("a.txt", "old a").expect("Failed to write a.txt");
+
+    // Successful transaction: all files written, new directories created, no temporary files left
+    let mut transaction = Transaction::new();
+    transaction.write(PathBuf::from("a.txt"), "new a");
+    transaction.write(PathBuf::from("dir/sub/b.txt"), "new b");
+    let applied = transaction.apply().expect("apply failed");
+
+    assert_eq!(fs::read_to_string("a.txt").unwrap(), "new a");
+    assert_eq!(fs::read_to_string("dir/sub/b.txt").unwrap(), "new b");
+    assert!(!PathBuf::from(".a.txt.tmp").exists());
+
+    // Rolling back restores the previous state
+    applied.rollback().expect("rollback failed");
+    assert_eq!(fs::read_to_string("a.txt").unwrap(), "old a");
+    assert!(!PathBuf::from("dir").exists());
+
+    // Failing transaction: 'blocker' is a file, so 'blocker/c.txt' cannot be written
+    fs::write("blocker", "").expect("Failed to write blocker");
+
+    let mut transaction = Transaction::new();
+    transaction.write(PathBuf::from("a.txt"), "newer a");
+    transaction.write(PathBuf::from("new/d.txt"), "new d");
+    transaction.write(PathBuf::from("blocker/c.txt"), "new c");
+    assert!(transaction.apply().is_err());
+
+    assert_eq!(fs::read_to_string("a.txt").unwrap(), "old a");
+    assert!(!PathBuf::from("new").exists());
+    assert!(!PathBuf::from(".a.txt.tmp").exists());
+}
+
+#[test]
+fn test_set_all_or_nothing() {
+    let _env = TestEnv::new();
+
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+
+    // Corrupt the meta.yaml of the last issue
+    fs::write(".gitissues/issues/0000000003/meta.yaml", "id: [").expect("Failed to corrupt meta.yaml");
+
+    run_command(&["set", "1,2,3", "--state", "active"]).expect_err("set should fail on malformatted meta.yaml");
+
+    // Issues processed before the failure are unchanged
+    let meta = load_yaml_values(".gitissues/issues/0000000001/meta.yaml");
+    assert_eq!(meta["state"].as_str().unwrap(), "new");
+    let meta = load_yaml_values(".gitissues/issues/0000000002/meta.yaml");
+    assert_eq!(meta["state"].as_str().unwrap(), "new");
+}
+
+#[test]
+fn test_link_all_or_nothing() {
+    let _env = TestEnv::new();
+
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+
+    // The removal fails (no such link), so the additions must not be written either
+    run_command(&["link", "1", "--add", "related=2", "child=3", "--remove", "parent=2"]).expect_err("link should fail");
+
+    for id in 1..=3 {
+        let meta = load_yaml_values(&format!(".gitissues/issues/000000000{id}/meta.yaml"));
+        let relationships = meta["relationships"].as_mapping().unwrap();
+        assert!(relationships.is_empty(), "issue {id} has relationships: {relationships:?}");
+    }
+}
+
+#[test]
+fn test_commit_only_issue_paths() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+
+    // Unrelated staged changes are not part of issue commits
+    fs::write("unrelated.txt", "staged by the user").expect("Failed to write unrelated.txt");
+    run_git(&["add", "unrelated.txt"]);
+
+    run_command(&["link", "1", "--add", "related=2"]).expect("link failed");
+
+    let files = run_git(&["show", "--name-only", "--format=", "HEAD"]);
+    assert!(files.contains(".gitissues/issues/0000000001/meta.yaml"));
+    assert!(files.contains(".gitissues/issues/0000000002/meta.yaml"));
+    assert!(!files.contains("unrelated.txt"));
+
+    let status = run_git(&["status", "--porcelain"]);
+    assert!(status.contains("A  unrelated.txt"));
+}
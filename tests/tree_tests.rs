@@ -0,0 +1,71 @@
+mod common;
+use common::{TestEnv, run_command, setup_issues};
+
+/// Output lines without padding and header separator
+fn lines(stdout: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .filter(|line| !line.starts_with("---"))
+        .collect()
+}
+
+#[test]
+fn test_tree() {
+    let _env = TestEnv::new();
+    setup_issues(5);
+
+    // 1 -> (2 -> 4), 3; 5 stands alone
+    run_command(&["link", "1", "--add", "child=2,3"]).expect("link 1 failed");
+    run_command(&["link", "4", "--add", "parent=2"]).expect("link 4 failed");
+
+    let output = run_command(&["tree", "--columns", "id,title", "--sort", "id=asc"]).expect("tree failed");
+    let expected = vec![
+        "id  title",
+        "1   Issue 1",
+        "2   └ Issue 2",
+        "4     └ Issue 4",
+        "3   └ Issue 3",
+        "5   Issue 5",
+    ];
+    assert_eq!(lines(&output.stdout), expected);
+
+    // Subtree of an issue
+    let output = run_command(&["tree", "2", "--columns", "id"]).expect("tree of ID failed");
+    assert_eq!(lines(&output.stdout), vec!["id", "2", "└ 4"]);
+
+    // Parent filtered out: child becomes a root
+    let output = run_command(&["list", "--tree", "--columns", "id,title", "--filter", "id=2,4"]).expect("list --tree failed");
+    assert_eq!(lines(&output.stdout), vec!["id  title", "2   Issue 2", "4   └ Issue 4"]);
+
+    run_command(&["tree", "99"]).expect_err("tree of unknown ID should fail");
+}
+
+#[test]
+fn test_list_rollups() {
+    let _env = TestEnv::new();
+    setup_issues(4);
+
+    run_command(&["link", "1", "--add", "child=2,3,4"]).expect("link failed");
+    run_command(&["set", "2", "--state", "closed"]).expect("set state failed");
+    run_command(&["set", "3", "--due-date", "2030-05-01"]).expect("set due_date 3 failed");
+    run_command(&["set", "4", "--due-date", "2030-02-01"]).expect("set due_date 4 failed");
+
+    let output = run_command(&[
+        "list",
+        "--columns",
+        "id,children,children_closed,children_due",
+        "--filter",
+        "id=1,2",
+        "--sort",
+        "id=asc",
+    ])
+    .expect("list failed");
+
+    let expected = vec![
+        "id  children  children_closed  children_due",
+        "1   3         33%              2030-02-01",
+        "2   0         -                -",
+    ];
+    assert_eq!(lines(&output.stdout), expected);
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_command_with_stdin, run_git};
+
+fn state(id: u32) -> String {
+    let meta = load_yaml_values(&format!(".gitissues/issues/{id:010}/meta.yaml"));
+    meta["state"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_undo_commits() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+
+    // Undo a bulk set: single revert commit
+    run_command(&["list"]).expect("list failed");
+    run_command_with_stdin(&["set", "*", "--state", "closed"], "y\n").expect("bulk set failed");
+    assert_eq!(state(1), "closed");
+
+    let output = run_command(&["undo"]).expect("undo failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Undone: [issue] set state of 3 issues: #1,#2,#3"));
+    assert_eq!(state(1), "new");
+    assert_eq!(state(3), "new");
+
+    let message = run_git(&["log", "-1", "--format=%B"]);
+    assert!(message.starts_with("[issue] undo: [issue] set state of 3 issues"));
+    assert!(message.contains("Issue-Action: undo"));
+    assert!(message.contains("Issue-Reverts: "));
+    assert!(run_git(&["status", "--porcelain"]).trim().is_empty());
+
+    // Undo again: the already undone commit is skipped, the last `new` is undone
+    run_command(&["undo"]).expect("undo 2 failed");
+    assert!(!PathBuf::from(".gitissues/issues/0000000003").exists());
+    assert!(PathBuf::from(".gitissues/issues/0000000002").exists());
+
+    // Undo several commits at once
+    run_command(&["set", "1,2", "--state", "active"]).expect("set failed");
+    run_command(&["undo", "-n", "2"]).expect("undo -n 2 failed");
+    assert_eq!(state(1), "new");
+    assert_eq!(state(2), "new");
+}
+
+#[test]
+fn test_undo_commits_rejected() {
+    let _env = TestEnv::new();
+
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+
+    // Nothing to undo after init
+    run_command(&["undo"]).expect_err("undo after init should fail");
+
+    run_command(&["new", "Issue 1"]).expect("new failed");
+    run_command(&["set", "1", "--state", "active"]).expect("set failed");
+
+    // Uncommitted changes of the affected files
+    fs::write(".gitissues/issues/0000000001/meta.yaml", "changed").expect("Failed to write meta.yaml");
+    run_command(&["undo"]).expect_err("undo with uncommitted changes should fail");
+    run_git(&["checkout", "--", "."]);
+
+    // Commit not made by git-issue
+    fs::write(".gitissues/issues/0000000001/description.md", "manual").expect("Failed to write description.md");
+    run_git(&["commit", "--quiet", "-am", "Manual change"]);
+    run_command(&["undo"]).expect_err("undo of a manual commit should fail");
+
+    // git-issue commit which also touched files outside of .gitissues/
+    fs::write("code.rs", "").expect("Failed to write code.rs");
+    run_git(&["add", "code.rs"]);
+    run_git(&["commit", "--quiet", "-m", "[issue] set state #1 -- Issue 1"]);
+    run_command(&["undo"]).expect_err("undo of a commit outside of .gitissues/ should fail");
+}
+
+#[test]
+fn test_undo_journal() {
+    let _env = TestEnv::new();
+
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "Issue 1"]).expect("new 1 failed");
+    run_command(&["new", "Issue 2"]).expect("new 2 failed");
+    run_command(&["set", "1,2", "--state", "closed"]).expect("set failed");
+    run_command(&["link", "1", "--add", "related=2"]).expect("link failed");
+
+    // Undo link and set
+    let output = run_command(&["undo", "-n", "2"]).expect("undo failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Undone: links updated #1,#2"));
+    assert!(stdout.contains("Undone: set state #1,#2"));
+    assert_eq!(state(1), "new");
+    assert_eq!(state(2), "new");
+
+    let meta = load_yaml_values(".gitissues/issues/0000000001/meta.yaml");
+    assert!(meta["relationships"].as_mapping().is_none_or(|m| m.is_empty()));
+
+    // Files changed outside of git-issue are not overwritten
+    fs::write(".gitissues/issues/0000000002/meta.yaml", "changed").expect("Failed to write meta.yaml");
+    run_command(&["undo"]).expect_err("undo of a changed file should fail");
+
+    // Undo new: the issue directory is removed
+    fs::remove_dir_all(".gitissues/.tmp/journal").expect("Failed to clear journal");
+    run_command(&["new", "Issue 3"]).expect("new 3 failed");
+    run_command(&["undo"]).expect("undo new failed");
+    assert!(!PathBuf::from(".gitissues/issues/0000000003").exists());
+
+    run_command(&["undo"]).expect_err("undo with empty journal should fail");
+}
@@ -0,0 +1,174 @@
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_git, save_yaml_values};
+
+const USERS: &str = r#"_version: 2
+users:
+  - id: alice
+    name: Alice Smith
+    emails: [alice@example.com]
+    aliases: [asmith]
+    teams: [backend]
+  - id: bob
+    teams: [backend, frontend]
+  - id: carol
+    name: Carol Jones
+    active: false
+    teams: [frontend]
+"#;
+
+#[test]
+fn test_users_aliases_inactive_teams() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+    std::fs::write(".gitissues/users.yaml", USERS).expect("write users.yaml failed");
+
+    // Aliases and emails are stored as the user ID
+    run_command(&["new", "A", "--assignee", "asmith"]).expect("new with alias failed");
+    run_command(&["new", "B", "--assignee", "bob", "--reporter", "ALICE@example.com"]).expect("new with email failed");
+
+    let meta = load_yaml_values(".gitissues/issues/0000000001/meta.yaml");
+    assert_eq!(meta["assignee"], "alice");
+    let meta = load_yaml_values(".gitissues/issues/0000000002/meta.yaml");
+    assert_eq!(meta["reporter"], "alice");
+
+    // Inactive users can't be assigned ...
+    let result = run_command(&["new", "C", "--assignee", "carol"]);
+    assert!(result.is_err_and(|e| e.contains("inactive")));
+    assert!(run_command(&["set", "1", "--assignee", "carol"]).is_err());
+    assert!(run_command(&["set", "1", "--assignee", "dave"]).is_err());
+
+    // ... but stay valid on existing issues
+    let mut meta = load_yaml_values(".gitissues/issues/0000000002/meta.yaml");
+    meta["assignee"] = serde_yaml::Value::String("carol".to_string());
+    save_yaml_values(".gitissues/issues/0000000002/meta.yaml", &meta);
+
+    run_command(&["set", "2", "--title", "B2"]).expect("set of an issue with an inactive assignee failed");
+
+    // Teams
+    let output = run_command(&["list", "--columns", "id", "--filter", "assignee=@backend", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "1"]);
+
+    let output = run_command(&["list", "--columns", "id", "--filter", "assignee=@frontend", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "2"]);
+
+    let output = run_command(&["list", "--columns", "id", "--filter", "assignee=@nobody", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id"]);
+
+    // IDs, aliases and emails are matched case-insensitively
+    for user in ["Alice", "ASMITH", "Alice@Example.com"] {
+        let filter = format!("assignee={user}");
+        let output = run_command(&["list", "--columns", "id", "--filter", &filter, "--csv"]).expect("list filter failed");
+        assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "1"]);
+    }
+
+    // Display names in the table, IDs in the CSV
+    let output = run_command(&["list", "--columns", "id,assignee"]).expect("list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1   Alice Smith"));
+    assert!(stdout.contains("2   Carol Jones"));
+
+    let output = run_command(&["list", "--columns", "id,assignee", "--csv"]).expect("list csv failed");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(),
+        ["id,assignee", "2,carol", "1,alice"]
+    );
+
+    // Show: 'cat' as editor prints the markdown
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["editor"] = serde_yaml::Value::String(if cfg!(windows) { "type" } else { "cat" }.to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    let output = run_command(&["show", "1"]).expect("show failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("| **assignee**      | Alice Smith (alice)"));
+}
+
+#[test]
+fn test_users_default_user_from_git_email() {
+    let _env = TestEnv::new();
+    init_git_repo(); // user.email: test@example.com
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    let users = USERS.replace("alice@example.com", "test@example.com");
+    std::fs::write(".gitissues/users.yaml", users).expect("write users.yaml failed");
+
+    // settings.yaml:user is empty: reporter and 'me' from the git email
+    run_command(&["new", "A", "--assignee", "me"]).expect("new failed");
+
+    let meta = load_yaml_values(".gitissues/issues/0000000001/meta.yaml");
+    assert_eq!(meta["reporter"], "alice");
+    assert_eq!(meta["assignee"], "alice");
+
+    // settings.yaml:user overrides the git email
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["user"] = serde_yaml::Value::String("bob".to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    run_command(&["new", "B"]).expect("new failed");
+
+    let meta = load_yaml_values(".gitissues/issues/0000000002/meta.yaml");
+    assert_eq!(meta["reporter"], "bob");
+}
+
+#[test]
+fn test_users_whoami_register() {
+    let _env = TestEnv::new();
+    init_git_repo(); // user.name: Test User, user.email: test@example.com
+    let output = run_command(&["init"]).expect("init failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("git issue whoami --register"));
+
+    let output = run_command(&["whoami"]).expect("whoami failed");
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("No user."));
+
+    // Register the git identity: committed to users.yaml
+    run_command(&["whoami", "--register"]).expect("register failed");
+    assert!(run_command(&["whoami", "--register"]).is_err_and(|e| e.contains("Already registered")));
+    assert!(run_git(&["log", "-1", "--format=%s"]).contains("register user -- test"));
+
+    let users = load_yaml_values(".gitissues/users.yaml");
+    let user = &users["users"][3];
+    assert_eq!(user["id"], "test");
+    assert_eq!(user["name"], "Test User");
+    assert_eq!(user["emails"][0], "test@example.com");
+
+    let output = run_command(&["whoami"]).expect("whoami failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("test (from git user.email"));
+
+    run_command(&["new", "A"]).expect("new failed");
+    let meta = load_yaml_values(".gitissues/issues/0000000001/meta.yaml");
+    assert_eq!(meta["reporter"], "test");
+
+    // Fallback to git user.name
+    run_git(&["config", "user.email", "other@example.com"]);
+    let output = run_command(&["whoami"]).expect("whoami failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("test (from git user.name"));
+
+    // settings.yaml:user overrides the git identity
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["user"] = serde_yaml::Value::String("bob".to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    let output = run_command(&["whoami"]).expect("whoami failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("bob (from settings.yaml:user)"));
+}
+
+#[test]
+fn test_users_git_identity_not_cached() {
+    let _env = TestEnv::new();
+    init_git_repo(); // user.email: test@example.com
+    run_command(&["init", "--no-commit"]).expect("init failed");
+
+    let users = USERS
+        .replace("alice@example.com", "test@example.com")
+        .replace("  - id: bob\n", "  - id: bob\n    emails: [bob@example.com]\n");
+    std::fs::write(".gitissues/users.yaml", users).expect("write users.yaml failed");
+
+    // Long-running processes (web server, library users) see changes of the git identity
+    let (settings, _) = git_issue::model::load_settings().expect("load_settings failed");
+    assert_eq!(settings.user, "alice");
+
+    run_git(&["config", "user.email", "bob@example.com"]);
+    let (settings, _) = git_issue::model::load_settings().expect("load_settings failed");
+    assert_eq!(settings.user, "bob");
+}
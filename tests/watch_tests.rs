@@ -0,0 +1,120 @@
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_git, set_user};
+
+const USERS: &str = r#"_version: 2
+users:
+  - id: alice
+    emails: [alice@example.com]
+  - id: bob
+    emails: [bob@example.com]
+  - id: carol
+"#;
+
+/// Commit the next changes as another git author
+fn set_git_author(email: &str) {
+    run_git(&["config", "user.email", email]);
+}
+
+#[test]
+fn test_watchers() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+    std::fs::write(".gitissues/users.yaml", USERS).expect("write users.yaml failed");
+    set_user("alice");
+
+    // Reporter and assignee watch automatically
+    run_command(&["new", "A", "--assignee", "bob"]).expect("new failed");
+    run_command(&["new", "B"]).expect("new failed");
+
+    let meta = load_yaml_values(".gitissues/issues/0000000001/meta.yaml");
+    assert_eq!(
+        meta["watchers"],
+        serde_yaml::from_str::<serde_yaml::Value>("[alice, bob]").expect("yaml")
+    );
+
+    run_command(&["set", "2", "--assignee", "carol"]).expect("set failed");
+    let meta = load_yaml_values(".gitissues/issues/0000000002/meta.yaml");
+    assert_eq!(
+        meta["watchers"],
+        serde_yaml::from_str::<serde_yaml::Value>("[alice, carol]").expect("yaml")
+    );
+
+    // Watch and unwatch
+    let output = run_command(&["watch", "1", "--user", "carol"]).expect("watch failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Watching issue #1"));
+
+    let output = run_command(&["watch", "1"]).expect("watch failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Already watching issue #1"));
+
+    run_command(&["unwatch", "2"]).expect("unwatch failed");
+
+    assert!(run_command(&["watch", "1", "--user", "dave"]).is_err());
+    assert!(run_command(&["watch", "9"]).is_err());
+
+    let output = run_command(&["list", "--columns", "id,watchers", "--csv"]).expect("list failed");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(),
+        ["id,watchers", "2,carol", "1,\"alice,bob,carol\""]
+    );
+
+    let output = run_command(&["list", "--columns", "id", "--filter", "watchers=me", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "1"]);
+}
+
+#[test]
+fn test_inbox() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    std::fs::write(".gitissues/users.yaml", USERS).expect("write users.yaml failed");
+    run_git(&["commit", "-qam", "users"]);
+
+    // alice (by the git email) creates two issues
+    set_git_author("alice@example.com");
+    run_command(&["new", "A"]).expect("new failed");
+    run_command(&["new", "B", "--assignee", "bob"]).expect("new failed");
+
+    // Own changes are not in the inbox
+    let output = run_command(&["inbox"]).expect("inbox failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Inbox of alice: all changes"));
+    assert!(stdout.contains("Nothing new."));
+
+    // bob changes issue 1 and watches issue 2 (no change of the issue)
+    set_git_author("bob@example.com");
+    run_command(&["set", "1", "--title", "A2"]).expect("set failed");
+    run_command(&["watch", "2"]).expect("watch failed");
+
+    set_git_author("alice@example.com");
+
+    let output = run_command(&["inbox", "--keep"]).expect("inbox failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Inbox of alice: changes since"));
+    assert!(stdout.contains("1   new    A2     1        "));
+    assert!(stdout.contains("bob"));
+    assert!(!stdout.contains("2   new"));
+
+    // Marked as seen
+    run_command(&["inbox"]).expect("inbox failed");
+    let output = run_command(&["inbox"]).expect("inbox failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing new."));
+
+    // Unwatched issues are not in the inbox
+    set_git_author("bob@example.com");
+    run_command(&["set", "1", "--priority", "P1"]).expect("set failed");
+    run_command(&["set", "2", "--priority", "P1"]).expect("set failed");
+
+    set_git_author("alice@example.com");
+    run_command(&["unwatch", "1"]).expect("unwatch failed");
+
+    let output = run_command(&["inbox"]).expect("inbox failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2   new    B"));
+    assert!(!stdout.contains("1   new"));
+
+    // Any revision as start
+    let output = run_command(&["inbox", "--since", "HEAD~3"]).expect("inbox since failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2   new    B"));
+    assert!(run_command(&["inbox", "--since", "unknown"]).is_err());
+}
@@ -0,0 +1,124 @@
+mod common;
+use common::{TestEnv, disable_auto_commit, init_git_repo, load_yaml_values, run_command, run_git, save_yaml_values, set_user};
+
+#[test]
+fn test_estimate_log_time_list() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "A", "--estimate", "4h"]).expect("new 1 failed");
+    run_command(&["new", "B", "--estimate", "90m"]).expect("new 2 failed");
+    run_command(&["new", "C"]).expect("new 3 failed");
+
+    // Estimates are stored in canonical form
+    let meta = load_yaml_values(".gitissues/issues/0000000002/meta.yaml");
+    assert_eq!(meta["estimate"], "1h30m");
+
+    assert!(run_command(&["new", "D", "--estimate", "2 hours"]).is_err());
+    assert!(run_command(&["set", "1", "--estimate", "h"]).is_err());
+
+    // No author without settings.yaml:user
+    assert!(run_command(&["log-time", "1", "1h"]).is_err());
+
+    set_user("alice");
+
+    run_command(&["log-time", "1", "2h30m", "--note", "Prototype"]).expect("log-time failed");
+    run_command(&["log-time", "1", "1h", "--date", "2000-01-01"]).expect("log-time failed");
+    run_command(&["log-time", "2", "2h"]).expect("log-time failed");
+
+    assert!(run_command(&["log-time", "1", "0m"]).is_err());
+    assert!(run_command(&["log-time", "1", "1h", "--date", "2000-13-01"]).is_err());
+    assert!(run_command(&["log-time", "9", "1h"]).is_err());
+
+    // One file per entry
+    let entries = std::fs::read_dir(".gitissues/issues/0000000001/worklog")
+        .expect("worklog missing")
+        .count();
+    assert_eq!(entries, 2);
+
+    let output = run_command(&[
+        "list",
+        "--columns",
+        "id,estimate,spent,remaining",
+        "--sort",
+        "remaining=asc",
+        "--csv",
+    ])
+    .expect("list failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["id,estimate,spent,remaining", "3,-,-,-", "2,1h30m,2h,0m", "1,4h,3h30m,30m"]);
+
+    let output = run_command(&["list", "--columns", "id", "--filter", "remaining=0m", "--csv"]).expect("list filter failed");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().collect::<Vec<_>>(), ["id", "2"]);
+
+    // Show: 'cat' as editor prints the markdown
+    let mut settings = load_yaml_values(".gitissues/settings.yaml");
+    settings["editor"] = serde_yaml::Value::String(if cfg!(windows) { "type" } else { "cat" }.to_string());
+    save_yaml_values(".gitissues/settings.yaml", &settings);
+
+    let output = run_command(&["show", "1"]).expect("show failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| **estimate**      | 4h"));
+    assert!(stdout.contains("## Work Log"));
+    assert!(stdout.contains("| 2000-01-01 | alice      | 1h        | -         |"));
+    assert!(stdout.contains("**spent**: 3h30m of 4h (remaining: 30m)"));
+}
+
+#[test]
+fn test_timesheet() {
+    let _env = TestEnv::new();
+    run_command(&["init", "--no-commit"]).expect("init failed");
+    disable_auto_commit();
+
+    run_command(&["new", "A"]).expect("new 1 failed");
+    run_command(&["new", "B"]).expect("new 2 failed");
+
+    set_user("alice");
+    run_command(&["log-time", "1", "1h", "--date", "2000-01-03"]).expect("log-time failed");
+    run_command(&["log-time", "1", "30m", "--date", "2000-01-03"]).expect("log-time failed");
+    run_command(&["log-time", "2", "2h", "--date", "2000-01-05"]).expect("log-time failed");
+    run_command(&["log-time", "2", "8h", "--date", "2000-01-10"]).expect("log-time failed"); // next week
+
+    set_user("bob");
+    run_command(&["log-time", "1", "4h", "--date", "2000-01-04"]).expect("log-time failed");
+
+    let output = run_command(&["timesheet", "--user", "alice", "--from", "2000-01-03", "--to", "2000-01-09"]).expect("timesheet failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Timesheet of alice: 2000-01-03 to 2000-01-09"));
+    assert!(stdout.contains("1   A      1h30m      -          -          -          -          -          -          1h30m"));
+    assert!(stdout.contains("2   B      -          -          2h         -          -          -          -          2h"));
+    assert!(stdout.contains("    total  1h30m      -          2h         -          -          -          -          3h30m"));
+
+    // 'me' is settings.yaml:user, the week ends on Sunday of --to
+    let output = run_command(&["timesheet", "--to", "2000-01-09"]).expect("timesheet me failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Timesheet of bob: 2000-01-03 to 2000-01-09"));
+    assert!(stdout.contains("1   A      -          4h"));
+    assert!(!stdout.contains("B  "));
+
+    let output = run_command(&["timesheet", "--week"]).expect("timesheet week failed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No time logged."));
+
+    assert!(run_command(&["timesheet", "--week", "--from", "2000-01-03"]).is_err());
+    assert!(run_command(&["timesheet", "--from", "2000-01-09", "--to", "2000-01-03"]).is_err());
+}
+
+#[test]
+fn test_log_time_commit() {
+    let _env = TestEnv::new();
+    init_git_repo();
+    run_command(&["init"]).expect("init failed");
+    set_user("alice");
+
+    run_command(&["new", "A"]).expect("new failed");
+    run_command(&["log-time", "1", "2h"]).expect("log-time failed");
+
+    let log = run_git(&["log", "-1", "--format=%s%n%b"]);
+    assert!(log.contains("log-time 2h"));
+    assert!(log.contains("Issue-Action: log-time"));
+
+    run_command(&["undo"]).expect("undo failed");
+    assert!(!std::path::Path::new(".gitissues/issues/0000000001/worklog").exists());
+}